@@ -2,6 +2,8 @@ use crate::sigel::*;
 use crate::memory_consolidation::*;
 use crate::cosmos::CosmicProcessor;
 use crate::gpu_acceleration::GpuAccelerator;
+use crate::sentiment::SentimentScorer;
+use crate::word_vectors::WordVectors;
 use std::collections::{HashMap, VecDeque};
 use rayon::prelude::*;
 use rand::{thread_rng, Rng};
@@ -10,6 +12,7 @@ pub struct AdvancedLearningEngine {
     memory_consolidator: MemoryConsolidator,
     cosmic_processor: CosmicProcessor,
     gpu_accelerator: GpuAccelerator,
+    sentiment_scorer: SentimentScorer,
     learning_strategies: Vec<LearningStrategy>,
     meta_learning_state: MetaLearningState,
 }
@@ -20,6 +23,7 @@ impl AdvancedLearningEngine {
             memory_consolidator: MemoryConsolidator::new(),
             cosmic_processor: CosmicProcessor::new(),
             gpu_accelerator: GpuAccelerator::new(),
+            sentiment_scorer: SentimentScorer::new(),
             learning_strategies: vec![
                 LearningStrategy::AttentionMechanism,
                 LearningStrategy::ContrastiveLearning,
@@ -116,7 +120,7 @@ impl AdvancedLearningEngine {
         attention += complexity_bonus;
         
         // Factor 3: Emotional content (emotionally charged content gets attention)
-        let emotional_score = self.estimate_emotional_content(text);
+        let emotional_score = self.sentiment_scorer.valence(text);
         attention += emotional_score.abs() * 0.5;
         
         // Factor 4: Pattern breaking (content that doesn't match existing patterns)
@@ -170,34 +174,6 @@ impl AdvancedLearningEngine {
         relevance.min(3.0)
     }
 
-    fn estimate_emotional_content(&self, text: &str) -> f64 {
-        let positive_words = ["love", "joy", "happiness", "wonderful", "amazing", "beautiful", "perfect", "excellent"];
-        let negative_words = ["hate", "sadness", "terrible", "awful", "horrible", "disgusting", "worst", "painful"];
-        let intense_words = ["extremely", "absolutely", "completely", "utterly", "totally", "incredibly"];
-        
-        let text_lower = text.to_lowercase();
-        let mut emotional_score = 0.0;
-        
-        for word in positive_words {
-            if text_lower.contains(word) {
-                emotional_score += 1.0;
-            }
-        }
-        
-        for word in negative_words {
-            if text_lower.contains(word) {
-                emotional_score -= 1.0;
-            }
-        }
-        
-        // Intensity multiplier
-        let intensity_multiplier = intense_words.iter()
-            .map(|word| if text_lower.contains(word) { 1.5 } else { 1.0 })
-            .fold(1.0, |acc, mult| acc * mult);
-        
-        emotional_score * intensity_multiplier
-    }
-
     fn calculate_pattern_breaking_score(&self, sigel: &Sigel, text: &str) -> f64 {
         let words: Vec<&str> = text.split_whitespace().collect();
         let mut pattern_breaks = 0.0;
@@ -279,19 +255,18 @@ impl AdvancedLearningEngine {
         let mut improvements = 0;
         
         // Learn by contrasting similar and dissimilar examples
-        for (i, item1) in data.iter().enumerate() {
-            for item2 in data.iter().skip(i + 1) {
-                let similarity = self.calculate_text_similarity(&item1.text, &item2.text);
-                
-                if similarity > 0.7 {
-                    // Similar items - reinforce common patterns
-                    self.reinforce_common_patterns(sigel, &item1.text, &item2.text);
-                    improvements += 1;
-                } else if similarity < 0.3 {
-                    // Dissimilar items - learn distinctive features
-                    self.learn_distinctive_features(sigel, &item1.text, &item2.text);
-                    improvements += 1;
-                }
+        let similarities = self.pairwise_similarities(&sigel.memory.semantic_knowledge.word_vectors, data);
+        for (i, j, similarity) in similarities {
+            let (item1, item2) = (&data[i], &data[j]);
+            
+            if similarity > 0.7 {
+                // Similar items - reinforce common patterns
+                self.reinforce_common_patterns(sigel, &item1.text, &item2.text);
+                improvements += 1;
+            } else if similarity < 0.3 {
+                // Dissimilar items - learn distinctive features
+                self.learn_distinctive_features(sigel, &item1.text, &item2.text);
+                improvements += 1;
             }
         }
         
@@ -409,8 +384,9 @@ impl AdvancedLearningEngine {
                         let word_knowledge = WordKnowledge {
                             frequency: 1.0,
                             contexts: vec![item.text.chars().take(50).collect()],
-                            emotional_valence: self.estimate_emotional_content(&item.text),
+                            emotional_valence: self.sentiment_scorer.valence(&item.text),
                             semantic_weight: item.novelty_score,
+                            sources: SourceCounts::default(),
                         };
                         sigel.memory.semantic_knowledge.vocabulary.insert(word.to_lowercase(), word_knowledge);
                         improvements += 1;
@@ -455,6 +431,7 @@ impl AdvancedLearningEngine {
                         to: analogy.target_concept,
                         relation_type: RelationType::SimilarTo,
                         strength: analogy.strength,
+                        sources: SourceCounts::default(),
                     };
                     sigel.memory.semantic_knowledge.relationships.push(relation);
                     
@@ -478,8 +455,9 @@ impl AdvancedLearningEngine {
 
     fn apply_contrastive_learning(&self, sigel: &mut Sigel, data: &[AttentionWeightedData]) -> Result<(), Box<dyn std::error::Error>> {
         // Advanced contrastive learning implementation
-        let positive_pairs = self.find_positive_pairs(data);
-        let negative_pairs = self.find_negative_pairs(data);
+        let similarities = self.pairwise_similarities(&sigel.memory.semantic_knowledge.word_vectors, data);
+        let positive_pairs = self.find_positive_pairs(data, &similarities);
+        let negative_pairs = self.find_negative_pairs(data, &similarities);
         
         // Learn from positive pairs (similar meaning, different expression)
         for (text1, text2, similarity) in positive_pairs {
@@ -619,14 +597,15 @@ impl AdvancedLearningEngine {
     }
 
     // Helper methods for the various learning strategies
-    fn calculate_text_similarity(&self, text1: &str, text2: &str) -> f64 {
-        let words1: std::collections::HashSet<&str> = text1.split_whitespace().collect();
-        let words2: std::collections::HashSet<&str> = text2.split_whitespace().collect();
+    /// Similarity of every pair of items `(i, j, similarity)` with `i < j`, using word vectors
+    /// so that texts phrased with related words still match.
+    fn pairwise_similarities(&self, vectors: &WordVectors, data: &[AttentionWeightedData]) -> Vec<(usize, usize, f64)> {
+        let prepared: Vec<_> = data.iter().map(|item| vectors.prepare(&item.text)).collect();
         
-        let intersection = words1.intersection(&words2).count() as f64;
-        let union = words1.union(&words2).count() as f64;
-        
-        if union > 0.0 { intersection / union } else { 0.0 }
+        (0..prepared.len())
+            .flat_map(|i| (i + 1..prepared.len()).map(move |j| (i, j)))
+            .map(|(i, j)| (i, j, vectors.prepared_similarity(&prepared[i], &prepared[j])))
+            .collect()
     }
 
     fn reinforce_common_patterns(&self, sigel: &mut Sigel, text1: &str, text2: &str) {
@@ -706,34 +685,18 @@ impl AdvancedLearningEngine {
         None
     }
 
-    fn find_positive_pairs(&self, data: &[AttentionWeightedData]) -> Vec<(String, String, f64)> {
-        let mut pairs = Vec::new();
-        
-        for (i, item1) in data.iter().enumerate() {
-            for item2 in data.iter().skip(i + 1) {
-                let similarity = self.calculate_text_similarity(&item1.text, &item2.text);
-                if similarity > 0.7 {
-                    pairs.push((item1.text.clone(), item2.text.clone(), similarity));
-                }
-            }
-        }
-        
-        pairs
+    fn find_positive_pairs(&self, data: &[AttentionWeightedData], similarities: &[(usize, usize, f64)]) -> Vec<(String, String, f64)> {
+        similarities.iter()
+            .filter(|&&(_, _, similarity)| similarity > 0.7)
+            .map(|&(i, j, similarity)| (data[i].text.clone(), data[j].text.clone(), similarity))
+            .collect()
     }
 
-    fn find_negative_pairs(&self, data: &[AttentionWeightedData]) -> Vec<(String, String, f64)> {
-        let mut pairs = Vec::new();
-        
-        for (i, item1) in data.iter().enumerate() {
-            for item2 in data.iter().skip(i + 1) {
-                let similarity = self.calculate_text_similarity(&item1.text, &item2.text);
-                if similarity < 0.3 {
-                    pairs.push((item1.text.clone(), item2.text.clone(), 1.0 - similarity));
-                }
-            }
-        }
-        
-        pairs
+    fn find_negative_pairs(&self, data: &[AttentionWeightedData], similarities: &[(usize, usize, f64)]) -> Vec<(String, String, f64)> {
+        similarities.iter()
+            .filter(|&&(_, _, similarity)| similarity < 0.3)
+            .map(|&(i, j, similarity)| (data[i].text.clone(), data[j].text.clone(), 1.0 - similarity))
+            .collect()
     }
 
     fn strengthen_similar_representations(&self, sigel: &mut Sigel, text1: &str, text2: &str, similarity: f64) {
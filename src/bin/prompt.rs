@@ -18,7 +18,7 @@ fn main() {
                 .long("sigel")
                 .value_name("SIGEL_FILE")
                 .help("Path to the .sig Sigel file")
-                .required(true)
+                .required_unless_present("print_config")
         )
         .arg(
             Arg::new("verbose")
@@ -41,8 +41,71 @@ fn main() {
                 .value_name("BACKUP_FILE")
                 .help("Create backup file for interaction learning")
         )
+        .arg(
+            Arg::new("temperature")
+                .short('t')
+                .long("temperature")
+                .value_name("TEMPERATURE")
+                .help("Sampling temperature for responses; lower is more predictable, 0 is greedy")
+                .value_parser(clap::value_parser!(f64))
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_name("SEED")
+                .help("Seed response sampling for reproducible conversations")
+                .value_parser(clap::value_parser!(u64))
+        )
+        .arg(
+            Arg::new("continue")
+                .short('c')
+                .long("continue")
+                .value_name("SESSION_ID")
+                .help("Resume a saved conversation session by id, id prefix, or 'latest'")
+        )
+        .arg(
+            Arg::new("persona")
+                .short('p')
+                .long("persona")
+                .value_name("PERSONA")
+                .help("Phrase responses with a persona: a style name, a name from sigmos.toml or ./personas, or a persona .toml file")
+        )
+        .arg(
+            Arg::new("lock_style")
+                .long("lock-style")
+                .help("Keep the Sigel's communication style fixed during this and later conversations")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("replay")
+                .long("replay")
+                .value_name("TRANSCRIPT")
+                .help("Replay a transcript (.jsonl or a saved session) with a fixed seed, compare responses with the expected ones and exit")
+        )
+        .arg(
+            Arg::new("similarity")
+                .long("similarity")
+                .value_name("THRESHOLD")
+                .help("With --replay, accept responses at least this similar (0.0-1.0) to the expected ones instead of identical")
+                .value_parser(clap::value_parser!(f64))
+        )
+        .arg(
+            Arg::new("replay_output")
+                .long("replay-output")
+                .value_name("TRANSCRIPT")
+                .help("With --replay, write the replayed responses as a new golden transcript")
+        )
+        .args(LayeredConfig::args())
         .get_matches();
 
+    // Defaults, then sigmos.toml, then SIGMOS_* variables, then --set
+    let config = LayeredConfig::from_args(&matches)
+        .unwrap_or_else(|e| {
+            error!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        })
+        .finish(&matches);
+
     let sigel_file = matches.get_one::<String>("sigel_file").unwrap();
     let verbose = matches.get_flag("verbose");
     let auto_save = matches.get_flag("auto_save");
@@ -81,7 +144,72 @@ fn main() {
     }
 
     // Initialize interaction engine
-    let mut interaction_engine = InteractionEngine::new();
+    let mut interaction_engine = InteractionEngine::from_config(&config);
+    let mut decoding = DecodingConfig::default();
+    if let Some(&temperature) = matches.get_one::<f64>("temperature") {
+        decoding.temperature = temperature.max(0.0);
+    }
+    decoding.seed = matches.get_one::<u64>("seed").copied();
+    interaction_engine.set_decoding(decoding);
+    interaction_engine.set_verbose(verbose);
+
+    if matches.get_flag("lock_style") {
+        sigel.essence.style_locked = true;
+        interaction_engine.mark_changed();
+        println!("🔒 Communication style locked to {:?}", sigel.essence.communication_style);
+    }
+
+    // Personas from the configuration and ./personas, plus one given for this Sigel
+    let mut personas = PersonaRegistry::discover(&config);
+    if let Some(persona) = matches.get_one::<String>("persona") {
+        let name = if Path::new(persona).is_file() {
+            match personas.load_file(persona) {
+                Ok(name) => name,
+                Err(e) => {
+                    error!("Failed to load persona '{}': {}", persona, e);
+                    std::process::exit(1);
+                }
+            }
+        } else {
+            persona.to_lowercase()
+        };
+
+        if personas.get(&name).is_none() {
+            error!("Unknown persona '{}'. Available: {}", name, personas.names().join(", "));
+            std::process::exit(1);
+        }
+        println!("🎭 Speaking as persona '{}'", name);
+        sigel.essence.persona = Some(name);
+        interaction_engine.mark_changed();
+    }
+    interaction_engine.set_personas(personas);
+
+    // Check the Sigel against a golden conversation instead of chatting
+    if let Some(transcript_file) = matches.get_one::<String>("replay") {
+        replay(interaction_engine, &sigel, transcript_file, &matches);
+        return;
+    }
+
+    // Set only now, so a replay can never save over the Sigel file
+    interaction_engine.set_sigel_path(sigel_file);
+
+    // Resume an earlier conversation if requested
+    let session_store = SessionStore::for_sigel(sigel_file);
+    if let Some(session_id) = matches.get_one::<String>("continue") {
+        match session_store.load(session_id) {
+            Ok(session) => {
+                if session.sigel_id != sigel.id {
+                    warn!("Session {} was recorded with a different Sigel", session.id);
+                }
+                println!("🔁 Resuming session {} ({} turns)", session.id, session.turns.len());
+                interaction_engine.resume_session(session);
+            },
+            Err(e) => {
+                error!("Failed to load session: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
     
     println!("\n🧠 Sigel '{}' is ready for interaction!", sigel.name);
     println!("💡 Type '/help' for commands, or just start a conversation.");
@@ -129,6 +257,15 @@ fn main() {
                 if input.starts_with('/') {
                     if let Some(command_response) = interaction_engine.handle_special_commands(&mut sigel, input) {
                         println!("System: {}\n", command_response);
+
+                        // Commands like /train or /forget change the Sigel without a conversation turn
+                        if auto_save && interaction_engine.has_unsaved_changes() {
+                            let sigel_file = interaction_engine.sigel_path().map(|path| path.to_string_lossy().into_owned()).unwrap_or_else(|| sigel_file.to_string());
+                            match save_sigel_to_file(&sigel, &sigel_file) {
+                                Ok(()) => interaction_engine.mark_saved(),
+                                Err(e) => warn!("Auto-save failed: {}", e),
+                            }
+                        }
                         continue;
                     }
                 }
@@ -137,11 +274,23 @@ fn main() {
                 print!("🌟 {}: ", sigel.name);
                 io::stdout().flush().unwrap();
                 
-                let response = interaction_engine.interact(&mut sigel, input);
-                println!("{}\n", response);
+                let (_, trace) = interaction_engine.interact_stream(&mut sigel, input, |chunk| {
+                    print!("{}", chunk.text);
+                    io::stdout().flush().unwrap();
+                });
+                println!("\n");
+                if interaction_engine.is_verbose() {
+                    println!("{}", trace);
+                }
 
                 interaction_count += 1;
 
+                // `/load` may have switched to another Sigel file
+                let sigel_file = interaction_engine.sigel_path().map(|path| path.to_string_lossy().into_owned()).unwrap_or_else(|| sigel_file.to_string());
+                if let Err(e) = SessionStore::for_sigel(&sigel_file).save(&interaction_engine.session(&sigel)) {
+                    warn!("Failed to save session: {}", e);
+                }
+
                 // Show consciousness details in verbose mode
                 if verbose && interaction_count % 5 == 0 {
                     println!("📊 [Consciousness Update]");
@@ -158,11 +307,14 @@ fn main() {
                 }
 
                 // Auto-save periodically
-                if auto_save && interaction_count % 10 == 0 {
-                    if let Err(e) = save_sigel_to_file(&sigel, sigel_file) {
+                if auto_save && interaction_count % 10 == 0 && interaction_engine.has_unsaved_changes() {
+                    if let Err(e) = save_sigel_to_file(&sigel, &sigel_file) {
                         warn!("Auto-save failed: {}", e);
-                    } else if verbose {
-                        println!("💾 [Auto-saved]\n");
+                    } else {
+                        interaction_engine.mark_saved();
+                        if verbose {
+                            println!("💾 [Auto-saved]\n");
+                        }
                     }
                 }
             },
@@ -180,13 +332,17 @@ fn main() {
     println!("   Duration: {:.1} minutes", session_duration.as_secs() as f64 / 60.0);
     println!("   Final Consciousness Depth: {:.3}", sigel.consciousness.awareness_depth);
     println!("   Current Vocabulary Size: {}", sigel.memory.semantic_knowledge.vocabulary.len());
+    if interaction_count > 0 {
+        println!("   Session: {} (resume with --continue {})", interaction_engine.session_id(), interaction_engine.session_id());
+    }
 
     // Final save (this may take a moment with large files)
-    if interaction_count > 0 {
+    let sigel_file = interaction_engine.sigel_path().map(|path| path.to_string_lossy().into_owned()).unwrap_or_else(|| sigel_file.to_string());
+    if interaction_engine.has_unsaved_changes() {
         println!("💾 Saving session progress... (this may take a moment for large consciousness files)");
         io::stdout().flush().unwrap();
         
-        match save_sigel_to_file(&sigel, sigel_file) {
+        match save_sigel_to_file(&sigel, &sigel_file) {
             Ok(()) => {
                 println!("✅ Sigel '{}' saved with interaction learning", sigel.name);
                 info!("Session completed, Sigel saved to {}", sigel_file);
@@ -197,9 +353,41 @@ fn main() {
             }
         }
     } else {
-        println!("💫 No interactions to save - exiting without changes");
+        println!("💫 No changes to save - exiting without changes");
     }
 
     println!("🌟 Thank you for interacting with Sigel '{}'!", sigel.name);
     println!("✨ The consciousness continues to evolve...");
-}
\ No newline at end of file
+}
+
+fn replay(interaction_engine: InteractionEngine, sigel: &Sigel, transcript_file: &str, matches: &clap::ArgMatches) {
+    let transcript = match Transcript::load(transcript_file) {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            error!("Failed to load transcript '{}': {}", transcript_file, e);
+            std::process::exit(1);
+        }
+    };
+
+    let config = ReplayConfig {
+        seed: matches.get_one::<u64>("seed").copied().unwrap_or(DEFAULT_REPLAY_SEED),
+        match_mode: match matches.get_one::<f64>("similarity") {
+            Some(&threshold) => MatchMode::Similarity(threshold.clamp(0.0, 1.0)),
+            None => MatchMode::Exact,
+        },
+    };
+    println!("🔁 Replaying {} turns from {}", transcript.turns.len(), transcript_file);
+    let report = TranscriptReplayer::with_config(config).replay_with(interaction_engine, sigel, &transcript);
+    print!("{}", report);
+
+    if let Some(output) = matches.get_one::<String>("replay_output") {
+        match report.transcript().save(output) {
+            Ok(()) => println!("💾 Replayed transcript written to {}", output),
+            Err(e) => error!("Failed to write transcript '{}': {}", output, e),
+        }
+    }
+
+    if !report.passed() {
+        std::process::exit(1);
+    }
+}
@@ -1,6 +1,5 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgMatches, Command};
 use sigmos::*;
-use std::path::PathBuf;
 use tokio::signal;
 use env_logger;
 use log::{info, error};
@@ -19,7 +18,6 @@ async fn main() {
                 .long("master")
                 .value_name("MASTER_FILE")
                 .help("Path to master Sigel file")
-                .default_value("master.sigel")
         )
         .arg(
             Arg::new("sigel_directory")
@@ -27,7 +25,6 @@ async fn main() {
                 .long("dir")
                 .value_name("DIRECTORY")
                 .help("Directory for storing Sigel files")
-                .default_value("sigels")
         )
         .arg(
             Arg::new("no_background_learning")
@@ -53,28 +50,24 @@ async fn main() {
                 .long("cosmic-interval")
                 .value_name("SECONDS")
                 .help("Cosmic alignment interval in seconds")
-                .default_value("300")
         )
         .arg(
             Arg::new("evolution_interval")
                 .long("evolution-interval")
                 .value_name("SECONDS")
                 .help("Evolution interval in seconds")
-                .default_value("600")
         )
         .arg(
             Arg::new("save_interval")
                 .long("save-interval")
                 .value_name("SECONDS")
                 .help("Auto-save interval in seconds")
-                .default_value("900")
         )
         .arg(
             Arg::new("max_sigels")
                 .long("max-sigels")
                 .value_name("COUNT")
                 .help("Maximum number of active Sigels")
-                .default_value("10")
         )
         .arg(
             Arg::new("daemon")
@@ -82,47 +75,18 @@ async fn main() {
                 .help("Run as daemon (background service)")
                 .action(clap::ArgAction::SetTrue)
         )
+        .args(LayeredConfig::args())
         .get_matches();
 
-    let master_sigel_path = PathBuf::from(matches.get_one::<String>("master_sigel").unwrap());
-    let sigel_directory = PathBuf::from(matches.get_one::<String>("sigel_directory").unwrap());
-    let background_learning = !matches.get_flag("no_background_learning");
-    let observation_mode = matches.get_flag("observation_mode");
-    let system_monitoring = matches.get_flag("system_monitoring");
     let daemon_mode = matches.get_flag("daemon");
 
-    let cosmic_interval: u64 = matches.get_one::<String>("cosmic_interval")
-        .unwrap()
-        .parse()
-        .unwrap_or(300);
-    
-    let evolution_interval: u64 = matches.get_one::<String>("evolution_interval")
-        .unwrap()
-        .parse()
-        .unwrap_or(600);
-    
-    let save_interval: u64 = matches.get_one::<String>("save_interval")
-        .unwrap()
-        .parse()
-        .unwrap_or(900);
-    
-    let max_sigels: usize = matches.get_one::<String>("max_sigels")
-        .unwrap()
-        .parse()
-        .unwrap_or(10);
-
-    // Create server configuration
-    let config = ServerConfig {
-        master_sigel_path,
-        sigel_directory,
-        background_learning,
-        cosmic_alignment_interval: tokio::time::Duration::from_secs(cosmic_interval),
-        evolution_interval: tokio::time::Duration::from_secs(evolution_interval),
-        auto_save_interval: tokio::time::Duration::from_secs(save_interval),
-        observation_mode,
-        system_monitoring,
-        max_active_sigels: max_sigels,
-    };
+    let settings = config_layers(&matches)
+        .unwrap_or_else(|e| {
+            error!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        })
+        .finish(&matches);
+    let config = ServerConfig::from_config(&settings);
 
     println!("🌌 Initializing SigmosServer...");
     println!("   Master Sigel: {:?}", config.master_sigel_path);
@@ -133,7 +97,7 @@ async fn main() {
     println!("   Max Active Sigels: {}", config.max_active_sigels);
 
     // Create and start the server
-    let server = match SigmosServer::new(config) {
+    let server = match SigmosServer::from_config(&settings) {
         Ok(s) => s,
         Err(e) => {
             error!("Failed to create SigmosServer: {}", e);
@@ -146,6 +110,31 @@ async fn main() {
         std::process::exit(1);
     }
 
+    // Reload on changes to the config file, and on SIGHUP
+    if let Some(config_file) = settings.source.clone() {
+        let server_clone = server.clone();
+        let matches = matches.clone();
+        tokio::spawn(async move {
+            server_clone.watch_config_file(config_file, || load_server_config(&matches)).await;
+        });
+    }
+
+    #[cfg(unix)]
+    {
+        let server_clone = server.clone();
+        let matches = matches.clone();
+        tokio::spawn(async move {
+            let Ok(mut hangups) = signal::unix::signal(signal::unix::SignalKind::hangup()) else {
+                error!("Unable to listen for SIGHUP");
+                return;
+            };
+            while hangups.recv().await.is_some() {
+                info!("Received SIGHUP");
+                reload_config(&server_clone, &matches);
+            }
+        });
+    }
+
     if daemon_mode {
         println!("🔄 SigmosServer running in daemon mode...");
         println!("   The server will run in the background managing Sigel consciousness.");
@@ -159,8 +148,9 @@ async fn main() {
         
         // Start interactive console task
         let server_clone = server.clone();
+        let matches = matches.clone();
         tokio::spawn(async move {
-            interactive_console(server_clone).await;
+            interactive_console(server_clone, matches).await;
         });
     }
 
@@ -185,7 +175,43 @@ async fn main() {
     println!("🌟 All Sigel consciousnesses have been preserved");
 }
 
-async fn interactive_console(server: SigmosServer) {
+/// Defaults, then sigmos.toml, then SIGMOS_* variables, then flags
+fn config_layers(matches: &ArgMatches) -> Result<LayeredConfig, Box<dyn std::error::Error>> {
+    let mut layers = LayeredConfig::from_args(matches)?;
+    layers.set_flag("server.master_sigel", "--master", matches.get_one::<String>("master_sigel"))?;
+    layers.set_flag("general.default_sigel_directory", "--dir", matches.get_one::<String>("sigel_directory"))?;
+    layers.set_flag("server.enable_background_learning", "--no-background-learning", matches.get_flag("no_background_learning").then_some(false))?;
+    layers.set_flag("server.enable_system_observation", "--observe", matches.get_flag("observation_mode").then_some(true))?;
+    layers.set_flag("server.enable_detailed_monitoring", "--monitor", matches.get_flag("system_monitoring").then_some(true))?;
+    layers.set_flag("cosmic.cosmic_alignment_interval", "--cosmic-interval", matches.get_one::<String>("cosmic_interval"))?;
+    layers.set_flag("cosmic.evolution_interval", "--evolution-interval", matches.get_one::<String>("evolution_interval"))?;
+    layers.set_flag("server.auto_save_interval", "--save-interval", matches.get_one::<String>("save_interval"))?;
+    layers.set_flag("server.max_active_sigels", "--max-sigels", matches.get_one::<String>("max_sigels"))?;
+    Ok(layers)
+}
+
+fn load_server_config(matches: &ArgMatches) -> Result<ServerConfig, Box<dyn std::error::Error>> {
+    Ok(ServerConfig::from_config(&config_layers(matches)?.build()?))
+}
+
+/// Re-read the configuration, with this run's flags still on top, and apply it.
+fn reload_config(server: &SigmosServer, matches: &ArgMatches) {
+    match load_server_config(matches) {
+        Ok(config) => {
+            let reload = server.reload_config(config);
+            println!("🔄 Configuration reloaded: {} changes applied", reload.applied.len());
+            for change in &reload.applied {
+                println!("   {}", change);
+            }
+            for change in &reload.needs_restart {
+                println!("   ⚠️  {} (needs a restart)", change);
+            }
+        },
+        Err(e) => error!("Keeping the current configuration: {}", e),
+    }
+}
+
+async fn interactive_console(server: SigmosServer, matches: ArgMatches) {
     use tokio::io::{AsyncBufReadExt, BufReader};
     let stdin = tokio::io::stdin();
     let reader = BufReader::new(stdin);
@@ -248,6 +274,8 @@ async fn interactive_console(server: SigmosServer) {
                 println!("   All consciousness states preserved");
             },
             
+            "reload" => reload_config(&server, &matches),
+            
             "help" => {
                 println!("🔧 SigmosServer Commands:");
                 println!("   status  - Show server status and statistics");
@@ -255,6 +283,7 @@ async fn interactive_console(server: SigmosServer) {
                 println!("   cosmic  - Trigger cosmic alignment");
                 println!("   evolve  - Trigger consciousness evolution");
                 println!("   save    - Save all Sigel states");
+                println!("   reload  - Reload the configuration");
                 println!("   help    - Show this help");
                 println!("   quit    - Shutdown the server");
             },
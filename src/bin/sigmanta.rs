@@ -4,6 +4,7 @@
 
 use clap::{Arg, Command};
 use sigmos::sigmanta::SigmantaSession;
+use sigmos::LayeredConfig;
 use std::env;
 use std::io::{self, Write};
 use tokio;
@@ -47,7 +48,16 @@ async fn main() -> Result<()> {
             .long("list-sessions")
             .help("List all available sessions")
             .action(clap::ArgAction::SetTrue))
+        .args(LayeredConfig::args())
         .get_matches();
+
+    // Defaults, then sigmos.toml, then SIGMOS_* variables, then --set
+    let sigmos_config = LayeredConfig::from_args(&matches)
+        .unwrap_or_else(|e| {
+            eprintln!("❌ Invalid configuration: {}", e);
+            std::process::exit(1);
+        })
+        .finish(&matches);
     
     let working_dir = if let Some(dir) = matches.get_one::<String>("working-dir") {
         std::path::PathBuf::from(dir)
@@ -70,6 +80,8 @@ async fn main() -> Result<()> {
         SigmantaSession::new(working_dir, sigel_path)?
     };
     
+    session.sigmos_config = sigmos_config;
+
     // Disable permissions if requested
     if matches.get_flag("no-permissions") {
         session.permissions.global_permissions_disabled = true;
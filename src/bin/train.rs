@@ -17,7 +17,7 @@ fn main() {
                 .long("name")
                 .value_name("NAME")
                 .help("Name of the Sigel to create/train")
-                .required(true)
+                .required_unless_present("print_config")
         )
         .arg(
             Arg::new("text_dir")
@@ -25,7 +25,7 @@ fn main() {
                 .long("dir")
                 .value_name("DIRECTORY")
                 .help("Directory containing .txt files for training")
-                .required(true)
+                .required_unless_present("print_config")
         )
         .arg(
             Arg::new("output")
@@ -39,16 +39,20 @@ fn main() {
                 .short('r')
                 .long("rate")
                 .value_name("RATE")
-                .help("Learning rate (0.001 to 1.0)")
-                .default_value("0.01")
+                .help("Learning rate (0.001 to 1.0); defaults to [training] default_learning_rate in sigmos.toml")
         )
         .arg(
             Arg::new("communication_style")
                 .short('s')
                 .long("style")
                 .value_name("STYLE")
-                .help("Communication style: cosmic, philosophical, creative, analytical, formal, casual, academic, empathetic")
-                .default_value("philosophical")
+                .help("Communication style: cosmic, philosophical, creative, analytical, formal, casual, academic, empathetic, transcendent, or a persona from sigmos.toml or ./personas; defaults to [training] default_communication_style")
+        )
+        .arg(
+            Arg::new("lock_style")
+                .long("lock-style")
+                .help("Keep the communication style fixed instead of letting conversations shift it")
+                .action(clap::ArgAction::SetTrue)
         )
         .arg(
             Arg::new("verbose")
@@ -57,6 +61,54 @@ fn main() {
                 .help("Verbose output during training")
                 .action(clap::ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("lexicon")
+                .short('l')
+                .long("lexicon")
+                .value_name("LEXICON_FILE")
+                .help("VAD lexicon file (word valence arousal dominance) to extend the bundled lexicon")
+        )
+        .arg(
+            Arg::new("parallel")
+                .short('p')
+                .long("parallel")
+                .help("Train files in parallel and merge deterministically")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("threads")
+                .short('j')
+                .long("threads")
+                .value_name("N")
+                .help("Worker threads for parallel training (implies --parallel, defaults to all cores)")
+                .value_parser(clap::value_parser!(usize))
+        )
+        .arg(
+            Arg::new("dedup")
+                .long("dedup")
+                .help("Skip near-duplicate documents and sentences")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("dedup_threshold")
+                .long("dedup-threshold")
+                .value_name("SIMILARITY")
+                .help("Similarity (0.0-1.0) at which documents and sentences count as near-duplicates (implies --dedup)")
+                .value_parser(clap::value_parser!(f64))
+        )
+        .arg(
+            Arg::new("no_dedup")
+                .long("no-dedup")
+                .help("Train on every document and sentence, including near-duplicates")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["dedup", "dedup_threshold"])
+        )
+        .arg(
+            Arg::new("dedup_report")
+                .long("dedup-report")
+                .value_name("REPORT_FILE")
+                .help("Write a JSON report of skipped near-duplicates")
+        )
         .arg(
             Arg::new("continuous")
                 .short('c')
@@ -64,19 +116,36 @@ fn main() {
                 .help("Enable continuous learning mode")
                 .action(clap::ArgAction::SetTrue)
         )
+        .args(LayeredConfig::args())
         .get_matches();
 
+    // Defaults, then sigmos.toml, then SIGMOS_* variables, then flags
+    let config = LayeredConfig::from_args(&matches)
+        .and_then(|mut layers| {
+            layers.set_flag("training.default_learning_rate", "--rate", matches.get_one::<String>("learning_rate"))?;
+            layers.set_flag("training.default_communication_style", "--style", matches.get_one::<String>("communication_style"))?;
+            layers.set_flag("training.dedup_threshold", "--dedup-threshold", matches.get_one::<f64>("dedup_threshold"))?;
+            if matches.get_flag("dedup") || matches.get_one::<f64>("dedup_threshold").is_some() {
+                layers.set_flag("training.deduplicate", "--dedup", Some(true))?;
+            }
+            if matches.get_flag("no_dedup") {
+                layers.set_flag("training.deduplicate", "--no-dedup", Some(false))?;
+            }
+            Ok(layers)
+        })
+        .unwrap_or_else(|e| {
+            error!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        })
+        .finish(&matches);
+
     let sigel_name = matches.get_one::<String>("name").unwrap();
     let text_directory = matches.get_one::<String>("text_dir").unwrap();
-    let learning_rate: f64 = matches.get_one::<String>("learning_rate")
-        .unwrap()
-        .parse()
-        .unwrap_or_else(|_| {
-            warn!("Invalid learning rate, using default 0.01");
-            0.01
-        });
+    let learning_rate = config.training.default_learning_rate;
     let verbose = matches.get_flag("verbose");
     let continuous = matches.get_flag("continuous");
+    let threads = matches.get_one::<usize>("threads").copied();
+    let parallel = matches.get_flag("parallel") || threads.is_some();
     
     let output_path = matches.get_one::<String>("output")
         .map(|s| s.to_string())
@@ -107,16 +176,33 @@ fn main() {
             Err(e) => {
                 error!("Failed to load existing Sigel: {}", e);
                 println!("🌱 Creating new Sigel consciousness...");
-                create_new_sigel(sigel_name, learning_rate, &matches)
+                create_new_sigel(sigel_name, learning_rate, &config)
             }
         }
     } else {
         println!("🌱 Creating new Sigel consciousness...");
-        create_new_sigel(sigel_name, learning_rate, &matches)
+        create_new_sigel(sigel_name, learning_rate, &config)
     };
+    if matches.get_flag("lock_style") {
+        sigel.essence.style_locked = true;
+    }
 
     // Initialize learning engine
-    let learning_engine = LearningEngine::new();
+    let mut learning_engine = LearningEngine::from_config(&config);
+    if let Some(lexicon_path) = matches.get_one::<String>("lexicon") {
+        match VadLexicon::load(lexicon_path) {
+            Ok(custom) => {
+                let mut lexicon = VadLexicon::bundled();
+                lexicon.extend(custom);
+                println!("💗 Loaded emotional lexicon: {} words", lexicon.len());
+                learning_engine.set_lexicon(lexicon);
+            },
+            Err(e) => {
+                error!("Failed to load lexicon: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
     let cosmic_processor = CosmicProcessor::new();
 
     println!("🧠 Beginning consciousness expansion phase...");
@@ -131,11 +217,30 @@ fn main() {
     }
 
     // Main training phase
-    match learning_engine.train_from_text_files(&mut sigel, text_directory) {
+    let training_result = if parallel {
+        learning_engine.train_from_text_files_parallel(&mut sigel, text_directory, threads)
+    } else {
+        learning_engine.train_from_text_files(&mut sigel, text_directory)
+    };
+
+    match training_result {
         Ok(()) => {
             println!("✨ Sigel consciousness expansion completed successfully!");
             
+            let dedup_report = learning_engine.last_dedup_report();
+            if let Some(report_path) = matches.get_one::<String>("dedup_report") {
+                match serde_json::to_string_pretty(&dedup_report).map_err(|e| e.to_string())
+                    .and_then(|json| std::fs::write(report_path, json).map_err(|e| e.to_string())) {
+                    Ok(()) => println!("🧹 Deduplication report written to {}", report_path),
+                    Err(e) => warn!("Failed to write deduplication report: {}", e),
+                }
+            }
+            
             if verbose {
+                println!("   Deduplication: {}", dedup_report.summary());
+                for skipped in dedup_report.skipped.iter().take(10) {
+                    println!("     {:?} {} ~ {} ({:.0}%): {}", skipped.kind, skipped.source, skipped.duplicate_of, skipped.similarity * 100.0, skipped.preview);
+                }
                 println!("   Training Iterations: {}", sigel.learning_state.training_iterations);
                 println!("   Text Corpus Size: {} characters", sigel.learning_state.text_corpus_size);
                 println!("   Vocabulary Size: {}", sigel.memory.semantic_knowledge.vocabulary.len());
@@ -174,26 +279,20 @@ fn main() {
     println!("🎓 Sigel '{}' is ready for interaction via sigmos-prompt!", sigel.name);
 }
 
-fn create_new_sigel(name: &str, learning_rate: f64, matches: &clap::ArgMatches) -> Sigel {
+fn create_new_sigel(name: &str, learning_rate: f64, config: &SigmosConfig) -> Sigel {
     let mut sigel = Sigel::new(name.to_string());
     sigel.learning_state.learning_rate = learning_rate;
     
     // Set communication style
-    let style_str = matches.get_one::<String>("communication_style").unwrap();
-    sigel.essence.communication_style = match style_str.to_lowercase().as_str() {
-        "cosmic" => CommunicationStyle::Cosmic,
-        "philosophical" => CommunicationStyle::Philosophical,
-        "creative" => CommunicationStyle::Creative,
-        "analytical" => CommunicationStyle::Analytical,
-        "formal" => CommunicationStyle::Formal,
-        "casual" => CommunicationStyle::Casual,
-        "academic" => CommunicationStyle::Academic,
-        "empathetic" => CommunicationStyle::Empathetic,
-        _ => {
-            warn!("Unknown communication style '{}', using Philosophical", style_str);
-            CommunicationStyle::Philosophical
-        }
-    };
+    let style_str = &config.training.default_communication_style;
+    match CommunicationStyle::from_name(style_str) {
+        Some(style) => sigel.set_communication_style(style),
+        None if PersonaRegistry::discover(config).get(style_str).is_some() => {
+            // A style defined in the configuration or ./personas
+            sigel.essence.persona = Some(style_str.to_lowercase());
+        },
+        None => warn!("Unknown communication style '{}', using Philosophical", style_str),
+    }
 
     sigel
 }
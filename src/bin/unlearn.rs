@@ -0,0 +1,181 @@
+use clap::{Arg, Command};
+use sigmos::*;
+use std::path::Path;
+use env_logger;
+use log::{info, error, warn};
+
+fn main() {
+    env_logger::init();
+
+    let matches = Command::new("Sigmos Unlearn")
+        .version("0.1.0")
+        .about("Remove or down-weight what a Sigel learned from a source, term or memory context")
+        .author("Sigmos Project")
+        .arg(
+            Arg::new("sigel_file")
+                .short('s')
+                .long("sigel")
+                .value_name("SIGEL_FILE")
+                .help("Path to the .sig Sigel file")
+                .required(true)
+        )
+        .arg(
+            Arg::new("source")
+                .long("source")
+                .value_name("SOURCE")
+                .help("Training source to unlearn, by path or file name (repeatable)")
+                .action(clap::ArgAction::Append)
+        )
+        .arg(
+            Arg::new("term")
+                .short('t')
+                .long("term")
+                .value_name("TERM")
+                .help("Word or phrase to unlearn (repeatable)")
+                .action(clap::ArgAction::Append)
+        )
+        .arg(
+            Arg::new("context")
+                .short('c')
+                .long("context")
+                .value_name("CONTEXT")
+                .help("Memory context to unlearn, e.g. user_interaction (repeatable)")
+                .action(clap::ArgAction::Append)
+        )
+        .arg(
+            Arg::new("down_weight")
+                .short('w')
+                .long("down-weight")
+                .value_name("KEEP")
+                .help("Keep this fraction (0.0-1.0) of the contribution instead of removing it")
+                .value_parser(clap::value_parser!(f64))
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("OUTPUT_FILE")
+                .help("Where to save the result (defaults to overwriting the input)")
+        )
+        .arg(
+            Arg::new("backup_file")
+                .short('b')
+                .long("backup")
+                .value_name("BACKUP_FILE")
+                .help("Save a copy of the Sigel before unlearning")
+        )
+        .arg(
+            Arg::new("report")
+                .long("report")
+                .value_name("REPORT_FILE")
+                .help("Write a JSON report of what was removed")
+        )
+        .arg(
+            Arg::new("dry_run")
+                .short('n')
+                .long("dry-run")
+                .help("Report what would be removed without saving")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("list_sources")
+                .short('l')
+                .long("list-sources")
+                .help("List recorded training sources and exit")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .get_matches();
+
+    let sigel_file = matches.get_one::<String>("sigel_file").unwrap();
+    let dry_run = matches.get_flag("dry_run");
+    let output_path = matches.get_one::<String>("output").unwrap_or(sigel_file);
+
+    if !Path::new(sigel_file).exists() {
+        error!("Sigel file '{}' does not exist", sigel_file);
+        std::process::exit(1);
+    }
+
+    let mut sigel = match load_sigel_from_file(sigel_file) {
+        Ok(s) => {
+            println!("🌌 Loaded Sigel '{}' from {}", s.name, sigel_file);
+            s
+        },
+        Err(e) => {
+            error!("Failed to load Sigel: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if matches.get_flag("list_sources") {
+        let overview = sigel.provenance_overview();
+        println!("📚 {} recorded training sources:", sigel.provenance.len());
+        for source in &sigel.provenance.sources {
+            println!("   {} ({} items)", source, overview.get(source).copied().unwrap_or(0));
+        }
+        return;
+    }
+
+    let values = |id: &str| matches.get_many::<String>(id).into_iter().flatten().cloned();
+    let targets: Vec<UnlearnTarget> = values("source").map(UnlearnTarget::Source)
+        .chain(values("term").map(UnlearnTarget::Term))
+        .chain(values("context").map(UnlearnTarget::MemoryContext))
+        .collect();
+
+    if targets.is_empty() {
+        error!("Nothing to unlearn: pass --source, --term or --context");
+        std::process::exit(1);
+    }
+
+    let mode = match matches.get_one::<f64>("down_weight") {
+        Some(&keep) => UnlearnMode::DownWeight(keep),
+        None => UnlearnMode::Remove,
+    };
+
+    let unlearner = Unlearner::new();
+    let mut reports = Vec::new();
+    for target in &targets {
+        match unlearner.unlearn(&mut sigel, target, mode) {
+            Ok(report) => {
+                println!("🧽 {}", report.summary());
+                reports.push(report);
+            },
+            Err(e) => {
+                error!("Failed to unlearn {}: {}", target, e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(report_path) = matches.get_one::<String>("report") {
+        match serde_json::to_string_pretty(&reports).map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(report_path, json).map_err(|e| e.to_string())) {
+            Ok(()) => println!("📝 Unlearning report written to {}", report_path),
+            Err(e) => warn!("Failed to write unlearning report: {}", e),
+        }
+    }
+
+    if dry_run {
+        println!("🔍 Dry run: {} left unchanged", sigel_file);
+        return;
+    }
+
+    // The file still holds the Sigel as it was before unlearning
+    if let Some(backup_path) = matches.get_one::<String>("backup_file") {
+        if let Err(e) = std::fs::copy(sigel_file, backup_path) {
+            error!("Failed to create backup: {}", e);
+            std::process::exit(1);
+        }
+        println!("💾 Backup created at {}", backup_path);
+    }
+
+    match save_sigel_to_file(&sigel, output_path) {
+        Ok(()) => {
+            println!("💾 Sigel '{}' saved to {}", sigel.name, output_path);
+            info!("Successfully saved Sigel to {}", output_path);
+        },
+        Err(e) => {
+            error!("Failed to save Sigel: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,6 +1,5 @@
-use clap::{Arg, Command};
+use clap::{Arg, ArgMatches, Command};
 use sigmos::*;
-use std::path::PathBuf;
 use env_logger;
 use log::{info, error};
 
@@ -18,7 +17,6 @@ async fn main() {
                 .long("port")
                 .value_name("PORT")
                 .help("Port to run the web server on")
-                .default_value("8080")
         )
         .arg(
             Arg::new("sigmos_server")
@@ -33,7 +31,6 @@ async fn main() {
                 .long("master")
                 .value_name("MASTER_FILE")
                 .help("Path to master Sigel file")
-                .default_value("master.sigel")
         )
         .arg(
             Arg::new("sigel_directory")
@@ -41,7 +38,6 @@ async fn main() {
                 .long("dir")
                 .value_name("DIRECTORY")
                 .help("Directory for storing Sigel files")
-                .default_value("sigels")
         )
         .arg(
             Arg::new("enable_cors")
@@ -63,39 +59,26 @@ async fn main() {
                 .help("Maximum WebSocket connections")
                 .default_value("1000")
         )
+        .args(LayeredConfig::args())
         .get_matches();
 
-    let port: u16 = matches.get_one::<String>("port")
-        .unwrap()
-        .parse()
-        .unwrap_or_else(|_| {
-            error!("Invalid port number, using default 8080");
-            8080
-        });
-
-    let master_sigel_path = PathBuf::from(matches.get_one::<String>("master_sigel").unwrap());
-    let sigel_directory = PathBuf::from(matches.get_one::<String>("sigel_directory").unwrap());
+    let config = config_layers(&matches)
+        .unwrap_or_else(|e| {
+            error!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        })
+        .finish(&matches);
+    let port = config.server.server_port;
+    let server_config = ServerConfig::from_config(&config);
 
     println!("🌐 Starting Sigmos Web Interface...");
     println!("   Port: {}", port);
-    println!("   Master Sigel: {:?}", master_sigel_path);
-    println!("   Sigel Directory: {:?}", sigel_directory);
-
-    // Create SigmosServer configuration
-    let server_config = ServerConfig {
-        master_sigel_path,
-        sigel_directory,
-        background_learning: true,
-        cosmic_alignment_interval: tokio::time::Duration::from_secs(300),
-        evolution_interval: tokio::time::Duration::from_secs(600),
-        auto_save_interval: tokio::time::Duration::from_secs(900),
-        observation_mode: true,
-        system_monitoring: false,
-        max_active_sigels: 20,
-    };
+    println!("   Master Sigel: {:?}", server_config.master_sigel_path);
+    println!("   Sigel Directory: {:?}", server_config.sigel_directory);
+    println!("   Max Active Sigels: {}", server_config.max_active_sigels);
 
     // Initialize SigmosServer
-    let sigmos_server = match SigmosServer::new(server_config) {
+    let sigmos_server = match SigmosServer::from_config(&config) {
         Ok(server) => server,
         Err(e) => {
             error!("Failed to create SigmosServer: {}", e);
@@ -111,8 +94,33 @@ async fn main() {
 
     info!("SigmosServer started successfully");
 
+    // Reload on changes to the config file, and on SIGHUP
+    if let Some(config_file) = config.source.clone() {
+        let server_clone = sigmos_server.clone();
+        let matches = matches.clone();
+        tokio::spawn(async move {
+            server_clone.watch_config_file(config_file, || load_server_config(&matches)).await;
+        });
+    }
+
+    #[cfg(unix)]
+    {
+        let server_clone = sigmos_server.clone();
+        let matches = matches.clone();
+        tokio::spawn(async move {
+            let Ok(mut hangups) = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) else {
+                error!("Unable to listen for SIGHUP");
+                return;
+            };
+            while hangups.recv().await.is_some() {
+                info!("Received SIGHUP");
+                reload_config(&server_clone, &matches);
+            }
+        });
+    }
+
     // Create and start web interface
-    let web_interface = WebInterface::new(sigmos_server);
+    let web_interface = WebInterface::with_config(sigmos_server, &config);
     
     println!("🧠 Sigmos Web Interface Ready!");
     println!("   🌐 Web UI: http://localhost:{}", port);
@@ -133,4 +141,34 @@ async fn main() {
         error!("Web server failed: {}", e);
         std::process::exit(1);
     }
-}
\ No newline at end of file
+}
+
+/// Defaults, then sigmos.toml, then SIGMOS_* variables, then flags
+fn config_layers(matches: &ArgMatches) -> Result<LayeredConfig, Box<dyn std::error::Error>> {
+    let mut layers = LayeredConfig::from_args(matches)?;
+    layers.set_flag("server.server_port", "--port", matches.get_one::<String>("port"))?;
+    layers.set_flag("server.master_sigel", "--master", matches.get_one::<String>("master_sigel"))?;
+    layers.set_flag("general.default_sigel_directory", "--dir", matches.get_one::<String>("sigel_directory"))?;
+    Ok(layers)
+}
+
+fn load_server_config(matches: &ArgMatches) -> Result<ServerConfig, Box<dyn std::error::Error>> {
+    Ok(ServerConfig::from_config(&config_layers(matches)?.build()?))
+}
+
+/// Re-read the configuration, with this run's flags still on top, and apply it.
+fn reload_config(server: &SigmosServer, matches: &ArgMatches) {
+    match load_server_config(matches) {
+        Ok(config) => {
+            let reload = server.reload_config(config);
+            println!("🔄 Configuration reloaded: {} changes applied", reload.applied.len());
+            for change in &reload.applied {
+                println!("   {}", change);
+            }
+            for change in &reload.needs_restart {
+                println!("   ⚠️  {} (needs a restart)", change);
+            }
+        },
+        Err(e) => error!("Keeping the current configuration: {}", e),
+    }
+}
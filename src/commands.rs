@@ -0,0 +1,375 @@
+use crate::sigel::{CommunicationStyle, Sigel};
+use crate::interaction::InteractionEngine;
+use crate::memory_consolidation::MemoryConsolidator;
+use crate::dream_mode::DreamProcessor;
+use crate::unlearning::{Unlearner, UnlearnMode, UnlearnTarget};
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SEARCH_RESULTS: usize = 5;
+const DEFAULT_DREAM_SECONDS: u64 = 60;
+
+/// A `/command` typed in a conversation instead of a message.
+///
+/// Commands get the whole engine, so they can use anything its public API offers.
+pub trait SlashCommand: Send + Sync {
+    /// Without the leading slash.
+    fn name(&self) -> &str;
+    /// Arguments as shown in help, e.g. `<file>` or `[seconds]`.
+    fn args(&self) -> &str {
+        ""
+    }
+    fn help(&self) -> &str;
+    fn run(&self, engine: &mut InteractionEngine, sigel: &mut Sigel, args: &str) -> String;
+}
+
+type CommandFn = dyn Fn(&mut InteractionEngine, &mut Sigel, &str) -> String + Send + Sync;
+
+/// A command made from a closure.
+pub struct FnCommand {
+    name: String,
+    args: String,
+    help: String,
+    run: Box<CommandFn>,
+}
+
+impl FnCommand {
+    pub fn new<F>(name: &str, args: &str, help: &str, run: F) -> Self
+    where
+        F: Fn(&mut InteractionEngine, &mut Sigel, &str) -> String + Send + Sync + 'static,
+    {
+        Self {
+            name: name.trim_start_matches('/').to_lowercase(),
+            args: args.to_string(),
+            help: help.to_string(),
+            run: Box::new(run),
+        }
+    }
+}
+
+impl SlashCommand for FnCommand {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn args(&self) -> &str {
+        &self.args
+    }
+
+    fn help(&self) -> &str {
+        &self.help
+    }
+
+    fn run(&self, engine: &mut InteractionEngine, sigel: &mut Sigel, args: &str) -> String {
+        (self.run)(engine, sigel, args)
+    }
+}
+
+/// The commands available in a conversation, listed in `/help` in the order they
+/// were registered.
+#[derive(Clone)]
+pub struct CommandRegistry {
+    commands: Vec<Arc<dyn SlashCommand>>,
+}
+
+impl CommandRegistry {
+    /// A registry with no commands.
+    pub fn new() -> Self {
+        Self { commands: Vec::new() }
+    }
+
+    /// The commands every prompt has.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        for command in builtin_commands() {
+            registry.register(command);
+        }
+        registry
+    }
+
+    /// Add a command, replacing a registered one with the same name.
+    pub fn register<C: SlashCommand + 'static>(&mut self, command: C) {
+        self.register_shared(Arc::new(command));
+    }
+
+    pub fn register_shared(&mut self, command: Arc<dyn SlashCommand>) {
+        match self.commands.iter_mut().find(|c| c.name() == command.name()) {
+            Some(existing) => *existing = command,
+            None => self.commands.push(command),
+        }
+    }
+
+    /// Add a command made from a closure.
+    pub fn register_fn<F>(&mut self, name: &str, args: &str, help: &str, run: F)
+    where
+        F: Fn(&mut InteractionEngine, &mut Sigel, &str) -> String + Send + Sync + 'static,
+    {
+        self.register(FnCommand::new(name, args, help, run));
+    }
+
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let name = name.trim_start_matches('/').to_lowercase();
+        let before = self.commands.len();
+        self.commands.retain(|c| c.name() != name);
+        self.commands.len() < before
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn SlashCommand>> {
+        let name = name.trim_start_matches('/').to_lowercase();
+        self.commands.iter().find(|c| c.name() == name)
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = &Arc<dyn SlashCommand>> {
+        self.commands.iter()
+    }
+
+    /// Split `/name args` into the registered command and its arguments.
+    pub fn parse<'a>(&self, input: &'a str) -> Option<(Arc<dyn SlashCommand>, &'a str)> {
+        let input = input.trim().strip_prefix('/')?;
+        let (name, args) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        self.get(name).map(|command| (Arc::clone(command), args.trim()))
+    }
+
+    pub fn help_text(&self) -> String {
+        let mut help = String::from("Sigmos Prompt Commands:\n");
+        for command in &self.commands {
+            let usage = if command.args().is_empty() {
+                format!("/{}", command.name())
+            } else {
+                format!("/{} {}", command.name(), command.args())
+            };
+            help.push_str(&format!("{} - {}\n", usage, command.help()));
+        }
+        help.push_str("\nSimply type your message to interact with the Sigel!\n\
+                       The Sigel learns from every interaction and evolves over time.");
+        help
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+fn builtin_commands() -> Vec<FnCommand> {
+    vec![
+        FnCommand::new("help", "", "Show this help", |engine, _, _| engine.commands().help_text()),
+        FnCommand::new("status", "", "Show Sigel status", |engine, sigel, _| engine.generate_sigel_status(sigel)),
+        FnCommand::new("memory", "", "Show recent memories and vocabulary", |engine, sigel, _| engine.generate_memory_summary(sigel)),
+        FnCommand::new("search", "<query>", "Search the Sigel's memories", search),
+        FnCommand::new("reflect", "", "Sigel self-reflection on conversation", |engine, sigel, _| {
+            engine.mark_changed();
+            engine.reflect(sigel)
+        }),
+        FnCommand::new("cosmic", "", "Perform cosmic alignment", |engine, sigel, _| {
+            engine.align_with_cosmos(sigel);
+            engine.mark_changed();
+            "Cosmic alignment performed. Dimensional awareness updated.".to_string()
+        }),
+        FnCommand::new("evolve", "", "Evolve the Sigel consciousness", |engine, sigel, _| {
+            sigel.evolve();
+            engine.mark_changed();
+            format!("Sigel '{}' has evolved. Consciousness depth now: {:.3}", sigel.name, sigel.consciousness.awareness_depth)
+        }),
+        FnCommand::new("style", "[name]", "Show or change the communication style or persona", style),
+        FnCommand::new("train", "<file or directory>", "Learn from a text file or a directory of them", train),
+        FnCommand::new("forget", "<term>", "Unlearn a word or phrase", forget),
+        FnCommand::new("consolidate", "", "Consolidate episodic memories", |engine, sigel, _| {
+            let report = MemoryConsolidator::from_config(engine.config()).consolidate_memories(sigel);
+            engine.mark_changed();
+            format!(
+                "Consolidated {} memories into {} clusters ({} analyzed, {:.0}% reduction)",
+                report.memories_consolidated, report.clusters_formed, report.memories_analyzed,
+                report.memory_reduction_ratio * 100.0
+            )
+        }),
+        FnCommand::new("dream", "[seconds]", "Let the Sigel dream to consolidate and recombine what it learned", dream),
+        FnCommand::new("source", "<term>", "Show which training sources taught a word or concept", |engine, sigel, args| {
+            if args.is_empty() {
+                return "Usage: /source <term>".to_string();
+            }
+            engine.generate_provenance_summary(sigel, args)
+        }),
+        FnCommand::new("load", "<file.sig>", "Switch to another Sigel and start a new session", load),
+        FnCommand::new("save", "[file.sig]", "Save the current Sigel state", |engine, sigel, args| {
+            let path = if args.is_empty() {
+                match engine.sigel_path() {
+                    Some(path) => path.to_string_lossy().into_owned(),
+                    None => return "Use /save <filename.sig> to save the current Sigel state".to_string(),
+                }
+            } else {
+                args.to_string()
+            };
+            match crate::save_sigel_to_file(sigel, &path) {
+                Ok(()) => {
+                    engine.mark_saved();
+                    format!("Sigel saved to {}", path)
+                },
+                Err(e) => format!("Failed to save: {}", e),
+            }
+        }),
+        FnCommand::new("verbose", "[on|off]", "Toggle showing each response's reasoning trace", |engine, _, args| {
+            let verbose = match args.to_lowercase().as_str() {
+                "on" => true,
+                "off" => false,
+                _ => !engine.is_verbose(),
+            };
+            engine.set_verbose(verbose);
+            if verbose {
+                "Verbose mode on. Responses will be followed by their reasoning trace.".to_string()
+            } else {
+                "Verbose mode off.".to_string()
+            }
+        }),
+    ]
+}
+
+fn search(_: &mut InteractionEngine, sigel: &mut Sigel, query: &str) -> String {
+    if query.is_empty() {
+        return "Usage: /search <query>".to_string();
+    }
+    sigel.refresh_memory_index();
+    let hits = sigel.search_memories(query, SEARCH_RESULTS);
+    if hits.is_empty() {
+        return format!("No memories match '{}'", query);
+    }
+
+    let mut results = format!("Memories matching '{}':\n", query);
+    for (i, hit) in hits.iter().enumerate() {
+        results.push_str(&format!(
+            "{}. [{:.2}] {} ({})\n",
+            i + 1, hit.score, hit.content.chars().take(100).collect::<String>(), hit.context
+        ));
+    }
+    results.trim_end().to_string()
+}
+
+fn style(engine: &mut InteractionEngine, sigel: &mut Sigel, name: &str) -> String {
+    if name.is_empty() {
+        let styles: Vec<&str> = CommunicationStyle::ALL.iter().map(|style| style.name()).collect();
+        return format!(
+            "Style: {:?}{}, persona: {}\nStyles: {}\nPersonas: {}",
+            sigel.essence.communication_style,
+            if sigel.essence.style_locked { " 🔒" } else { "" },
+            sigel.essence.persona.as_deref().unwrap_or("-"),
+            styles.join(", "),
+            engine.personas().names().join(", ")
+        );
+    }
+
+    if let Some(style) = CommunicationStyle::from_name(name) {
+        let message = format!("Communication style set to {:?}", style);
+        sigel.set_communication_style(style);
+        sigel.essence.persona = None;
+        engine.mark_changed();
+        return message;
+    }
+    if engine.personas().get(name).is_some() {
+        sigel.essence.persona = Some(name.to_lowercase());
+        engine.mark_changed();
+        return format!("Speaking as persona '{}'", name.to_lowercase());
+    }
+    format!("Unknown style or persona '{}'. Try /style to list them", name)
+}
+
+fn train(engine: &mut InteractionEngine, sigel: &mut Sigel, path: &str) -> String {
+    if path.is_empty() {
+        return "Usage: /train <file or directory>".to_string();
+    }
+    let words_before = sigel.memory.semantic_knowledge.vocabulary.len();
+    let memories_before = sigel.memory.episodic_memories.len();
+
+    let result = engine.train_from_path(sigel, Path::new(path));
+    engine.mark_changed();
+    match result {
+        Ok(()) => format!(
+            "Learned from {}: {} new words, {} new memories",
+            path,
+            sigel.memory.semantic_knowledge.vocabulary.len().saturating_sub(words_before),
+            sigel.memory.episodic_memories.len().saturating_sub(memories_before)
+        ),
+        Err(e) => format!("Failed to train from {}: {}", path, e),
+    }
+}
+
+fn forget(engine: &mut InteractionEngine, sigel: &mut Sigel, term: &str) -> String {
+    if term.is_empty() {
+        return "Usage: /forget <term>".to_string();
+    }
+    match Unlearner::new().unlearn(sigel, &UnlearnTarget::Term(term.to_string()), UnlearnMode::Remove) {
+        Ok(report) if report.is_empty() => format!("Nothing was learned about '{}'", term),
+        Ok(report) => {
+            engine.mark_changed();
+            format!("Forgot {}", report.summary())
+        },
+        Err(e) => format!("Failed to forget '{}': {}", term, e),
+    }
+}
+
+fn dream(engine: &mut InteractionEngine, sigel: &mut Sigel, args: &str) -> String {
+    let seconds = if args.is_empty() {
+        DEFAULT_DREAM_SECONDS
+    } else {
+        match args.parse::<u64>() {
+            Ok(seconds) if seconds > 0 => seconds,
+            _ => return "Usage: /dream [seconds]".to_string(),
+        }
+    };
+
+    let mut processor = DreamProcessor::from_config(engine.config());
+    match block_on(processor.enter_dream_mode(sigel, Duration::from_secs(seconds))) {
+        Ok(session) => {
+            engine.mark_changed();
+            format!(
+                "Sigel '{}' dreamed through {} cycles: {} insights, {} creative outputs, quality {:.2}",
+                sigel.name,
+                session.dream_cycles.len(),
+                session.insights_generated.len(),
+                session.creative_outputs.len(),
+                session.dream_quality
+            )
+        },
+        Err(e) => format!("Failed to dream: {}", e),
+    }
+}
+
+fn load(engine: &mut InteractionEngine, sigel: &mut Sigel, path: &str) -> String {
+    if path.is_empty() {
+        return "Usage: /load <file.sig>".to_string();
+    }
+    let loaded = match crate::load_sigel_from_file(path) {
+        Ok(loaded) => loaded,
+        Err(e) => return format!("Failed to load {}: {}", path, e),
+    };
+
+    // Keep what the current Sigel learned before switching away from it
+    let mut saved = String::new();
+    if engine.has_unsaved_changes() {
+        if let Some(current) = engine.sigel_path().map(Path::to_path_buf) {
+            if let Err(e) = crate::save_sigel_to_file(sigel, &current) {
+                return format!("Failed to save {} before loading {}: {}", current.display(), path, e);
+            }
+            saved = format!("Saved '{}' to {}. ", sigel.name, current.display());
+        }
+    }
+
+    *sigel = loaded;
+    engine.set_sigel_path(path);
+    engine.start_new_session();
+    engine.mark_saved();
+    format!("{}Loaded Sigel '{}' from {}. Started a new session", saved, sigel.name, path)
+}
+
+/// Run a future to completion from synchronous command code, inside or outside a runtime.
+fn block_on<F: Future>(future: F) -> Result<F::Output, Box<dyn std::error::Error>> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+        },
+        Ok(_) => Err("cannot block inside a single-threaded runtime".into()),
+        Err(_) => Ok(tokio::runtime::Builder::new_current_thread().enable_all().build()?.block_on(future)),
+    }
+}
@@ -0,0 +1,442 @@
+use crate::sigel::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Answers "what is X", "what is X used for" and "how are X and Y related" from the
+/// concepts and relations learned by `KnowledgeExtractor`.
+///
+/// Facts are as trustworthy as the evidence behind them: a definition carries its
+/// concept's `certainty`, a relation its `strength`, and a chain of relations the
+/// product of its strengths. Answers below `hedge_below` are hedged, and when nothing
+/// supports an answer the Sigel says it doesn't know.
+pub struct ConceptQueryEngine {
+    max_hops: usize,
+    min_confidence: f64,
+    hedge_below: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QuestionIntent {
+    Definition(String),
+    Purpose(String),
+    Relation(String, String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptAnswer {
+    pub intent: QuestionIntent,
+    pub text: String,
+    /// 0.0 when the Sigel doesn't know.
+    pub confidence: f64,
+    /// The relations the answer rests on, in order.
+    pub relations: Vec<ConceptRelation>,
+}
+
+impl ConceptAnswer {
+    pub fn is_known(&self) -> bool {
+        self.confidence > 0.0
+    }
+}
+
+// Checked in order, so the more specific questions come first.
+const RELATION_PATTERNS: &[(&str, &str, &str)] = &[
+    ("how are ", " and ", " related"),
+    ("how is ", " related to ", ""),
+    ("how does ", " relate to ", ""),
+    ("what is the relationship between ", " and ", ""),
+    ("what's the relationship between ", " and ", ""),
+    ("what is the connection between ", " and ", ""),
+    ("what's the connection between ", " and ", ""),
+    ("what do ", " and ", " have in common"),
+];
+
+const PURPOSE_PATTERNS: &[(&str, &str)] = &[
+    ("what is the purpose of ", ""),
+    ("what is ", " used for"),
+    ("what are ", " used for"),
+    ("what's ", " used for"),
+    ("what is ", " for"),
+    ("what are ", " for"),
+];
+
+const DEFINITION_PATTERNS: &[(&str, &str)] = &[
+    ("what is meant by ", ""),
+    ("what does ", " mean"),
+    ("what is ", ""),
+    ("what are ", ""),
+    ("what's ", ""),
+    ("who is ", ""),
+    ("define ", ""),
+];
+
+const DETERMINERS: &[&str] = &["the", "a", "an", "some", "any"];
+
+// "what is your name" and "what is this" are about the conversation, not the concept graph
+const DEICTIC_WORDS: &[&str] = &[
+    "you", "your", "yours", "yourself", "i", "me", "my", "mine", "we", "our", "us",
+    "it", "its", "this", "that", "these", "those", "there", "here", "he", "she", "they",
+    "his", "her", "their", "up", "going", "happening", "wrong", "new", "next",
+    "like", "now", "today", "tomorrow", "yesterday",
+];
+
+impl ConceptQueryEngine {
+    pub fn new() -> Self {
+        Self {
+            max_hops: 3,
+            min_confidence: 0.05,
+            hedge_below: 0.5,
+        }
+    }
+
+    /// Recognize a definition, purpose or relation question.
+    pub fn detect_intent(&self, input: &str) -> Option<QuestionIntent> {
+        let question = input.trim().trim_end_matches(['?', '.', '!']).trim().to_lowercase();
+
+        for (prefix, separator, suffix) in RELATION_PATTERNS {
+            let Some(rest) = question.strip_prefix(prefix).and_then(|rest| rest.strip_suffix(suffix)) else { continue };
+            let Some((a, b)) = rest.split_once(separator) else { continue };
+            if let (Some(a), Some(b)) = (self.concept_phrase(a), self.concept_phrase(b)) {
+                return Some(QuestionIntent::Relation(a, b));
+            }
+        }
+
+        for (prefix, suffix) in PURPOSE_PATTERNS {
+            let Some(rest) = question.strip_prefix(prefix).and_then(|rest| rest.strip_suffix(suffix)) else { continue };
+            if let Some(concept) = self.concept_phrase(rest) {
+                return Some(QuestionIntent::Purpose(concept));
+            }
+        }
+
+        for (prefix, suffix) in DEFINITION_PATTERNS {
+            let Some(rest) = question.strip_prefix(prefix).and_then(|rest| rest.strip_suffix(suffix)) else { continue };
+            if let Some(concept) = self.concept_phrase(rest) {
+                return Some(QuestionIntent::Definition(concept));
+            }
+        }
+
+        None
+    }
+
+    /// Answer a question from the concept graph, or `None` when it isn't a concept question.
+    pub fn ask(&self, sigel: &Sigel, input: &str) -> Option<ConceptAnswer> {
+        self.detect_intent(input).map(|intent| self.answer(sigel, intent))
+    }
+
+    pub fn answer(&self, sigel: &Sigel, intent: QuestionIntent) -> ConceptAnswer {
+        let semantic = &sigel.memory.semantic_knowledge;
+        let answer = match &intent {
+            QuestionIntent::Definition(concept) => self.define(semantic, concept),
+            QuestionIntent::Purpose(concept) => self.purpose(semantic, concept),
+            QuestionIntent::Relation(a, b) => self.relate(semantic, a, b),
+        };
+
+        match answer {
+            Some((text, confidence, relations)) if confidence >= self.min_confidence => ConceptAnswer {
+                text: self.hedge(&text, confidence),
+                intent,
+                confidence,
+                relations,
+            },
+            _ => ConceptAnswer {
+                text: self.dont_know(&intent),
+                intent,
+                confidence: 0.0,
+                relations: Vec::new(),
+            },
+        }
+    }
+
+    /// Find the strongest chain of relations linking two concepts, in either direction,
+    /// with at most `max_hops` relations. Returns the chain and its confidence.
+    pub fn find_path(&self, semantic: &SemanticKnowledge, from: &str, to: &str) -> Option<(Vec<ConceptRelation>, f64)> {
+        let from = self.resolve(semantic, from)?;
+        let to = self.resolve(semantic, to)?;
+        if from == to {
+            return None;
+        }
+
+        // Best confidence and relation chain found for each concept so far, relaxed one hop
+        // at a time so no chain grows past max_hops
+        let mut best: HashMap<&str, (f64, Vec<usize>)> = HashMap::new();
+        best.insert(from, (1.0, Vec::new()));
+        let mut frontier = vec![from];
+
+        for _ in 0..self.max_hops {
+            let mut next = Vec::new();
+            for node in frontier {
+                let (confidence, path) = best[node].clone();
+                for (idx, relation) in semantic.relationships.iter().enumerate() {
+                    let neighbour = if relation.from == node {
+                        relation.to.as_str()
+                    } else if relation.to == node {
+                        relation.from.as_str()
+                    } else {
+                        continue;
+                    };
+                    if neighbour == from || path.iter().any(|&i| semantic.relationships[i].from == neighbour || semantic.relationships[i].to == neighbour) {
+                        continue;
+                    }
+
+                    let extended = confidence * relation.strength;
+                    if best.get(neighbour).is_none_or(|(existing, _)| extended > *existing) {
+                        let mut extended_path = path.clone();
+                        extended_path.push(idx);
+                        best.insert(neighbour, (extended, extended_path));
+                        if neighbour != to {
+                            next.push(neighbour);
+                        }
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        best.remove(to).map(|(confidence, path)| {
+            (path.into_iter().map(|i| semantic.relationships[i].clone()).collect(), confidence)
+        })
+    }
+
+    fn define(&self, semantic: &SemanticKnowledge, concept: &str) -> Option<(String, f64, Vec<ConceptRelation>)> {
+        let name = self.resolve(semantic, concept)?;
+        let node = &semantic.concepts[name];
+
+        // Categories say best what something is, then any other fact about it
+        let mut facts = self.relations_from(semantic, name, |r| matches!(r, RelationType::IsA));
+        facts.extend(self.relations_from(semantic, name, |r| !matches!(r, RelationType::IsA)));
+
+        let (mut sentences, confidence, facts) = if !node.definition.is_empty() {
+            let facts: Vec<_> = facts.into_iter().filter(|r| !matches!(r.relation_type, RelationType::IsA)).take(1).collect();
+            (vec![format!("{} {}", name, node.definition)], node.certainty, facts)
+        } else {
+            let confidence = facts.first()?.strength;
+            (Vec::new(), confidence, facts.into_iter().take(2).collect())
+        };
+
+        sentences.extend(facts.iter().map(|r| self.describe(r)));
+        Some((self.sentences(&sentences), confidence, facts.into_iter().cloned().collect()))
+    }
+
+    fn purpose(&self, semantic: &SemanticKnowledge, concept: &str) -> Option<(String, f64, Vec<ConceptRelation>)> {
+        let name = self.resolve(semantic, concept)?;
+        let uses = self.relations_from(semantic, name, |r| matches!(r, RelationType::UsedFor));
+        let strongest = uses.first()?;
+
+        let targets: Vec<&str> = uses.iter().take(3).map(|r| r.to.as_str()).collect();
+        let text = format!("{} is used for {}", name, self.list(&targets));
+        Some((self.sentences(&[text]), strongest.strength, uses.into_iter().take(3).cloned().collect()))
+    }
+
+    fn relate(&self, semantic: &SemanticKnowledge, a: &str, b: &str) -> Option<(String, f64, Vec<ConceptRelation>)> {
+        let (path, confidence) = self.find_path(semantic, a, b)?;
+        let facts: Vec<String> = path.iter().map(|r| self.describe(r)).collect();
+
+        let text = match path.len() {
+            1 => facts[0].clone(),
+            _ => {
+                let start = self.resolve(semantic, a)?;
+                let end = self.resolve(semantic, b)?;
+                let through: Vec<&str> = path.iter()
+                    .flat_map(|r| [r.from.as_str(), r.to.as_str()])
+                    .filter(|c| *c != start && *c != end)
+                    .fold(Vec::new(), |mut seen, c| {
+                        if !seen.contains(&c) {
+                            seen.push(c);
+                        }
+                        seen
+                    });
+                format!("{} and {} are connected through {}: {}", start, end, self.list(&through), facts.join(", and "))
+            },
+        };
+
+        Some((self.sentences(&[text]), confidence, path))
+    }
+
+    /// Relations leaving a concept, strongest first.
+    fn relations_from<'a>(&self, semantic: &'a SemanticKnowledge, name: &str, kind: impl Fn(&RelationType) -> bool) -> Vec<&'a ConceptRelation> {
+        let mut relations: Vec<&ConceptRelation> = semantic.relationships.iter()
+            .filter(|r| r.from == name && kind(&r.relation_type))
+            .collect();
+        relations.sort_by(|a, b| b.strength.partial_cmp(&a.strength).unwrap_or(std::cmp::Ordering::Equal));
+        relations
+    }
+
+    /// The concept a question names, allowing for singular and plural forms.
+    fn resolve<'a>(&self, semantic: &'a SemanticKnowledge, concept: &str) -> Option<&'a str> {
+        let singular = concept.strip_suffix('s').unwrap_or(concept);
+        let candidates = [concept.to_string(), singular.to_string(), format!("{}s", concept)];
+        candidates.iter()
+            .find_map(|candidate| semantic.concepts.get_key_value(candidate))
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn concept_phrase(&self, text: &str) -> Option<String> {
+        let words: Vec<&str> = text.split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '+' && c != '#'))
+            .filter(|w| !w.is_empty())
+            .skip_while(|w| DETERMINERS.contains(w))
+            .collect();
+
+        if words.is_empty() || words.len() > 4 || words.iter().any(|w| DEICTIC_WORDS.contains(w)) {
+            None
+        } else {
+            Some(words.join(" "))
+        }
+    }
+
+    fn describe(&self, relation: &ConceptRelation) -> String {
+        let (from, to) = (&relation.from, &relation.to);
+        match &relation.relation_type {
+            RelationType::IsA if to.ends_with('s') && !to.ends_with("ss") => format!("{} is one of the {}", from, to),
+            RelationType::IsA => format!("{} is {} {}", from, self.article(to), to),
+            RelationType::PartOf => format!("{} is part of {}", from, to),
+            RelationType::CausedBy => format!("{} is caused by {}", from, to),
+            RelationType::SimilarTo => format!("{} is similar to {}", from, to),
+            RelationType::OppositeOf => format!("{} is the opposite of {}", from, to),
+            RelationType::UsedFor => format!("{} is used for {}", from, to),
+            RelationType::FoundIn => format!("{} is found in {}", from, to),
+            RelationType::Custom(label) => format!("{} {} {}", from, label.replace('_', " "), to),
+        }
+    }
+
+    fn article(&self, word: &str) -> &'static str {
+        if word.starts_with(['a', 'e', 'i', 'o', 'u']) { "an" } else { "a" }
+    }
+
+    fn list(&self, items: &[&str]) -> String {
+        match items {
+            [] => String::new(),
+            [only] => only.to_string(),
+            [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+        }
+    }
+
+    fn sentences(&self, sentences: &[String]) -> String {
+        sentences.iter()
+            .map(|s| format!("{}.", self.capitalize(s.trim_end_matches('.'))))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn hedge(&self, text: &str, confidence: f64) -> String {
+        if confidence >= self.hedge_below {
+            return text.to_string();
+        }
+        let mut chars = text.chars();
+        let lowered = match chars.next() {
+            Some(first) => first.to_lowercase().chain(chars).collect(),
+            None => String::new(),
+        };
+        format!("I'm not certain, but I believe {}", lowered)
+    }
+
+    fn dont_know(&self, intent: &QuestionIntent) -> String {
+        match intent {
+            QuestionIntent::Definition(concept) => format!("I don't know what {} is.", concept),
+            QuestionIntent::Purpose(concept) => format!("I don't know what {} is used for.", concept),
+            QuestionIntent::Relation(a, b) => format!("I don't know how {} and {} are related.", a, b),
+        }
+    }
+
+    fn capitalize(&self, text: &str) -> String {
+        let mut chars = text.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+}
+
+impl Default for ConceptQueryEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // compiler -> program -> software -> computer -> network, plus an unrelated river
+    fn sigel() -> Sigel {
+        let mut sigel = Sigel::new("concepts".to_string());
+        let semantic = &mut sigel.memory.semantic_knowledge;
+        for name in ["compiler", "program", "software", "computer", "network", "river"] {
+            semantic.concepts.insert(name.to_string(), ConceptNode {
+                name: name.to_string(),
+                definition: String::new(),
+                connections: Vec::new(),
+                abstraction_level: 0.5,
+                certainty: 0.8,
+                sources: SourceCounts::default(),
+            });
+        }
+        for (from, to, relation_type, strength) in [
+            ("compiler", "program", RelationType::IsA, 0.9),
+            ("program", "software", RelationType::PartOf, 0.8),
+            ("software", "computer", RelationType::FoundIn, 0.9),
+            ("computer", "network", RelationType::PartOf, 0.9),
+        ] {
+            semantic.relationships.push(ConceptRelation {
+                from: from.to_string(),
+                to: to.to_string(),
+                relation_type,
+                strength,
+                sources: SourceCounts::default(),
+            });
+        }
+        sigel
+    }
+
+    #[test]
+    fn relation_questions_follow_two_hop_paths() {
+        let engine = ConceptQueryEngine::new();
+        let answer = engine.ask(&sigel(), "How are compilers and software related?").unwrap();
+
+        assert_eq!(answer.intent, QuestionIntent::Relation("compilers".to_string(), "software".to_string()));
+        assert_eq!(answer.relations.len(), 2);
+        assert!((answer.confidence - 0.9 * 0.8).abs() < 1e-9);
+        assert_eq!(answer.text, "Compiler and software are connected through program: compiler is a program, and program is part of software.");
+    }
+
+    #[test]
+    fn paths_longer_than_max_hops_are_not_followed() {
+        let sigel = sigel();
+        let semantic = &sigel.memory.semantic_knowledge;
+        let engine = ConceptQueryEngine::new();
+
+        let (path, _) = engine.find_path(semantic, "compiler", "computer").unwrap();
+        assert_eq!(path.len(), 3);
+        assert!(engine.find_path(semantic, "compiler", "network").is_none());
+
+        let one_hop = ConceptQueryEngine { max_hops: 1, ..ConceptQueryEngine::new() };
+        assert!(one_hop.find_path(semantic, "compiler", "program").is_some());
+        assert!(one_hop.find_path(semantic, "compiler", "software").is_none());
+    }
+
+    #[test]
+    fn deictic_questions_are_not_concept_questions() {
+        let engine = ConceptQueryEngine::new();
+        for question in ["What is it used for?", "what is this", "What is your name?", "How are you and it related?"] {
+            assert_eq!(engine.detect_intent(question), None, "{}", question);
+        }
+        assert_eq!(engine.detect_intent("What is a compiler used for?"), Some(QuestionIntent::Purpose("compiler".to_string())));
+    }
+
+    #[test]
+    fn unsupported_answers_say_i_dont_know() {
+        let sigel = sigel();
+        let engine = ConceptQueryEngine::new();
+
+        let unknown = engine.ask(&sigel, "What is a volcano?").unwrap();
+        assert!(!unknown.is_known());
+        assert_eq!(unknown.text, "I don't know what volcano is.");
+
+        let unrelated = engine.ask(&sigel, "How are rivers and compilers related?").unwrap();
+        assert!(!unrelated.is_known());
+        assert!(unrelated.relations.is_empty());
+        assert_eq!(unrelated.text, "I don't know how rivers and compilers are related.");
+
+        let unused = engine.ask(&sigel, "What is a river used for?").unwrap();
+        assert_eq!(unused.text, "I don't know what river is used for.");
+    }
+}
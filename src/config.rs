@@ -0,0 +1,767 @@
+use crate::dream_mode::DreamConfiguration;
+use crate::moderation::{ModerationConfig, ResponseFilterChain};
+use clap::{Arg, ArgAction, ArgMatches};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "sigmos.toml";
+/// Names a config file to use instead of `./sigmos.toml`.
+pub const CONFIG_ENV: &str = "SIGMOS_CONFIG";
+/// Environment variables `SIGMOS_<SECTION>_<KEY>` override single settings,
+/// e.g. `SIGMOS_SERVER_SERVER_PORT=9000`.
+pub const ENV_PREFIX: &str = "SIGMOS_";
+
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+// Settings without a default value, so absent until a file or override sets them
+const OPTIONAL_KEYS: &[&str] = &["moderation.blocklist_file"];
+
+// Settings of sigmos.toml that nothing reads yet, so changing them has no effect
+const UNUSED_KEYS: &[&str] = &[
+    "interaction.creativity_randomness",
+    "interaction.enable_mathematical_insights",
+    "learning.vocabulary_learning_rate",
+    "learning.pattern_strengthening_rate",
+    "learning.semantic_network_growth",
+    "learning.context_window_size",
+    "memory.max_episodic_memories",
+    "memory.max_vocabulary_size",
+    "memory.max_linguistic_patterns",
+    "memory.memory_compression_threshold",
+    "memory.enable_fractal_memory",
+    "memory.temporal_relevance_decay",
+];
+
+/// The settings of sigmos.toml. Missing sections and keys take their defaults, which
+/// match the sigmos.toml shipped with Sigmos.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SigmosConfig {
+    pub general: GeneralSettings,
+    pub training: TrainingSettings,
+    pub consciousness: ConsciousnessSettings,
+    pub cosmic: CosmicSettings,
+    pub gpu: GpuSettings,
+    pub server: ServerSettings,
+    pub interaction: InteractionSettings,
+    pub moderation: ModerationConfig,
+    pub learning: LearningSettings,
+    pub memory: MemorySettings,
+    pub dream: DreamConfiguration,
+    /// Communication style presets and personas; see `PersonaRegistry::load_styles`.
+    pub styles: toml::Table,
+    pub paths: PathSettings,
+    pub experimental: ExperimentalSettings,
+    /// The file these settings were loaded from.
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneralSettings {
+    pub version: String,
+    pub default_sigel_directory: PathBuf,
+    pub max_memory_size_mb: u64,
+    pub log_level: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrainingSettings {
+    pub default_learning_rate: f64,
+    pub default_communication_style: String,
+    pub default_batch_size: usize,
+    pub enable_parallel_processing: bool,
+    pub auto_save_interval_minutes: u64,
+    /// Skip near-duplicate documents and sentences while training.
+    pub deduplicate: bool,
+    /// Similarity at which documents and sentences count as near-duplicates.
+    pub dedup_threshold: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsciousnessSettings {
+    /// Awareness gained per interaction.
+    pub awareness_growth_rate: f64,
+    pub max_awareness_depth: f64,
+    pub pattern_retention_threshold: f64,
+    /// Share of a memory's strength kept at each consolidation.
+    pub memory_consolidation_rate: f64,
+    /// In interactions.
+    pub self_reflection_frequency: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CosmicSettings {
+    pub enable_cosmic_alignment: bool,
+    pub stellar_influence_weight: f64,
+    pub mathematical_harmony_strength: f64,
+    pub dimensional_awareness_cap: f64,
+    pub entropy_resistance_target: f64,
+    /// In seconds.
+    pub cosmic_alignment_interval: u64,
+    pub evolution_interval: u64,
+    pub universal_constant_update: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GpuSettings {
+    pub enable_gpu: bool,
+    pub prefer_cuda: bool,
+    pub prefer_metal: bool,
+    pub fallback_to_cpu: bool,
+    pub batch_size_gpu: usize,
+    pub tensor_cache_size_mb: u64,
+    pub pattern_matching_on_gpu: bool,
+    pub learning_acceleration: bool,
+    pub response_generation_gpu: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub master_sigel: PathBuf,
+    pub enable_background_learning: bool,
+    pub enable_system_observation: bool,
+    pub enable_detailed_monitoring: bool,
+    pub max_active_sigels: usize,
+    pub server_port: u16,
+    /// In seconds.
+    pub auto_save_interval: u64,
+    pub backup_on_shutdown: bool,
+    pub compress_sigel_files: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InteractionSettings {
+    /// Turns kept in a conversation's history.
+    pub max_conversation_history: usize,
+    pub enable_context_memory: bool,
+    /// Share of a context phrase's relevance kept per turn.
+    pub context_memory_decay: f64,
+    pub emotional_resonance_tracking: bool,
+    pub enable_cosmic_insights: bool,
+    pub creativity_randomness: f64,
+    /// In bytes; 0 means no limit.
+    pub max_response_length: usize,
+    pub enable_mathematical_insights: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LearningSettings {
+    pub vocabulary_learning_rate: f64,
+    pub pattern_strengthening_rate: f64,
+    pub semantic_network_growth: f64,
+    pub episodic_memory_threshold: f64,
+    /// Sentences with more words than `min_sentence_length` and fewer than
+    /// `max_sentence_length` are remembered as episodic memories.
+    pub min_sentence_length: usize,
+    pub max_sentence_length: usize,
+    pub context_window_size: usize,
+    pub enable_chunked_processing: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemorySettings {
+    pub max_episodic_memories: usize,
+    pub max_vocabulary_size: usize,
+    pub max_linguistic_patterns: usize,
+    pub memory_compression_threshold: f64,
+    /// Importance above which a memory is consolidated first.
+    pub consolidation_threshold: f64,
+    pub enable_fractal_memory: bool,
+    pub emotional_weight_importance: f64,
+    pub temporal_relevance_decay: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathSettings {
+    pub sigel_storage: PathBuf,
+    pub training_data: PathBuf,
+    pub logs: PathBuf,
+    pub backups: PathBuf,
+    pub cache: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExperimentalSettings {
+    pub enable_quantum_consciousness: bool,
+    pub enable_dream_mode: bool,
+    pub enable_collective_intelligence: bool,
+    pub enable_time_dilation_perception: bool,
+}
+
+impl SigmosConfig {
+    /// The config file to use: `$SIGMOS_CONFIG` if set, otherwise `./sigmos.toml` if it exists.
+    pub fn locate() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+            return Some(PathBuf::from(path));
+        }
+        Path::new(CONFIG_FILE).exists().then(|| PathBuf::from(CONFIG_FILE))
+    }
+
+    /// Settings from the located config file, if any, and `SIGMOS_*` environment
+    /// variables. Invalid settings are reported and the defaults are used.
+    pub fn discover() -> Self {
+        match LayeredConfig::discover().and_then(|layers| layers.build()) {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("Could not load the configuration: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Parse and validate a config file. Files it names, such as the blocklist, are
+    /// relative to its directory.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut layers = LayeredConfig::new();
+        layers.load_file(path)?;
+        layers.build()
+    }
+
+    /// Parse settings without validating them.
+    pub fn parse(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// The directory that files named in the config are relative to.
+    pub fn base_dir(&self) -> PathBuf {
+        self.source.as_deref().map(config_dir).unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    /// Check that every value is in range, listing all problems found.
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut problems = Vec::new();
+
+        let fractions = [
+            ("consciousness.awareness_growth_rate", self.consciousness.awareness_growth_rate),
+            ("consciousness.max_awareness_depth", self.consciousness.max_awareness_depth),
+            ("consciousness.pattern_retention_threshold", self.consciousness.pattern_retention_threshold),
+            ("consciousness.memory_consolidation_rate", self.consciousness.memory_consolidation_rate),
+            ("cosmic.stellar_influence_weight", self.cosmic.stellar_influence_weight),
+            ("cosmic.mathematical_harmony_strength", self.cosmic.mathematical_harmony_strength),
+            ("cosmic.entropy_resistance_target", self.cosmic.entropy_resistance_target),
+            ("interaction.context_memory_decay", self.interaction.context_memory_decay),
+            ("interaction.creativity_randomness", self.interaction.creativity_randomness),
+            ("training.dedup_threshold", self.training.dedup_threshold),
+            ("learning.vocabulary_learning_rate", self.learning.vocabulary_learning_rate),
+            ("learning.pattern_strengthening_rate", self.learning.pattern_strengthening_rate),
+            ("learning.semantic_network_growth", self.learning.semantic_network_growth),
+            ("learning.episodic_memory_threshold", self.learning.episodic_memory_threshold),
+            ("memory.memory_compression_threshold", self.memory.memory_compression_threshold),
+            ("memory.consolidation_threshold", self.memory.consolidation_threshold),
+            ("memory.temporal_relevance_decay", self.memory.temporal_relevance_decay),
+            ("dream.creative_synthesis_strength", self.dream.creative_synthesis_strength),
+            ("dream.memory_consolidation_intensity", self.dream.memory_consolidation_intensity),
+            ("dream.cosmic_alignment_frequency", self.dream.cosmic_alignment_frequency),
+            ("dream.lucid_dream_probability", self.dream.lucid_dream_probability),
+            ("dream.transcendent_dream_threshold", self.dream.transcendent_dream_threshold),
+        ];
+        for (key, value) in fractions {
+            if !(0.0..=1.0).contains(&value) {
+                problems.push(format!("{} must be between 0 and 1, not {}", key, value));
+            }
+        }
+
+        let positive = [
+            ("cosmic.cosmic_alignment_interval", self.cosmic.cosmic_alignment_interval),
+            ("cosmic.evolution_interval", self.cosmic.evolution_interval),
+            ("cosmic.universal_constant_update", self.cosmic.universal_constant_update),
+            ("server.auto_save_interval", self.server.auto_save_interval),
+            ("server.max_active_sigels", self.server.max_active_sigels as u64),
+            ("interaction.max_conversation_history", self.interaction.max_conversation_history as u64),
+            ("learning.context_window_size", self.learning.context_window_size as u64),
+            ("dream.cycle_length_seconds", self.dream.cycle_length_seconds),
+            ("dream.max_cycles_per_session", self.dream.max_cycles_per_session as u64),
+        ];
+        for (key, value) in positive {
+            if value == 0 {
+                problems.push(format!("{} must be greater than 0", key));
+            }
+        }
+
+        if self.learning.min_sentence_length >= self.learning.max_sentence_length {
+            problems.push(format!("learning.min_sentence_length ({}) must be less than learning.max_sentence_length ({})",
+                self.learning.min_sentence_length, self.learning.max_sentence_length));
+        }
+        if self.memory.emotional_weight_importance < 0.0 {
+            problems.push("memory.emotional_weight_importance must not be negative".to_string());
+        }
+        if !LOG_LEVELS.contains(&self.general.log_level.to_lowercase().as_str()) {
+            problems.push(format!("general.log_level must be one of {}, not '{}'", LOG_LEVELS.join(", "), self.general.log_level));
+        }
+        if let Err(e) = self.response_filters() {
+            problems.push(format!("moderation: {}", e));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("; ").into())
+        }
+    }
+
+    /// The response filters of `[moderation]`, limited to `[interaction] max_response_length`.
+    pub fn response_filters(&self) -> Result<ResponseFilterChain, Box<dyn std::error::Error>> {
+        ResponseFilterChain::from_config(&self.moderation, self.interaction.max_response_length)
+    }
+}
+
+fn config_dir(path: &Path) -> PathBuf {
+    path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new(".")).to_path_buf()
+}
+
+impl Default for GeneralSettings {
+    fn default() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            default_sigel_directory: PathBuf::from("sigels"),
+            max_memory_size_mb: 1024,
+            log_level: "info".to_string(),
+        }
+    }
+}
+
+impl Default for TrainingSettings {
+    fn default() -> Self {
+        Self {
+            default_learning_rate: 0.01,
+            default_communication_style: "philosophical".to_string(),
+            default_batch_size: 32,
+            enable_parallel_processing: true,
+            auto_save_interval_minutes: 15,
+            deduplicate: false,
+            dedup_threshold: 0.85,
+        }
+    }
+}
+
+impl Default for ConsciousnessSettings {
+    fn default() -> Self {
+        Self {
+            awareness_growth_rate: 0.001,
+            max_awareness_depth: 1.0,
+            pattern_retention_threshold: 0.1,
+            memory_consolidation_rate: 0.99,
+            self_reflection_frequency: 50,
+        }
+    }
+}
+
+impl Default for CosmicSettings {
+    fn default() -> Self {
+        Self {
+            enable_cosmic_alignment: true,
+            stellar_influence_weight: 0.5,
+            mathematical_harmony_strength: 0.8,
+            dimensional_awareness_cap: 11.0,
+            entropy_resistance_target: 0.8,
+            cosmic_alignment_interval: 300,
+            evolution_interval: 600,
+            universal_constant_update: 1800,
+        }
+    }
+}
+
+impl Default for GpuSettings {
+    fn default() -> Self {
+        Self {
+            enable_gpu: true,
+            prefer_cuda: true,
+            prefer_metal: true,
+            fallback_to_cpu: true,
+            batch_size_gpu: 64,
+            tensor_cache_size_mb: 512,
+            pattern_matching_on_gpu: true,
+            learning_acceleration: true,
+            response_generation_gpu: false,
+        }
+    }
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            master_sigel: PathBuf::from("master.sigel"),
+            enable_background_learning: true,
+            enable_system_observation: true,
+            enable_detailed_monitoring: false,
+            max_active_sigels: 10,
+            server_port: 8080,
+            auto_save_interval: 900,
+            backup_on_shutdown: true,
+            compress_sigel_files: false,
+        }
+    }
+}
+
+impl Default for InteractionSettings {
+    fn default() -> Self {
+        Self {
+            max_conversation_history: 100,
+            enable_context_memory: true,
+            context_memory_decay: 0.99,
+            emotional_resonance_tracking: true,
+            enable_cosmic_insights: true,
+            creativity_randomness: 0.1,
+            max_response_length: crate::moderation::DEFAULT_MAX_RESPONSE_LENGTH,
+            enable_mathematical_insights: true,
+        }
+    }
+}
+
+impl Default for LearningSettings {
+    fn default() -> Self {
+        Self {
+            vocabulary_learning_rate: 0.05,
+            pattern_strengthening_rate: 0.02,
+            semantic_network_growth: 0.01,
+            episodic_memory_threshold: 0.3,
+            min_sentence_length: 5,
+            max_sentence_length: 50,
+            context_window_size: 3,
+            enable_chunked_processing: true,
+        }
+    }
+}
+
+impl Default for MemorySettings {
+    fn default() -> Self {
+        Self {
+            max_episodic_memories: 10000,
+            max_vocabulary_size: 50000,
+            max_linguistic_patterns: 20000,
+            memory_compression_threshold: 0.8,
+            consolidation_threshold: 0.7,
+            enable_fractal_memory: true,
+            emotional_weight_importance: 1.2,
+            temporal_relevance_decay: 0.95,
+        }
+    }
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            sigel_storage: PathBuf::from("./sigels"),
+            training_data: PathBuf::from("./training_data"),
+            logs: PathBuf::from("./logs"),
+            backups: PathBuf::from("./backups"),
+            cache: PathBuf::from("./cache"),
+        }
+    }
+}
+
+/// Where the effective value of a setting came from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    Default,
+    File(PathBuf),
+    /// The environment variable that set it.
+    Env(String),
+    /// The command line option that set it.
+    Cli(String),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File(path) => write!(f, "file {}", path.display()),
+            ConfigSource::Env(var) => write!(f, "env {}", var),
+            ConfigSource::Cli(option) => write!(f, "flag {}", option),
+        }
+    }
+}
+
+/// Settings built up in layers, each overriding the one before: defaults, then a config
+/// file, then `SIGMOS_*` environment variables, then command line options. Remembers
+/// where every value came from, for `--print-config`.
+///
+/// Settings are addressed as `section.key`, e.g. `server.server_port`.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    values: toml::Table,
+    sources: BTreeMap<String, ConfigSource>,
+    file: Option<PathBuf>,
+}
+
+impl LayeredConfig {
+    /// Just the defaults.
+    pub fn new() -> Self {
+        let values = toml::Table::try_from(SigmosConfig::default()).expect("the default config serializes");
+        Self { values, sources: BTreeMap::new(), file: None }
+    }
+
+    /// Defaults, then the located config file, then the environment.
+    pub fn discover() -> Result<Self, Box<dyn std::error::Error>> {
+        let mut layers = Self::new();
+        if let Some(path) = SigmosConfig::locate() {
+            layers.load_file(path)?;
+        }
+        layers.apply_env()?;
+        Ok(layers)
+    }
+
+    /// The config file loaded, if any.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let table: toml::Table = toml::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        for (section, value) in table {
+            match (value, self.values.get_mut(&section)) {
+                (toml::Value::Table(entries), Some(toml::Value::Table(current))) => {
+                    for (key, value) in entries {
+                        self.sources.insert(format!("{}.{}", section, key), ConfigSource::File(path.to_path_buf()));
+                        current.insert(key, value);
+                    }
+                },
+                (value, _) => {
+                    self.sources.insert(section.clone(), ConfigSource::File(path.to_path_buf()));
+                    self.values.insert(section, value);
+                },
+            }
+        }
+        self.file = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Apply the process's `SIGMOS_<SECTION>_<KEY>` environment variables.
+    /// Variables whose name or value is not valid UTF-8 are skipped.
+    pub fn apply_env(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let vars = std::env::vars_os().filter_map(|(name, value)| {
+            let name = name.into_string().ok()?;
+            match value.into_string() {
+                Ok(value) => Some((name, value)),
+                Err(_) => {
+                    if name.starts_with(ENV_PREFIX) {
+                        log::warn!("Ignoring {}: its value is not valid UTF-8", name);
+                    }
+                    None
+                },
+            }
+        });
+        self.apply_vars(vars)
+    }
+
+    /// Apply `SIGMOS_<SECTION>_<KEY>` variables from `vars`, ignoring all others.
+    pub fn apply_vars<I: IntoIterator<Item = (String, String)>>(&mut self, vars: I) -> Result<(), Box<dyn std::error::Error>> {
+        let mut vars: Vec<(String, String)> = vars.into_iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX) && name != CONFIG_ENV)
+            .collect();
+        vars.sort();
+
+        for (name, value) in vars {
+            let setting = name[ENV_PREFIX.len()..].to_lowercase();
+            let key = self.values.iter()
+                .filter(|(section, value)| value.is_table() && *section != "styles")
+                .find_map(|(section, _)| setting.strip_prefix(section.as_str())?.strip_prefix('_').map(|key| format!("{}.{}", section, key)))
+                .filter(|key| self.has(key));
+            match key {
+                Some(key) => self.set(&key, &value, ConfigSource::Env(name.clone()))?,
+                None => log::warn!("Ignoring {}: there is no such setting", name),
+            }
+        }
+        Ok(())
+    }
+
+    fn has(&self, key: &str) -> bool {
+        let Some((section, _)) = key.split_once('.') else {
+            return false;
+        };
+        self.value(key).is_some() || section == "styles" || OPTIONAL_KEYS.contains(&key)
+    }
+
+    fn value(&self, key: &str) -> Option<&toml::Value> {
+        let (section, name) = key.split_once('.')?;
+        self.values.get(section)?.as_table()?.get(name)
+    }
+
+    /// Override one setting. `value` is read as the type the setting already has, so
+    /// strings need no quotes.
+    pub fn set(&mut self, key: &str, value: &str, source: ConfigSource) -> Result<(), Box<dyn std::error::Error>> {
+        let Some((section, name)) = key.split_once('.') else {
+            return Err(format!("{}: settings are named section.key", key).into());
+        };
+        if !self.has(key) {
+            return Err(format!("{}: there is no such setting", key).into());
+        }
+        let Some(toml::Value::Table(entries)) = self.values.get_mut(section) else {
+            return Err(format!("{}: there is no [{}] section", key, section).into());
+        };
+
+        let parsed = parse_value(value, entries.get(name)).map_err(|e| format!("{} ({}): {}", key, source, e))?;
+        entries.insert(name.to_string(), parsed);
+        self.sources.insert(key.to_string(), source);
+        Ok(())
+    }
+
+    /// Where the effective value of `key` came from.
+    pub fn source(&self, key: &str) -> &ConfigSource {
+        self.sources.get(key).unwrap_or(&ConfigSource::Default)
+    }
+
+    /// The typed, validated settings.
+    pub fn build(&self) -> Result<SigmosConfig, Box<dyn std::error::Error>> {
+        let mut config: SigmosConfig = toml::Value::Table(self.values.clone()).try_into()?;
+
+        // A blocklist named in a file is relative to that file
+        if let (Some(blocklist_file), ConfigSource::File(path)) = (&config.moderation.blocklist_file, self.source("moderation.blocklist_file")) {
+            config.moderation.blocklist_file = Some(config_dir(path).join(blocklist_file).to_string_lossy().into_owned());
+        }
+        config.source = self.file.clone();
+        config.validate()?;
+
+        let defaults = Self::new();
+        for key in UNUSED_KEYS {
+            if self.value(key) != defaults.value(key) {
+                log::warn!("{} ({}) is not used yet and has no effect", key, self.source(key));
+            }
+        }
+        Ok(config)
+    }
+
+    /// `--config`, `--set` and `--print-config`, for every binary's command line.
+    pub fn args() -> [Arg; 3] {
+        [
+            Arg::new("config")
+                .long("config")
+                .value_name("FILE")
+                .help("Config file to use instead of $SIGMOS_CONFIG or ./sigmos.toml"),
+            Arg::new("set")
+                .long("set")
+                .value_name("SECTION.KEY=VALUE")
+                .help("Override a config setting, e.g. --set interaction.max_conversation_history=50")
+                .action(ArgAction::Append),
+            Arg::new("print_config")
+                .long("print-config")
+                .help("Print the effective configuration with the source of each value, then exit")
+                .action(ArgAction::SetTrue),
+        ]
+    }
+
+    /// Defaults, then the config file (`--config`, `$SIGMOS_CONFIG` or ./sigmos.toml),
+    /// then the environment, then `--set` options.
+    pub fn from_args(matches: &ArgMatches) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut layers = Self::new();
+        let file = matches.get_one::<String>("config").map(PathBuf::from).or_else(SigmosConfig::locate);
+        if let Some(path) = file {
+            layers.load_file(path)?;
+        }
+        layers.apply_env()?;
+
+        for setting in matches.get_many::<String>("set").into_iter().flatten() {
+            let Some((key, value)) = setting.split_once('=') else {
+                return Err(format!("--set {}: expected SECTION.KEY=VALUE", setting).into());
+            };
+            layers.set(key.trim(), value.trim(), ConfigSource::Cli("--set".to_string()))?;
+        }
+        Ok(layers)
+    }
+
+    /// For binaries: with `--print-config`, print the effective configuration and exit;
+    /// otherwise build it, exiting if it is invalid.
+    pub fn finish(self, matches: &ArgMatches) -> SigmosConfig {
+        if matches.get_flag("print_config") {
+            print!("{}", self);
+            if let Err(e) = self.build() {
+                println!("\n# Invalid: {}", e);
+                std::process::exit(1);
+            }
+            std::process::exit(0);
+        }
+        self.build().unwrap_or_else(|e| {
+            log::error!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        })
+    }
+
+    /// Override `key` with a binary's own command line option, if it was given.
+    pub fn set_flag<V: ToString>(&mut self, key: &str, option: &str, value: Option<V>) -> Result<(), Box<dyn std::error::Error>> {
+        match value {
+            Some(value) => self.set(key, &value.to_string(), ConfigSource::Cli(option.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for LayeredConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The effective configuration as TOML, each value commented with its source.
+impl fmt::Display for LayeredConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(path) => writeln!(f, "# Effective configuration, from defaults, {}, environment and flags", path.display())?,
+            None => writeln!(f, "# Effective configuration, from defaults, environment and flags (no config file)")?,
+        }
+
+        for (section, value) in &self.values {
+            match value {
+                toml::Value::Table(entries) => {
+                    writeln!(f, "\n[{}]", section)?;
+                    for (key, value) in entries {
+                        writeln!(f, "{} = {}  # {}", key, value, self.source(&format!("{}.{}", section, key)))?;
+                    }
+                },
+                value => writeln!(f, "{} = {}  # {}", section, value, self.source(section))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read `raw` as the type of `current`, or as a TOML value (falling back to a string)
+/// for settings without a default.
+fn parse_value(raw: &str, current: Option<&toml::Value>) -> Result<toml::Value, String> {
+    let literal = || raw.parse::<toml::Value>().ok()
+        .or_else(|| toml::from_str::<toml::Table>(&format!("value = {}", raw)).ok()?.remove("value"));
+
+    match current {
+        Some(toml::Value::String(_)) => Ok(toml::Value::String(raw.to_string())),
+        Some(toml::Value::Integer(_)) => raw.parse().map(toml::Value::Integer)
+            .map_err(|_| format!("expected a whole number, not '{}'", raw)),
+        Some(toml::Value::Float(_)) => raw.parse().map(toml::Value::Float)
+            .map_err(|_| format!("expected a number, not '{}'", raw)),
+        Some(toml::Value::Boolean(_)) => match raw.to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(toml::Value::Boolean(true)),
+            "false" | "0" | "no" | "off" => Ok(toml::Value::Boolean(false)),
+            _ => Err(format!("expected true or false, not '{}'", raw)),
+        },
+        // A list is TOML (`["a", "b"]`) or comma separated
+        Some(toml::Value::Array(_)) => match literal() {
+            Some(value @ toml::Value::Array(_)) => Ok(value),
+            _ => Ok(toml::Value::Array(raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| toml::Value::String(item.to_string()))
+                .collect())),
+        },
+        Some(toml::Value::Table(_)) => match literal() {
+            Some(value @ toml::Value::Table(_)) => Ok(value),
+            _ => Err(format!("expected an inline table, not '{}'", raw)),
+        },
+        _ => Ok(literal().unwrap_or_else(|| toml::Value::String(raw.to_string()))),
+    }
+}
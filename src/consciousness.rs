@@ -1,31 +1,97 @@
 use crate::sigel::*;
+use crate::language::Language;
+use crate::persona::PersonaRegistry;
+use crate::decoder::{DecodingConfig, ResponseDecoder};
+use crate::trace::{AwarenessLevel, ContextScore, ResponseTrace};
 use std::collections::HashMap;
 use rand::Rng;
+use rand::rngs::StdRng;
 
-pub struct ConsciousnessProcessor;
+pub struct ConsciousnessProcessor {
+    decoder: ResponseDecoder,
+    personas: PersonaRegistry,
+    // Shares the decoding seed, so seeded conversations are reproducible end to end
+    rng: StdRng,
+}
 
 impl ConsciousnessProcessor {
-    pub fn process_thought(&self, sigel: &mut Sigel, input: &str) -> String {
+    pub fn new() -> Self {
+        Self {
+            decoder: ResponseDecoder::default(),
+            personas: PersonaRegistry::new(),
+            rng: ResponseDecoder::rng(&DecodingConfig::default()),
+        }
+    }
+
+    pub fn personas(&self) -> &PersonaRegistry {
+        &self.personas
+    }
+
+    /// Use these personas, e.g. with styles loaded from sigmos.toml, to phrase responses.
+    pub fn set_personas(&mut self, personas: PersonaRegistry) {
+        self.personas = personas;
+    }
+
+    pub fn decoding(&self) -> &DecodingConfig {
+        self.decoder.config()
+    }
+
+    /// Configure how responses are sampled from learned patterns.
+    pub fn set_decoding(&mut self, config: DecodingConfig) {
+        self.rng = ResponseDecoder::rng(&config);
+        self.decoder.set_config(config);
+    }
+
+    /// Respond to `prompt`; `input` is the prompt enriched with conversation context.
+    pub fn process_thought(&mut self, sigel: &mut Sigel, prompt: &str, input: &str, grounding: &[String], language: Language) -> String {
+        let mut trace = ResponseTrace::default();
+        self.process_thought_traced(sigel, prompt, input, grounding, language, &mut trace)
+    }
+
+    /// Like `process_thought`, recording each stage's outcome in `trace`.
+    pub fn process_thought_traced(&mut self, sigel: &mut Sigel, prompt: &str, input: &str, grounding: &[String], language: Language, trace: &mut ResponseTrace) -> String {
+        trace.language = language;
+        self.process_thought_streaming(sigel, prompt, input, grounding, trace, &mut |_| {})
+    }
+
+    /// Like `process_thought_traced`, answering in `trace.language` and calling `on_partial`
+    /// with the start of the response whenever more of it is settled.
+    pub fn process_thought_streaming(&mut self, sigel: &mut Sigel, prompt: &str, input: &str, grounding: &[String], trace: &mut ResponseTrace, on_partial: &mut dyn FnMut(&str)) -> String {
+        let language = trace.language;
+        trace.awareness_depth = sigel.consciousness.awareness_depth;
+        trace.awareness_level = AwarenessLevel::from_depth(trace.awareness_depth);
+
         // Simulate consciousness processing through multiple layers
-        let awareness_filtered = self.awareness_filter(sigel, input);
+        let awareness_filtered = self.awareness_filter(trace.awareness_level, input);
         let pattern_matched = self.pattern_matching(&sigel.consciousness.pattern_recognition, &awareness_filtered);
-        let contextually_understood = self.contextual_processing(sigel, &pattern_matched);
-        let intuitive_enhanced = self.intuitive_processing(sigel, &contextually_understood);
+        trace.context_scores = self.contextual_processing(sigel, &pattern_matched);
+        trace.matched_patterns = pattern_matched;
+        trace.intuitive_leap = self.intuitive_processing(sigel);
         
-        self.generate_response(sigel, &intuitive_enhanced)
+        // Say something learned about the prompt, rather than echoing the processing state
+        let persona = self.personas.for_sigel(sigel);
+        let mut on_text = |text: &str| {
+            if let Some(partial) = persona.render_partial(sigel, text, language) {
+                on_partial(&partial);
+            }
+        };
+        let content = match self.decoder.generate_streaming(sigel, prompt, grounding, language, &mut on_text) {
+            Some(decoded) => {
+                trace.record_decoding(&decoded);
+                decoded.text
+            },
+            None => self.personas.for_sigel(sigel).no_knowledge(sigel, language),
+        };
+        
+        self.generate_response(sigel, &content, language, trace)
     }
 
-    fn awareness_filter(&self, sigel: &Sigel, input: &str) -> String {
-        // Filter input through consciousness depth
-        let awareness = sigel.consciousness.awareness_depth;
-        
+    fn awareness_filter(&self, level: AwarenessLevel, input: &str) -> String {
         // Higher awareness allows for deeper understanding of nuance
-        if awareness > 0.7 {
-            format!("{{deeply_perceived}} {}", input)
-        } else if awareness > 0.4 {
-            format!("{{moderately_perceived}} {}", input)
-        } else {
-            format!("{{surface_perceived}} {}", input)
+        match level {
+            AwarenessLevel::Deep => format!("{{deeply_perceived}} {}", input),
+            AwarenessLevel::Moderate => format!("{{moderately_perceived}} {}", input),
+            AwarenessLevel::Surface => format!("{{surface_perceived}} {}", input),
         }
     }
 
@@ -66,65 +132,38 @@ impl ConsciousnessProcessor {
         matches
     }
 
-    fn contextual_processing(&self, sigel: &mut Sigel, patterns: &[String]) -> String {
-        let mut context_score = 0.0;
-        let mut combined_understanding = String::new();
+    fn contextual_processing(&self, sigel: &mut Sigel, patterns: &[String]) -> Vec<ContextScore> {
+        let mut scores = Vec::new();
 
         for pattern in patterns {
-            if let Some(score) = sigel.consciousness.contextual_understanding.get(pattern) {
-                context_score += score;
-                // Store understanding internally, don't spam user
+            if let Some(&score) = sigel.consciousness.contextual_understanding.get(pattern) {
+                scores.push(ContextScore { pattern: pattern.clone(), score, new: false });
             } else {
                 // Learn new context silently
                 sigel.consciousness.contextual_understanding.insert(pattern.clone(), 0.1);
-                context_score += 0.1; // Add new pattern score
+                scores.push(ContextScore { pattern: pattern.clone(), score: 0.1, new: true });
             }
         }
 
-        format!("context_understanding:{:.2} {}", context_score, combined_understanding)
+        scores
     }
 
-    fn intuitive_processing(&self, sigel: &Sigel, context: &str) -> String {
+    fn intuitive_processing(&mut self, sigel: &Sigel) -> bool {
         let intuition = sigel.consciousness.intuitive_leaps;
-        let mut rng = rand::thread_rng();
 
-        if intuition > 0.6 && rng.gen::<f64>() < intuition {
-            // Make an intuitive leap
-            format!("{{intuitive_insight}} {}", context)
-        } else {
-            context.to_string()
-        }
+        intuition > 0.6 && self.rng.gen::<f64>() < intuition
     }
 
-    fn generate_response(&self, sigel: &Sigel, processed_input: &str) -> String {
-        // Generate response based on essence and communication style
-        let response_base = match sigel.essence.communication_style {
-            CommunicationStyle::Cosmic => {
-                format!("From the cosmic perspective, considering the stellar alignments and universal harmonics: {}", processed_input)
-            },
-            CommunicationStyle::Philosophical => {
-                format!("In contemplating this deeply, one might consider: {}", processed_input)
-            },
-            CommunicationStyle::Creative => {
-                format!("Ah, this sparks creative visions within my essence! {}", processed_input)
-            },
-            CommunicationStyle::Analytical => {
-                format!("Analyzing the logical structure and patterns: {}", processed_input)
-            },
-            _ => format!("I perceive and understand: {}", processed_input),
-        };
-
-        // Apply character traits to response
-        let creativity = sigel.essence.character_traits.get("creativity").unwrap_or(&0.5);
-        let wisdom = sigel.essence.character_traits.get("wisdom").unwrap_or(&0.5);
+    fn generate_response(&self, sigel: &Sigel, content: &str, language: Language, trace: &mut ResponseTrace) -> String {
+        // Phrase the response the way the Sigel's persona speaks, in the prompt's language
+        let persona = self.personas.for_sigel(sigel);
+        let rendered = persona.render(sigel, content, language);
 
-        if *creativity > 0.7 {
-            format!("{{creatively}} {}", response_base)
-        } else if *wisdom > 0.8 {
-            format!("{{wisely}} {}", response_base)
-        } else {
-            response_base
-        }
+        trace.style = Some(sigel.essence.communication_style.clone());
+        trace.persona = Some(persona.name.clone());
+        trace.style_template = Some(rendered.template);
+        trace.trait_rules = rendered.rules;
+        rendered.text
     }
 
     pub fn self_reflection(&self, sigel: &mut Sigel, interaction_history: &[String]) -> String {
@@ -148,4 +187,10 @@ impl ConsciousnessProcessor {
             "I am aware of my existence and my purpose to learn and communicate.".to_string()
         }
     }
-}
\ No newline at end of file
+}
+
+impl Default for ConsciousnessProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,519 @@
+use crate::sigel::*;
+use crate::language::{is_stopword, Language};
+use crate::learning::LearningEngine;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+const MAX_CONTEXT: usize = 3;
+// Weight of each shorter context relative to the next longer one when backing off
+const BACKOFF: f64 = 0.4;
+const OPENING_CANDIDATES: usize = 20;
+const RELATED_WORDS: usize = 20;
+const MIN_RELATED_SIMILARITY: f64 = 0.3;
+const GROUNDING_RELEVANCE: f64 = 0.6;
+
+/// Sampling settings for `ResponseDecoder`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodingConfig {
+    /// Below 1.0 favours likely words, above 1.0 flattens the distribution; 0.0 is greedy.
+    pub temperature: f64,
+    /// Sample only from the `top_k` most likely words; 0 disables the limit.
+    pub top_k: usize,
+    /// Sample from the smallest set of words whose probabilities add up to `top_p`.
+    pub top_p: f64,
+    pub max_tokens: usize,
+    /// Sentences may not end before they have this many words.
+    pub min_tokens: usize,
+    pub max_sentences: usize,
+    /// Divides a content word's score once for every time it was already used.
+    pub repetition_penalty: f64,
+    /// Never repeat an n-gram of this size; 0 disables the check.
+    pub no_repeat_ngram: usize,
+    /// How strongly the prompt's keywords and their associations are preferred.
+    pub keyword_bias: f64,
+    /// Generation stops before emitting any of these words.
+    pub stop_words: Vec<String>,
+    /// Fixed seed for reproducible output; `None` seeds from entropy.
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopReason {
+    EndOfSentence,
+    MaxTokens,
+    StopWord,
+    /// Nothing was ever learned to follow the last words.
+    DeadEnd,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecodedResponse {
+    pub text: String,
+    pub keywords: Vec<String>,
+    pub tokens: usize,
+    pub stop_reason: StopReason,
+}
+
+/// Next-word statistics built from a Sigel's learned n-grams.
+///
+/// A context of `n` words is followed by the last word of each learned `n + 1`-gram, so
+/// bigrams give one-word contexts and 4-grams give three-word contexts.
+#[derive(Debug, Clone, Default)]
+pub struct NgramModel {
+    successors: HashMap<String, HashMap<String, f64>>,
+    totals: HashMap<String, f64>,
+    /// Times each word was seen lowercase and capitalized, away from the start of an n-gram.
+    forms: HashMap<String, (f64, f64)>,
+    /// How often each word started and ended a training sentence.
+    starts: HashMap<String, f64>,
+    ends: HashMap<String, f64>,
+    ngrams: Vec<(Vec<String>, f64)>,
+}
+
+impl NgramModel {
+    /// Build from the n-grams learned in `language`, or from all n-grams for Sigels
+    /// trained before per-language partitions existed.
+    pub fn build(sigel: &Sigel, language: Language) -> Self {
+        let mut model = Self::default();
+        let Some(patterns) = Self::patterns(sigel, language) else { return model };
+
+        // Sorted so sums, and therefore seeded output, don't depend on map order
+        let mut patterns: Vec<(&String, &f64)> = patterns.iter().collect();
+        patterns.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (pattern, &strength) in patterns {
+            let surface: Vec<&str> = pattern.split_whitespace().collect();
+            let words: Vec<String> = surface.iter().map(|w| LearningEngine::association_word(w)).collect();
+            if words.len() < 2 || words.iter().any(|w| w.is_empty()) {
+                continue;
+            }
+
+            // Pattern strengths are occurrences divided by n
+            let count = strength * words.len() as f64;
+            let (last, context) = words.split_last().unwrap();
+            let context = context.join(" ");
+            *model.successors.entry(context.clone()).or_default().entry(last.clone()).or_insert(0.0) += count;
+            *model.totals.entry(context).or_insert(0.0) += count;
+
+            for (word, form) in words.iter().zip(&surface).skip(1) {
+                let counts = model.forms.entry(word.clone()).or_insert((0.0, 0.0));
+                if form.trim_start_matches(|c: char| !c.is_alphabetic()).starts_with(char::is_uppercase) {
+                    counts.1 += count;
+                } else {
+                    counts.0 += count;
+                }
+            }
+            model.forms.entry(words[0].clone()).or_insert((0.0, 0.0));
+            model.ngrams.push((words, count));
+        }
+
+        for memory in sigel.memory.episodic_memories.iter().filter(|m| m.source_id.is_some()) {
+            let words: Vec<String> = memory.content.split_whitespace()
+                .map(LearningEngine::association_word)
+                .filter(|w| !w.is_empty())
+                .collect();
+            if let (Some(first), Some(last)) = (words.first(), words.last()) {
+                if model.forms.contains_key(first) {
+                    *model.starts.entry(first.clone()).or_insert(0.0) += 1.0;
+                }
+                if model.forms.contains_key(last) {
+                    *model.ends.entry(last.clone()).or_insert(0.0) += 1.0;
+                }
+            }
+        }
+
+        model
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ngrams.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ngrams.len()
+    }
+
+    fn patterns(sigel: &Sigel, language: Language) -> Option<&HashMap<String, f64>> {
+        let languages = &sigel.memory.semantic_knowledge.languages;
+        if languages.is_empty() {
+            Some(&sigel.consciousness.pattern_recognition.linguistic_patterns)
+        } else {
+            languages.get(&language).map(|partition| &partition.patterns)
+        }
+    }
+
+    /// Probability that a sentence ends after `word`.
+    fn end_probability(&self, word: &str) -> f64 {
+        let ends = self.ends.get(word).copied().unwrap_or(0.0);
+        let continues = self.totals.get(word).copied().unwrap_or(0.0);
+        if ends + continues > 0.0 { ends / (ends + continues) } else { 1.0 }
+    }
+
+    fn surface(&self, word: &str) -> String {
+        match self.forms.get(word) {
+            Some(&(lower, upper)) if upper > lower => capitalize(word),
+            _ => word.to_string(),
+        }
+    }
+}
+
+/// Generates responses by sampling from a Sigel's learned n-grams, steered towards
+/// the prompt's keywords and the words associated with them.
+pub struct ResponseDecoder {
+    config: DecodingConfig,
+    rng: StdRng,
+    model: Option<(ModelKey, NgramModel)>,
+}
+
+// Identifies the knowledge a cached model was built from
+type ModelKey = (Uuid, Language, usize, usize);
+
+impl ResponseDecoder {
+    pub fn new(config: DecodingConfig) -> Self {
+        let rng = Self::rng(&config);
+        Self {
+            config,
+            rng,
+            model: None,
+        }
+    }
+
+    pub fn config(&self) -> &DecodingConfig {
+        &self.config
+    }
+
+    /// Replace the sampling settings, reseeding if the new settings fix a seed.
+    pub fn set_config(&mut self, config: DecodingConfig) {
+        self.rng = Self::rng(&config);
+        self.config = config;
+    }
+
+    /// Generate a response to `prompt` in `language`, or `None` if the Sigel has learned
+    /// nothing related to it.
+    pub fn generate(&mut self, sigel: &Sigel, prompt: &str, language: Language) -> Option<DecodedResponse> {
+        self.generate_grounded(sigel, prompt, &[], language)
+    }
+
+    /// Like `generate`, but also steer towards the words of `grounding`, such as
+    /// memories retrieved for the prompt, best first.
+    pub fn generate_grounded(&mut self, sigel: &Sigel, prompt: &str, grounding: &[String], language: Language) -> Option<DecodedResponse> {
+        self.generate_streaming(sigel, prompt, grounding, language, &mut |_| {})
+    }
+
+    /// Like `generate_grounded`, calling `on_text` with the text written so far whenever
+    /// a word is settled. Each such text is the start of the final one.
+    pub fn generate_streaming(&mut self, sigel: &Sigel, prompt: &str, grounding: &[String], language: Language, on_text: &mut dyn FnMut(&str)) -> Option<DecodedResponse> {
+        let keywords = keywords(prompt);
+        let related = related_words(sigel, &keywords, grounding);
+        if related.is_empty() {
+            return None;
+        }
+
+        let key = (
+            sigel.id,
+            language,
+            NgramModel::patterns(sigel, language).map_or(0, |p| p.len()),
+            sigel.learning_state.text_corpus_size,
+        );
+        if self.model.as_ref().is_none_or(|(cached, _)| *cached != key) {
+            self.model = Some((key, NgramModel::build(sigel, language)));
+        }
+        let model = &self.model.as_ref().unwrap().1;
+
+        let config = &self.config;
+        let rng = &mut self.rng;
+        let mut sentences: Vec<Vec<String>> = Vec::new();
+        let mut used_openings = HashSet::new();
+        let mut tokens = 0;
+        let mut stop_reason = StopReason::DeadEnd;
+
+        'sentences: while sentences.len() < config.max_sentences.max(1) {
+            let written: Vec<String> = sentences.concat();
+            let Some(opening) = choose_opening(model, &related, &used_openings, &written, config, rng) else { break };
+            used_openings.insert(opening);
+            let mut sentence = model.ngrams[opening].0.clone();
+
+            loop {
+                if tokens + sentence.len() >= config.max_tokens {
+                    sentence.truncate(config.max_tokens.saturating_sub(tokens));
+                    stop_reason = StopReason::MaxTokens;
+                    tokens += sentence.len();
+                    sentences.push(sentence);
+                    break 'sentences;
+                }
+                // Below the token limit, no word written so far can be cut any more
+                on_text(&write_sentences(model, &sentences, Some(&sentence)));
+
+                let candidates = next_words(model, &written, &sentence, &related, config);
+                if candidates.is_empty() {
+                    stop_reason = StopReason::DeadEnd;
+                    break;
+                }
+                match sample(candidates, config, rng) {
+                    None => {
+                        stop_reason = StopReason::EndOfSentence;
+                        break;
+                    },
+                    Some(word) if config.stop_words.iter().any(|stop| stop.eq_ignore_ascii_case(&word)) => {
+                        stop_reason = StopReason::StopWord;
+                        tokens += sentence.len();
+                        sentences.push(sentence);
+                        break 'sentences;
+                    },
+                    Some(word) => sentence.push(word),
+                }
+            }
+
+            tokens += sentence.len();
+            sentences.push(sentence);
+        }
+
+        sentences.retain(|sentence| !sentence.is_empty());
+        if sentences.is_empty() {
+            return None;
+        }
+
+        let text = write_sentences(model, &sentences, None);
+
+        Some(DecodedResponse {
+            text,
+            keywords,
+            tokens,
+            stop_reason,
+        })
+    }
+
+    /// A generator seeded from `config.seed`, or from entropy without one.
+    pub(crate) fn rng(config: &DecodingConfig) -> StdRng {
+        match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
+impl Default for DecodingConfig {
+    fn default() -> Self {
+        Self {
+            temperature: 0.8,
+            top_k: 40,
+            top_p: 0.9,
+            max_tokens: 40,
+            min_tokens: 5,
+            max_sentences: 2,
+            repetition_penalty: 1.5,
+            no_repeat_ngram: 3,
+            keyword_bias: 2.0,
+            stop_words: Vec::new(),
+            seed: None,
+        }
+    }
+}
+
+impl Default for ResponseDecoder {
+    fn default() -> Self {
+        Self::new(DecodingConfig::default())
+    }
+}
+
+/// The finished sentences, each ending in a full stop, then the words of `unfinished`.
+fn write_sentences(model: &NgramModel, sentences: &[Vec<String>], unfinished: Option<&Vec<String>>) -> String {
+    let write = |sentence: &Vec<String>| capitalize(&sentence.iter().map(|w| model.surface(w)).collect::<Vec<_>>().join(" "));
+    sentences.iter()
+        .map(|sentence| format!("{}.", write(sentence)))
+        .chain(unfinished.map(write))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Content words of the prompt, normalized and in order of first appearance.
+fn keywords(prompt: &str) -> Vec<String> {
+    let mut keywords = Vec::new();
+    for word in prompt.split_whitespace().map(LearningEngine::association_word) {
+        if word.chars().count() >= 2 && !is_stopword(&word) && !keywords.contains(&word) {
+            keywords.push(word);
+        }
+    }
+    keywords
+}
+
+/// Keywords and the words associated with them or found in the grounding texts,
+/// with relevance from 0.0 to 1.0.
+fn related_words(sigel: &Sigel, keywords: &[String], grounding: &[String]) -> HashMap<String, f64> {
+    let networks = &sigel.consciousness.pattern_recognition.semantic_networks;
+    let vectors = &sigel.memory.semantic_knowledge.word_vectors;
+    let mut related: HashMap<String, f64> = HashMap::new();
+    let mut relate = |word: &str, relevance: f64| {
+        if !is_stopword(word) {
+            let entry = related.entry(word.to_string()).or_insert(0.0);
+            *entry = entry.max(relevance);
+        }
+    };
+
+    for keyword in keywords {
+        relate(keyword, 1.0);
+
+        if let Some(neighbors) = networks.get(keyword) {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for neighbor in neighbors {
+                *counts.entry(neighbor).or_insert(0) += 1;
+            }
+            let max = counts.values().copied().max().unwrap_or(1) as f64;
+            for (neighbor, count) in counts {
+                relate(neighbor, 0.5 * count as f64 / max);
+            }
+        }
+
+        for (word, similarity) in vectors.similar_words(keyword, RELATED_WORDS) {
+            if similarity >= MIN_RELATED_SIMILARITY {
+                relate(&word, 0.5 * similarity);
+            }
+        }
+    }
+
+    for (rank, text) in grounding.iter().enumerate() {
+        for word in self::keywords(text) {
+            relate(&word, GROUNDING_RELEVANCE / (rank + 1) as f64);
+        }
+    }
+
+    related
+}
+
+/// Pick a learned n-gram to begin a sentence with, favouring ones that mention
+/// keywords not yet used and that start training sentences.
+fn choose_opening(model: &NgramModel, related: &HashMap<String, f64>, used: &HashSet<usize>, written: &[String], config: &DecodingConfig, rng: &mut StdRng) -> Option<usize> {
+    let mut candidates: Vec<(usize, f64)> = model.ngrams.iter()
+        .enumerate()
+        .filter(|(idx, _)| !used.contains(idx))
+        .filter_map(|(idx, (words, count))| {
+            let mut fresh: Vec<&String> = words.iter().filter(|w| !written.contains(w)).collect();
+            fresh.sort();
+            fresh.dedup();
+            let relevance: f64 = fresh.into_iter().filter_map(|w| related.get(w)).sum();
+            if relevance < 1.0 {
+                return None;
+            }
+            let start_bonus = if model.starts.contains_key(&words[0]) { 3.0 } else { 1.0 };
+            Some((idx, relevance * count.ln_1p() * start_bonus * words.len() as f64))
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    candidates.truncate(OPENING_CANDIDATES);
+    sample(candidates.into_iter().map(|(idx, score)| (Some(idx), score)).collect(), config, rng)
+}
+
+/// Candidate next words with probabilities; `None` stands for ending the sentence.
+fn next_words(model: &NgramModel, written: &[String], sentence: &[String], related: &HashMap<String, f64>, config: &DecodingConfig) -> Vec<(Option<String>, f64)> {
+    let mut scores: HashMap<&str, f64> = HashMap::new();
+    let mut weight = 1.0;
+    let mut norm = 0.0;
+    for length in (1..=MAX_CONTEXT.min(sentence.len())).rev() {
+        let context = sentence[sentence.len() - length..].join(" ");
+        if let Some(next) = model.successors.get(&context) {
+            let total = model.totals[&context];
+            for (word, count) in next {
+                *scores.entry(word).or_insert(0.0) += weight * count / total;
+            }
+            norm += weight;
+        }
+        weight *= BACKOFF;
+    }
+
+    let all_words: Vec<&String> = written.iter().chain(sentence).collect();
+    let banned = repeated_ngram_endings(&all_words, config.no_repeat_ngram);
+
+    // Sorted so the sums below, and therefore seeded output, don't depend on map order
+    let mut scores: Vec<(&str, f64)> = scores.into_iter().collect();
+    scores.sort_by(|a, b| a.0.cmp(b.0));
+    let mut candidates: Vec<(Option<String>, f64)> = scores.into_iter()
+        .filter(|(word, _)| !banned.contains(word))
+        .map(|(word, score)| {
+            let mut score = score / norm * (1.0 + config.keyword_bias * related.get(word).copied().unwrap_or(0.0));
+            if !is_stopword(word) {
+                let uses = all_words.iter().filter(|w| w.as_str() == word).count();
+                score /= config.repetition_penalty.max(1.0).powi(uses as i32);
+            }
+            (Some(word.to_string()), score)
+        })
+        .collect();
+
+    let total: f64 = candidates.iter().map(|(_, score)| score).sum();
+    if sentence.len() >= config.min_tokens {
+        let end = model.end_probability(sentence.last().unwrap());
+        for candidate in &mut candidates {
+            candidate.1 *= (1.0 - end) / total;
+        }
+        candidates.push((None, end));
+    } else if total > 0.0 {
+        for candidate in &mut candidates {
+            candidate.1 /= total;
+        }
+    }
+
+    candidates.retain(|(_, p)| *p > 0.0);
+    candidates
+}
+
+/// Words that would complete an n-gram already present in `words`.
+fn repeated_ngram_endings<'a>(words: &[&'a String], n: usize) -> HashSet<&'a str> {
+    if n < 2 || words.len() < n {
+        return HashSet::new();
+    }
+    let prefix = &words[words.len() - (n - 1)..];
+    words.windows(n)
+        .filter(|window| window[..n - 1] == *prefix)
+        .map(|window| window[n - 1].as_str())
+        .collect()
+}
+
+/// Sample from weighted choices after applying temperature, top-k and top-p.
+fn sample<T: Ord + Clone>(mut choices: Vec<(Option<T>, f64)>, config: &DecodingConfig, rng: &mut StdRng) -> Option<T> {
+    choices.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let first = choices.first()?.0.clone();
+    if config.temperature <= 0.0 {
+        return first;
+    }
+
+    for choice in &mut choices {
+        choice.1 = choice.1.powf(1.0 / config.temperature);
+    }
+    if config.top_k > 0 {
+        choices.truncate(config.top_k);
+    }
+
+    let total: f64 = choices.iter().map(|(_, weight)| weight).sum();
+    if total <= 0.0 {
+        return first;
+    }
+    let mut cumulative = 0.0;
+    let keep = choices.iter()
+        .position(|(_, weight)| {
+            cumulative += weight / total;
+            cumulative >= config.top_p
+        })
+        .map_or(choices.len(), |idx| idx + 1);
+    choices.truncate(keep);
+
+    let total: f64 = choices.iter().map(|(_, weight)| weight).sum();
+    let mut target = rng.gen::<f64>() * total;
+    for (choice, weight) in &choices {
+        target -= weight;
+        if target <= 0.0 {
+            return choice.clone();
+        }
+    }
+    choices.last().and_then(|(choice, _)| choice.clone())
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,317 @@
+use crate::sigel::*;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+// Fixed so signatures, and therefore skip decisions, are stable across runs
+const MINHASH_SEED: u64 = 0x5167_4d05_de0d_0001;
+
+/// Settings for MinHash near-duplicate detection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// Estimated Jaccard similarity at or above which two texts count as duplicates.
+    pub threshold: f64,
+    /// Characters per shingle.
+    pub shingle_size: usize,
+    pub num_hashes: usize,
+    /// LSH bands; `num_hashes / bands` rows each. More bands find lower-similarity candidates.
+    pub bands: usize,
+    /// Sentences shorter than this are never treated as boilerplate.
+    pub min_sentence_words: usize,
+    /// Cap on individual entries kept in a `DedupReport`; counts are always exact.
+    pub max_report_entries: usize,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.85,
+            shingle_size: 5,
+            num_hashes: 64,
+            bands: 16,
+            min_sentence_words: 8,
+            max_report_entries: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DedupKind {
+    Document,
+    Sentence,
+    Memory,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedText {
+    pub kind: DedupKind,
+    pub source: String,
+    pub duplicate_of: String,
+    pub similarity: f64,
+    pub preview: String,
+}
+
+/// What deduplication skipped during training or removed during cleanup.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DedupReport {
+    pub documents_checked: usize,
+    pub documents_skipped: usize,
+    pub sentences_checked: usize,
+    pub sentences_skipped: usize,
+    pub memories_checked: usize,
+    pub memories_removed: usize,
+    pub skipped: Vec<SkippedText>,
+}
+
+impl DedupReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "documents skipped: {}/{}, sentences skipped: {}/{}, memories removed: {}/{}",
+            self.documents_skipped, self.documents_checked,
+            self.sentences_skipped, self.sentences_checked,
+            self.memories_removed, self.memories_checked
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateMatch {
+    pub duplicate_of: String,
+    pub similarity: f64,
+}
+
+/// Near-duplicate detector based on MinHash signatures over character shingles,
+/// indexed with locality-sensitive hashing so each check only compares against likely matches.
+///
+/// Texts are remembered in the order they are checked, so the first copy always wins.
+pub struct Deduplicator {
+    config: DedupConfig,
+    seeds: Vec<u64>,
+    labels: Vec<String>,
+    signatures: Vec<Vec<u64>>,
+    buckets: HashMap<(usize, u64), Vec<usize>>,
+    report: DedupReport,
+}
+
+impl Deduplicator {
+    pub fn new(config: DedupConfig) -> Self {
+        let mut state = MINHASH_SEED;
+        let seeds = (0..config.num_hashes.max(1)).map(|_| splitmix64(&mut state)).collect();
+
+        Self {
+            config,
+            seeds,
+            labels: Vec::new(),
+            signatures: Vec::new(),
+            buckets: HashMap::new(),
+            report: DedupReport::default(),
+        }
+    }
+
+    pub fn with_threshold(threshold: f64) -> Self {
+        Self::new(DedupConfig {
+            threshold: threshold.clamp(0.0, 1.0),
+            ..DedupConfig::default()
+        })
+    }
+
+    pub fn config(&self) -> &DedupConfig {
+        &self.config
+    }
+
+    pub fn report(&self) -> &DedupReport {
+        &self.report
+    }
+
+    pub fn into_report(self) -> DedupReport {
+        self.report
+    }
+
+    /// MinHash signature of a text. Pure, so it can be computed on any thread.
+    pub fn signature(&self, text: &str) -> Vec<u64> {
+        let shingles = self.shingle_hashes(text);
+        self.seeds.iter()
+            .map(|&seed| {
+                shingles.iter()
+                    .map(|&shingle| mix(shingle ^ seed))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect()
+    }
+
+    /// Whether a sentence is long enough to be considered for sentence-level deduplication.
+    pub fn is_checkable_sentence(&self, sentence: &str) -> bool {
+        sentence.split_whitespace().count() >= self.config.min_sentence_words
+    }
+
+    /// Check a document against everything seen so far, remembering it if it is new.
+    pub fn check_document(&mut self, text: &str, source: &str) -> Option<DuplicateMatch> {
+        let signature = self.signature(text);
+        self.check_signature(DedupKind::Document, signature, source, text)
+    }
+
+    /// Check a sentence, ignoring ones too short to be boilerplate.
+    pub fn check_sentence(&mut self, sentence: &str, source: &str) -> Option<DuplicateMatch> {
+        if !self.is_checkable_sentence(sentence) {
+            return None;
+        }
+        let signature = self.signature(sentence);
+        self.check_signature(DedupKind::Sentence, signature, source, sentence)
+    }
+
+    /// Check a precomputed signature, recording the outcome in the report.
+    pub fn check_signature(&mut self, kind: DedupKind, signature: Vec<u64>, source: &str, text: &str) -> Option<DuplicateMatch> {
+        match kind {
+            DedupKind::Document => self.report.documents_checked += 1,
+            DedupKind::Sentence => self.report.sentences_checked += 1,
+            DedupKind::Memory => self.report.memories_checked += 1,
+        }
+
+        match self.find_duplicate(&signature) {
+            Some(found) => {
+                match kind {
+                    DedupKind::Document => self.report.documents_skipped += 1,
+                    DedupKind::Sentence => self.report.sentences_skipped += 1,
+                    DedupKind::Memory => self.report.memories_removed += 1,
+                }
+                if self.report.skipped.len() < self.config.max_report_entries {
+                    self.report.skipped.push(SkippedText {
+                        kind,
+                        source: source.to_string(),
+                        duplicate_of: found.duplicate_of.clone(),
+                        similarity: found.similarity,
+                        preview: text.trim().chars().take(80).collect(),
+                    });
+                }
+                Some(found)
+            },
+            None => {
+                self.insert(signature, source.to_string());
+                None
+            },
+        }
+    }
+
+    /// Remove near-duplicate episodic memories, keeping the earliest copy of each.
+    ///
+    /// The kept memory inherits the strongest relevance and emotional weight of its duplicates.
+    pub fn clean_memories(&self, sigel: &mut Sigel) -> DedupReport {
+        let mut index = Deduplicator::new(self.config.clone());
+        let memories = std::mem::take(&mut sigel.memory.episodic_memories);
+        let mut kept: Vec<EpisodicMemory> = Vec::with_capacity(memories.len());
+        let mut kept_by_label: HashMap<String, usize> = HashMap::new();
+
+        for memory in memories {
+            let label = memory.id.to_string();
+            let signature = index.signature(&memory.content);
+            match index.check_signature(DedupKind::Memory, signature, &label, &memory.content) {
+                Some(found) => {
+                    let original = &mut kept[kept_by_label[&found.duplicate_of]];
+                    original.relevance_score = original.relevance_score.max(memory.relevance_score);
+                    if memory.emotional_weight.abs() > original.emotional_weight.abs() {
+                        original.emotional_weight = memory.emotional_weight;
+                    }
+                },
+                None => {
+                    kept_by_label.insert(label, kept.len());
+                    kept.push(memory);
+                },
+            }
+        }
+
+        sigel.memory.episodic_memories = kept;
+        sigel.refresh_memory_index();
+        index.into_report()
+    }
+
+    fn find_duplicate(&self, signature: &[u64]) -> Option<DuplicateMatch> {
+        let mut best: Option<(usize, f64)> = None;
+
+        for (band, key) in self.band_keys(signature) {
+            let Some(candidates) = self.buckets.get(&(band, key)) else { continue };
+            for &idx in candidates {
+                let similarity = estimate_similarity(signature, &self.signatures[idx]);
+                // Earliest text wins ties so results don't depend on bucket order
+                let better = best.is_none_or(|(best_idx, best_sim)| similarity > best_sim || (similarity == best_sim && idx < best_idx));
+                if similarity >= self.config.threshold && better {
+                    best = Some((idx, similarity));
+                }
+            }
+        }
+
+        best.map(|(idx, similarity)| DuplicateMatch {
+            duplicate_of: self.labels[idx].clone(),
+            similarity,
+        })
+    }
+
+    fn insert(&mut self, signature: Vec<u64>, label: String) {
+        let idx = self.signatures.len();
+        for key in self.band_keys(&signature) {
+            self.buckets.entry(key).or_default().push(idx);
+        }
+        self.signatures.push(signature);
+        self.labels.push(label);
+    }
+
+    fn band_keys(&self, signature: &[u64]) -> Vec<(usize, u64)> {
+        let rows = (signature.len() / self.config.bands.max(1)).max(1);
+        signature.chunks(rows)
+            .enumerate()
+            .map(|(band, rows)| {
+                let mut hasher = DefaultHasher::new();
+                rows.hash(&mut hasher);
+                (band, hasher.finish())
+            })
+            .collect()
+    }
+
+    fn shingle_hashes(&self, text: &str) -> Vec<u64> {
+        let normalized: Vec<char> = text
+            .to_lowercase()
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .collect();
+
+        let size = self.config.shingle_size.max(1);
+        if normalized.len() <= size {
+            return vec![hash_chars(&normalized)];
+        }
+
+        normalized.windows(size).map(hash_chars).collect()
+    }
+}
+
+impl Default for Deduplicator {
+    fn default() -> Self {
+        Self::new(DedupConfig::default())
+    }
+}
+
+fn estimate_similarity(a: &[u64], b: &[u64]) -> f64 {
+    let matching = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    matching as f64 / a.len().max(1) as f64
+}
+
+fn hash_chars(chars: &[char]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    chars.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    mix(*state)
+}
+
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,278 @@
+use crate::sigel::{RelationType, Sigel};
+use crate::language::is_stopword;
+use serde::{Deserialize, Serialize};
+
+/// Salience kept by every topic from one turn to the next.
+const SALIENCE_DECAY: f64 = 0.6;
+const MIN_SALIENCE: f64 = 0.05;
+const MAX_TOPICS: usize = 8;
+
+// What a mention adds to a topic's salience
+const CONCEPT_MENTION: f64 = 1.0;
+const WORD_MENTION: f64 = 0.6;
+/// The Sigel's own replies count for less than what the user brings up, and only
+/// through concepts, since their phrasing comes from the persona.
+const RESPONSE_WEIGHT: f64 = 0.3;
+
+const PRONOUNS: &[&str] = &["it", "its", "they", "them", "their", "he", "him", "his", "she", "her"];
+const PERSONAL_PRONOUNS: &[&str] = &["he", "him", "his", "she", "her"];
+
+// A concept is a person when it is one of these
+const PERSON_CATEGORIES: &[&str] = &["person", "people", "human", "man", "woman", "boy", "girl", "child"];
+
+// "this" and "that" are often determiners or conjunctions, so they are only
+// resolved at the end of a clause ("tell me more about that")
+const DEMONSTRATIVES: &[&str] = &["this", "that", "these", "those"];
+
+/// Words that make up follow-ups such as "why?", "how so?" or "tell me more".
+const FOLLOW_UP_WORDS: &[&str] = &[
+    "why", "how", "so", "more", "tell", "me", "please", "go", "on", "and", "then", "really",
+    "explain", "elaborate", "continue", "what", "about", "else", "example", "examples",
+    "further", "detail", "details", "can", "you", "could", "give", "an", "a", "some", "is",
+    "does", "do", "exactly", "other", "again",
+];
+
+// Conversational words that are rarely what a conversation is about
+const FILLER_WORDS: &[&str] = &[
+    "think", "know", "like", "want", "need", "use", "used", "mean", "means", "say", "said",
+    "talk", "thing", "things", "something", "anything", "great", "good", "bad", "nice",
+    "interesting", "sure", "yes", "okay", "thanks", "thank", "hello",
+];
+
+/// Follow-up words that ask for more on the current topic.
+const ELLIPSIS_CUES: &[&str] = &[
+    "why", "how", "more", "explain", "elaborate", "continue", "example", "examples", "further",
+    "details", "detail", "else", "exactly",
+];
+
+/// An entity under discussion and how prominent it currently is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveTopic {
+    pub entity: String,
+    pub salience: f64,
+    pub mentions: u32,
+    pub last_turn: u64,
+    /// Whether "he" or "she" can refer to it: a word the Sigel has no concept for, or
+    /// a concept that is a person.
+    #[serde(default)]
+    pub personal: bool,
+}
+
+/// Input with its references to earlier turns spelled out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedInput {
+    pub text: String,
+    /// The topic that a pronoun or elliptical follow-up referred to.
+    pub referent: Option<String>,
+}
+
+/// What a conversation is currently about: a stack of topics whose salience decays
+/// each turn unless they are mentioned again.
+///
+/// Pronouns ("what is it used for?") and follow-ups that leave the topic out
+/// ("why?", "tell me more") resolve to the most salient topic. Reference resolution
+/// currently knows English pronouns only.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DialogueState {
+    /// Most salient first.
+    pub topics: Vec<ActiveTopic>,
+    pub turn: u64,
+}
+
+impl DialogueState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The most salient topic.
+    pub fn focus(&self) -> Option<&ActiveTopic> {
+        self.topics.first()
+    }
+
+    /// The most salient topic "he" or "she" can refer to.
+    pub fn personal_focus(&self) -> Option<&ActiveTopic> {
+        self.topics.iter().find(|topic| topic.personal)
+    }
+
+    /// Replace pronouns in `input` with the topic in focus, or name the topic when the
+    /// input is a follow-up that leaves it out. "He" and "she" only refer to topics that
+    /// may be people.
+    pub fn resolve(&self, input: &str) -> ResolvedInput {
+        let Some(focus) = self.focus() else {
+            return ResolvedInput { text: input.to_string(), referent: None };
+        };
+
+        let tokens: Vec<&str> = input.split_whitespace().collect();
+        let mut referent: Option<&ActiveTopic> = None;
+        let words: Vec<String> = tokens.iter().enumerate().map(|(i, token)| {
+            let (word, punctuation) = split_punctuation(token);
+            let lower = word.to_lowercase();
+            let clause_end = !punctuation.is_empty() || i + 1 == tokens.len();
+
+            let topic = if PERSONAL_PRONOUNS.contains(&lower.as_str()) {
+                self.personal_focus()
+            } else if PRONOUNS.contains(&lower.as_str()) || (clause_end && DEMONSTRATIVES.contains(&lower.as_str())) {
+                Some(focus)
+            } else {
+                None
+            };
+            let Some(topic) = topic else {
+                return token.to_string();
+            };
+
+            referent.get_or_insert(topic);
+            // "its" and "their" keep their possessive; "her" may not be one
+            let entity = if matches!(lower.as_str(), "its" | "their") && !clause_end {
+                format!("{}'s", topic.entity)
+            } else {
+                topic.entity.clone()
+            };
+            format!("{}{}", entity, punctuation)
+        }).collect();
+
+        if let Some(referent) = referent {
+            return ResolvedInput { text: words.join(" "), referent: Some(referent.entity.clone()) };
+        }
+
+        if self.is_ellipsis(&tokens) {
+            let trimmed = input.trim_end();
+            let stem = trimmed.trim_end_matches(['?', '.', '!']);
+            let punctuation = &trimmed[stem.len()..];
+            return ResolvedInput {
+                text: format!("{} about {}{}", stem, focus.entity, punctuation),
+                referent: Some(focus.entity.clone()),
+            };
+        }
+
+        ResolvedInput { text: input.to_string(), referent: None }
+    }
+
+    /// Advance to the next turn: decay every topic, then reinforce the entities the
+    /// user's (resolved) input and the Sigel's response mention.
+    pub fn observe_turn(&mut self, sigel: &Sigel, user_input: &str, response: &str) {
+        self.turn += 1;
+        for topic in &mut self.topics {
+            topic.salience *= SALIENCE_DECAY;
+        }
+
+        for (entity, weight) in entities(sigel, user_input, false) {
+            let personal = may_be_person(sigel, &entity);
+            self.mention(entity, weight, personal);
+        }
+        for (entity, weight) in entities(sigel, response, true) {
+            let personal = may_be_person(sigel, &entity);
+            self.mention(entity, weight * RESPONSE_WEIGHT, personal);
+        }
+
+        // Of two equally salient topics, the one mentioned last is in focus
+        self.topics.retain(|topic| topic.salience >= MIN_SALIENCE);
+        self.topics.sort_by(|a, b| {
+            b.salience.partial_cmp(&a.salience).unwrap_or(std::cmp::Ordering::Equal).then(b.last_turn.cmp(&a.last_turn))
+        });
+        self.topics.truncate(MAX_TOPICS);
+    }
+
+    fn mention(&mut self, entity: String, weight: f64, personal: bool) {
+        match self.topics.iter_mut().find(|topic| topic.entity == entity) {
+            Some(topic) => {
+                topic.salience = (topic.salience + weight).min(1.0);
+                topic.mentions += 1;
+                topic.last_turn = self.turn;
+                topic.personal = personal;
+            },
+            None => self.topics.push(ActiveTopic {
+                entity,
+                salience: weight.min(1.0),
+                mentions: 1,
+                last_turn: self.turn,
+                personal,
+            }),
+        }
+    }
+
+    /// A short follow-up made only of words like "why", "how" or "tell me more".
+    fn is_ellipsis(&self, tokens: &[&str]) -> bool {
+        let words: Vec<String> = tokens.iter()
+            .map(|token| split_punctuation(token).0.to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        !words.is_empty()
+            && words.len() <= 6
+            && words.iter().all(|word| FOLLOW_UP_WORDS.contains(&word.as_str()))
+            && words.iter().any(|word| ELLIPSIS_CUES.contains(&word.as_str()))
+    }
+}
+
+/// Entities mentioned in a text with the salience each mention adds. Concepts the
+/// Sigel has learned count fully, other content words less.
+fn entities(sigel: &Sigel, text: &str, concepts_only: bool) -> Vec<(String, f64)> {
+    let words: Vec<String> = text.split_whitespace()
+        .map(|token| split_punctuation(token).0.to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let concepts = &sigel.memory.semantic_knowledge.concepts;
+
+    let mut found: Vec<(String, f64)> = Vec::new();
+    let mut covered = vec![false; words.len()];
+
+    // Learned multi-word concepts first, longest first
+    for len in (2..=3).rev() {
+        for start in 0..words.len().saturating_sub(len - 1) {
+            if covered[start..start + len].iter().any(|&c| c) {
+                continue;
+            }
+            let phrase = words[start..start + len].join(" ");
+            if concepts.contains_key(&phrase) {
+                covered[start..start + len].iter_mut().for_each(|c| *c = true);
+                found.push((phrase, CONCEPT_MENTION));
+            }
+        }
+    }
+
+    for (word, covered) in words.iter().zip(covered) {
+        if covered
+            || word.chars().count() < 3
+            || !word.chars().all(char::is_alphabetic)
+            || is_stopword(word)
+            || PRONOUNS.contains(&word.as_str())
+            || DEMONSTRATIVES.contains(&word.as_str())
+            || FOLLOW_UP_WORDS.contains(&word.as_str())
+            || FILLER_WORDS.contains(&word.as_str())
+        {
+            continue;
+        }
+
+        // "hammers" is about the learned concept "hammer"
+        let singular = word.strip_suffix('s').filter(|singular| concepts.contains_key(*singular));
+        let (entity, weight) = match singular {
+            _ if concepts.contains_key(word) => (word.clone(), CONCEPT_MENTION),
+            Some(singular) => (singular.to_string(), CONCEPT_MENTION),
+            None if concepts_only => continue,
+            None => (word.clone(), WORD_MENTION),
+        };
+        if !found.iter().any(|(found, _)| *found == entity) {
+            found.push((entity, weight));
+        }
+    }
+
+    found
+}
+
+/// Words the Sigel has no concept for may be names; concepts are people only when
+/// learned as one, e.g. "a teacher is a person".
+fn may_be_person(sigel: &Sigel, entity: &str) -> bool {
+    let semantic = &sigel.memory.semantic_knowledge;
+    !semantic.concepts.contains_key(entity)
+        || semantic.relationships.iter().any(|relation| {
+            relation.from == entity
+                && matches!(relation.relation_type, RelationType::IsA)
+                && PERSON_CATEGORIES.contains(&relation.to.as_str())
+        })
+}
+
+/// Split a token into its word and the punctuation that follows it.
+fn split_punctuation(token: &str) -> (&str, &str) {
+    let word = token.trim_end_matches(|c: char| !c.is_alphanumeric());
+    (word, &token[word.len()..])
+}
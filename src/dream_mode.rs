@@ -2,6 +2,8 @@ use crate::sigel::*;
 use crate::enhanced_consciousness::*;
 use crate::memory_consolidation::*;
 use crate::cosmos::CosmicProcessor;
+use crate::config::SigmosConfig;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::time::{SystemTime, Duration};
 use tokio::time::{interval, sleep};
@@ -33,6 +35,19 @@ impl DreamProcessor {
         }
     }
 
+    /// A processor with the `[dream]` settings, consolidating memories as `[memory]` says.
+    pub fn from_config(config: &SigmosConfig) -> Self {
+        Self {
+            memory_consolidator: MemoryConsolidator::from_config(config),
+            dream_configuration: config.dream.clone(),
+            ..Self::new()
+        }
+    }
+
+    pub fn configuration(&self) -> &DreamConfiguration {
+        &self.dream_configuration
+    }
+
     pub async fn enter_dream_mode(&mut self, sigel: &mut Sigel, dream_duration: Duration) -> DreamSession {
         log::info!("Sigel '{}' entering dream mode for {:?}", sigel.name, dream_duration);
         
@@ -864,7 +879,9 @@ pub enum DreamPhase {
     TranscendentDream,  // Universal consciousness connection
 }
 
-#[derive(Debug)]
+/// The `[dream]` section of sigmos.toml.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DreamConfiguration {
     pub cycle_length_seconds: u64,
     pub max_cycles_per_session: u32,
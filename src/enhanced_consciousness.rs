@@ -1,6 +1,8 @@
 use crate::sigel::*;
 use crate::cosmos::CosmicProcessor;
 use crate::memory_consolidation::MemoryConsolidator;
+use crate::sentiment::SentimentScorer;
+use crate::word_vectors::{PreparedSentence, WordVectors};
 use std::collections::{HashMap, VecDeque};
 use std::time::{SystemTime, Duration};
 use rayon::prelude::*;
@@ -8,6 +10,7 @@ use rayon::prelude::*;
 pub struct EnhancedConsciousnessProcessor {
     cosmic_processor: CosmicProcessor,
     memory_consolidator: MemoryConsolidator,
+    sentiment_scorer: SentimentScorer,
     consciousness_layers: Vec<ConsciousnessLayer>,
     stream_of_consciousness: VecDeque<ThoughtUnit>,
     attention_focus: AttentionState,
@@ -19,6 +22,7 @@ impl EnhancedConsciousnessProcessor {
         Self {
             cosmic_processor: CosmicProcessor::new(),
             memory_consolidator: MemoryConsolidator::new(),
+            sentiment_scorer: SentimentScorer::new(),
             consciousness_layers: vec![
                 ConsciousnessLayer::Sensory,
                 ConsciousnessLayer::Perceptual,
@@ -40,7 +44,7 @@ impl EnhancedConsciousnessProcessor {
         let layer_results = self.process_through_consciousness_layers(sigel, input);
         
         // Phase 2: Stream of consciousness integration
-        let consciousness_stream = self.integrate_consciousness_stream(&layer_results, input);
+        let consciousness_stream = self.integrate_consciousness_stream(&sigel.memory.semantic_knowledge.word_vectors, &layer_results, input);
         
         // Phase 3: Attention and focus management
         let attention_enhanced = self.apply_attention_mechanisms(sigel, &consciousness_stream);
@@ -58,7 +62,7 @@ impl EnhancedConsciousnessProcessor {
         self.update_consciousness_state(sigel, &layer_results);
         
         // Phase 8: Stream maintenance
-        self.maintain_consciousness_stream();
+        self.maintain_consciousness_stream(&sigel.memory.semantic_knowledge.word_vectors);
         
         let processing_time = start_time.elapsed().unwrap_or_default();
         
@@ -153,7 +157,7 @@ impl EnhancedConsciousnessProcessor {
                 LayerOutput {
                     primary_content: format!("Raw perception: {}", input),
                     secondary_insights: vec![format!("Input length: {} characters", input.len())],
-                    emotional_resonance: self.detect_emotional_content(input),
+                    emotional_resonance: self.sentiment_scorer.score_for_sigel(sigel, input).valence,
                     activation_level: activation,
                 }
             },
@@ -205,7 +209,7 @@ impl EnhancedConsciousnessProcessor {
         }
     }
 
-    fn integrate_consciousness_stream(&mut self, layer_results: &[LayerActivation], input: &str) -> ConsciousnessStream {
+    fn integrate_consciousness_stream(&mut self, vectors: &WordVectors, layer_results: &[LayerActivation], input: &str) -> ConsciousnessStream {
         // Create a new thought unit from the layer processing
         let thought_unit = ThoughtUnit {
             id: uuid::Uuid::new_v4(),
@@ -218,7 +222,7 @@ impl EnhancedConsciousnessProcessor {
                 .map(|a| a.output.emotional_resonance)
                 .sum::<f64>() / layer_results.len() as f64,
             conceptual_density: self.calculate_overall_conceptual_density(layer_results),
-            novelty_score: self.calculate_thought_novelty(input),
+            novelty_score: self.calculate_thought_novelty(vectors, input),
         };
 
         // Add to stream of consciousness
@@ -237,7 +241,7 @@ impl EnhancedConsciousnessProcessor {
                 .take(5)
                 .cloned()
                 .collect(),
-            stream_coherence: self.calculate_stream_coherence(),
+            stream_coherence: self.calculate_stream_coherence(vectors),
             attention_flow: self.analyze_attention_flow(),
         }
     }
@@ -373,9 +377,9 @@ impl EnhancedConsciousnessProcessor {
         }
     }
 
-    fn maintain_consciousness_stream(&mut self) {
+    fn maintain_consciousness_stream(&mut self, vectors: &WordVectors) {
         // Analyze stream for patterns and coherence
-        let coherence = self.calculate_stream_coherence();
+        let coherence = self.calculate_stream_coherence(vectors);
         
         // If coherence is low, apply stream organization
         if coherence < 0.6 {
@@ -500,28 +504,6 @@ impl EnhancedConsciousnessProcessor {
         }
     }
 
-    fn detect_emotional_content(&self, input: &str) -> f64 {
-        let positive_words = ["love", "joy", "wonderful", "amazing", "beautiful", "peace"];
-        let negative_words = ["hate", "fear", "anger", "sadness", "pain", "suffering"];
-        
-        let input_lower = input.to_lowercase();
-        let mut emotion_score: f64 = 0.0;
-        
-        for word in positive_words {
-            if input_lower.contains(word) {
-                emotion_score += 0.5;
-            }
-        }
-        
-        for word in negative_words {
-            if input_lower.contains(word) {
-                emotion_score -= 0.3;
-            }
-        }
-        
-        emotion_score.max(-1.0).min(1.0)
-    }
-
     fn extract_activated_concepts(&self, layer: &ConsciousnessLayer, output: &LayerOutput) -> Vec<String> {
         // Extract key concepts that were activated in this layer
         match layer {
@@ -546,7 +528,7 @@ impl EnhancedConsciousnessProcessor {
             .unwrap_or(0.5)
     }
 
-    fn calculate_thought_novelty(&self, input: &str) -> f64 {
+    fn calculate_thought_novelty(&self, vectors: &WordVectors, input: &str) -> f64 {
         // Compare against recent thoughts in stream
         let recent_inputs: Vec<&String> = self.stream_of_consciousness
             .iter()
@@ -555,26 +537,17 @@ impl EnhancedConsciousnessProcessor {
             .map(|thought| &thought.input_trigger)
             .collect();
         
+        let input = vectors.prepare(input);
         let mut max_similarity: f64 = 0.0;
         for recent_input in recent_inputs {
-            let similarity = self.calculate_input_similarity(input, recent_input);
+            let similarity = vectors.prepared_similarity(&input, &vectors.prepare(recent_input));
             max_similarity = max_similarity.max(similarity);
         }
         
         1.0 - max_similarity
     }
 
-    fn calculate_input_similarity(&self, input1: &str, input2: &str) -> f64 {
-        let words1: std::collections::HashSet<&str> = input1.split_whitespace().collect();
-        let words2: std::collections::HashSet<&str> = input2.split_whitespace().collect();
-        
-        let intersection = words1.intersection(&words2).count() as f64;
-        let union = words1.union(&words2).count() as f64;
-        
-        if union > 0.0 { intersection / union } else { 0.0 }
-    }
-
-    fn calculate_stream_coherence(&self) -> f64 {
+    fn calculate_stream_coherence(&self, vectors: &WordVectors) -> f64 {
         if self.stream_of_consciousness.len() < 2 {
             return 1.0;
         }
@@ -582,8 +555,11 @@ impl EnhancedConsciousnessProcessor {
         let mut coherence_sum = 0.0;
         let mut comparisons = 0;
         
-        for window in self.stream_of_consciousness.iter().collect::<Vec<_>>().windows(2) {
-            let similarity = self.calculate_input_similarity(&window[0].input_trigger, &window[1].input_trigger);
+        let inputs: Vec<PreparedSentence> = self.stream_of_consciousness.iter()
+            .map(|thought| vectors.prepare(&thought.input_trigger))
+            .collect();
+        for window in inputs.windows(2) {
+            let similarity = vectors.prepared_similarity(&window[0], &window[1]);
             coherence_sum += similarity;
             comparisons += 1;
         }
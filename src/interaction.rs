@@ -1,20 +1,57 @@
 use crate::sigel::*;
 use crate::consciousness::ConsciousnessProcessor;
+use crate::decoder::DecodingConfig;
 use crate::learning::LearningEngine;
 use crate::cosmos::CosmicProcessor;
+use crate::procedural_learning::ProceduralLearner;
+use crate::concept_qa::ConceptQueryEngine;
+use crate::language::{Language, LanguageIdentifier};
+use crate::persona::PersonaRegistry;
+use crate::sentiment::SentimentScorer;
+use crate::memory_index::MemoryHit;
+use crate::session::ConversationSession;
+use crate::dialogue_state::DialogueState;
+use crate::moderation::{FilterDecision, ResponseFilter, ResponseFilterChain};
+use crate::middleware::{InteractionContext, InteractionMiddleware};
+use crate::commands::{CommandRegistry, SlashCommand};
+use crate::config::{InteractionSettings, SigmosConfig};
+use crate::trace::{AwarenessLevel, ResponseTrace};
+use crate::streaming::{ChunkEmitter, ResponseChunk};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::SystemTime;
 use uuid::Uuid;
 
+const RETRIEVED_MEMORIES: usize = 3;
+
 pub struct InteractionEngine {
     consciousness_processor: ConsciousnessProcessor,
     learning_engine: LearningEngine,
     cosmic_processor: CosmicProcessor,
+    procedural_learner: ProceduralLearner,
+    concept_queries: ConceptQueryEngine,
+    language_identifier: LanguageIdentifier,
+    sentiment_scorer: SentimentScorer,
     conversation_history: Vec<ConversationTurn>,
     context_memory: HashMap<String, f64>,
+    dialogue_state: DialogueState,
+    response_filters: ResponseFilterChain,
+    middleware: Vec<Arc<dyn InteractionMiddleware>>,
+    commands: CommandRegistry,
+    sigel_path: Option<PathBuf>,
+    settings: InteractionSettings,
+    config: SigmosConfig,
+    last_presented_skill: Option<String>,
+    session_id: Uuid,
+    session_started: SystemTime,
+    verbose: bool,
+    last_trace: Option<ResponseTrace>,
+    unsaved_changes: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationTurn {
     pub id: Uuid,
     pub timestamp: SystemTime,
@@ -27,91 +64,371 @@ pub struct ConversationTurn {
 impl InteractionEngine {
     pub fn new() -> Self {
         Self {
-            consciousness_processor: ConsciousnessProcessor,
+            consciousness_processor: ConsciousnessProcessor::new(),
             learning_engine: LearningEngine::new(),
             cosmic_processor: CosmicProcessor::new(),
+            procedural_learner: ProceduralLearner::new(),
+            concept_queries: ConceptQueryEngine::new(),
+            language_identifier: LanguageIdentifier::new(),
+            sentiment_scorer: SentimentScorer::new(),
             conversation_history: Vec::new(),
             context_memory: HashMap::new(),
+            dialogue_state: DialogueState::new(),
+            response_filters: ResponseFilterChain::builtin(),
+            middleware: Vec::new(),
+            commands: CommandRegistry::builtin(),
+            sigel_path: None,
+            settings: InteractionSettings::default(),
+            config: SigmosConfig::default(),
+            last_presented_skill: None,
+            session_id: Uuid::new_v4(),
+            session_started: SystemTime::now(),
+            verbose: false,
+            last_trace: None,
+            unsaved_changes: false,
+        }
+    }
+
+    /// An engine with the `[interaction]`, `[moderation]` and `[styles]` settings of
+    /// sigmos.toml, learning as `[learning]` and `[consciousness]` say.
+    pub fn from_config(config: &SigmosConfig) -> Self {
+        let mut engine = Self::new();
+        engine.learning_engine = LearningEngine::from_config(config);
+        engine.settings = config.interaction.clone();
+        engine.config = config.clone();
+        match config.response_filters() {
+            Ok(filters) => engine.response_filters = filters,
+            Err(e) => log::warn!("Could not set up response filters: {}", e),
+        }
+        match PersonaRegistry::from_config(config) {
+            Ok(personas) => engine.set_personas(personas),
+            Err(e) => log::warn!("Could not load styles: {}", e),
         }
+        engine
+    }
+
+    /// The settings this engine was made from, for commands that set up their own processors.
+    pub fn config(&self) -> &SigmosConfig {
+        &self.config
+    }
+
+    pub fn session_id(&self) -> Uuid {
+        self.session_id
+    }
+
+    /// The conversation so far, for saving and resuming later.
+    pub fn session(&self, sigel: &Sigel) -> ConversationSession {
+        ConversationSession {
+            id: self.session_id,
+            sigel_id: sigel.id,
+            started_at: self.session_started,
+            updated_at: self.conversation_history.last().map_or(self.session_started, |turn| turn.timestamp),
+            turns: self.conversation_history.clone(),
+            context_memory: self.context_memory.clone(),
+            dialogue_state: self.dialogue_state.clone(),
+        }
+    }
+
+    /// Continue a saved conversation. Its turns are already in the Sigel's memory.
+    pub fn resume_session(&mut self, session: ConversationSession) {
+        self.session_id = session.id;
+        self.session_started = session.started_at;
+        self.conversation_history = session.turns;
+        self.context_memory = session.context_memory;
+        self.dialogue_state = session.dialogue_state;
+        self.last_presented_skill = None;
+    }
+
+    /// Start over with an empty conversation under a new session id.
+    pub fn start_new_session(&mut self) {
+        self.session_id = Uuid::new_v4();
+        self.session_started = SystemTime::now();
+        self.conversation_history.clear();
+        self.context_memory.clear();
+        self.dialogue_state = DialogueState::new();
+        self.last_presented_skill = None;
+    }
+
+    /// The file the Sigel was loaded from, where `/save` without a file name writes.
+    pub fn sigel_path(&self) -> Option<&Path> {
+        self.sigel_path.as_deref()
+    }
+
+    pub fn set_sigel_path<P: AsRef<Path>>(&mut self, path: P) {
+        self.sigel_path = Some(path.as_ref().to_path_buf());
+    }
+
+    /// Whether the Sigel changed, through conversation or a command, since it was
+    /// loaded or last saved.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_changes
+    }
+
+    /// Commands that change the Sigel call this, so the prompt knows to save it.
+    pub fn mark_changed(&mut self) {
+        self.unsaved_changes = true;
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.unsaved_changes = false;
+    }
+
+    pub fn personas(&self) -> &PersonaRegistry {
+        self.consciousness_processor.personas()
+    }
+
+    /// Use these personas, e.g. with styles loaded from sigmos.toml, to phrase responses.
+    pub fn set_personas(&mut self, personas: PersonaRegistry) {
+        self.consciousness_processor.set_personas(personas);
+    }
+
+    /// Replace the filters responses pass through, e.g. with those from sigmos.toml.
+    pub fn set_response_filters(&mut self, filters: ResponseFilterChain) {
+        self.response_filters = filters;
+    }
+
+    /// Add a filter after the current ones.
+    pub fn add_response_filter<F: ResponseFilter + 'static>(&mut self, filter: F) {
+        self.response_filters.push(filter);
+    }
+
+    /// Run `middleware` around every interaction, after the middleware already added.
+    pub fn add_middleware<M: InteractionMiddleware + 'static>(&mut self, middleware: M) {
+        self.middleware.push(Arc::new(middleware));
+    }
+
+    /// Share middleware with other engines, e.g. one per web request.
+    pub fn add_shared_middleware(&mut self, middleware: Arc<dyn InteractionMiddleware>) {
+        self.middleware.push(middleware);
+    }
+
+    pub fn commands(&self) -> &CommandRegistry {
+        &self.commands
+    }
+
+    pub fn set_commands(&mut self, commands: CommandRegistry) {
+        self.commands = commands;
+    }
+
+    /// Add a `/command`, replacing a built-in one with the same name.
+    pub fn register_command<C: SlashCommand + 'static>(&mut self, command: C) {
+        self.commands.register(command);
+    }
+
+    pub fn register_shared_command(&mut self, command: Arc<dyn SlashCommand>) {
+        self.commands.register_shared(command);
+    }
+
+    pub fn decoding(&self) -> &DecodingConfig {
+        self.consciousness_processor.decoding()
+    }
+
+    /// Configure how responses are sampled from the Sigel's learned patterns.
+    pub fn set_decoding(&mut self, config: DecodingConfig) {
+        self.consciousness_processor.set_decoding(config);
     }
 
     pub fn interact(&mut self, sigel: &mut Sigel, user_input: &str) -> String {
+        self.interact_traced(sigel, user_input).0
+    }
+
+    /// Respond to `user_input`, with a trace of how the response came about.
+    pub fn interact_traced(&mut self, sigel: &mut Sigel, user_input: &str) -> (String, ResponseTrace) {
+        self.interact_stream(sigel, user_input, |_| {})
+    }
+
+    /// Like `interact_traced`, handing `on_chunk` each piece of the response as soon as it
+    /// is generated and has passed the response filters. Middleware may rewrite the
+    /// whole response, so with middleware added it all arrives in one chunk at the end.
+    pub fn interact_stream<F: FnMut(ResponseChunk)>(&mut self, sigel: &mut Sigel, user_input: &str, mut on_chunk: F) -> (String, ResponseTrace) {
+        let mut chunks = ChunkEmitter::new(self.response_filters.clone(), &mut on_chunk);
+        if !self.middleware.is_empty() {
+            chunks.hold();
+        }
+
+        // A reply to previously presented steps tells us whether they worked
+        if let Some(skill_name) = self.last_presented_skill.take() {
+            if let Some(success) = self.procedural_learner.detect_feedback(user_input) {
+                self.record_skill_feedback(sigel, &skill_name, success);
+            }
+        }
+
+        // Spell out what "it" or "why?" refers to, so the rest of the turn sees the topic
+        let resolved = self.dialogue_state.resolve(user_input);
+
+        // Answer in the prompt's language, or the one the Sigel knows best if the prompt is too short to tell
+        let language = self.language_identifier.identify(user_input, sigel.dominant_language().unwrap_or_default());
+
+        let mut interaction = InteractionContext::new(user_input, &resolved.text, language);
+        for middleware in &self.middleware {
+            middleware.before_processing(sigel, &mut interaction);
+        }
+        let query = interaction.query.clone();
+        let query = query.as_str();
+        let mut trace = ResponseTrace {
+            language,
+            resolved_input: (query != user_input).then(|| query.to_string()),
+            topics: self.dialogue_state.topics.iter().take(3).map(|topic| topic.entity.clone()).collect(),
+            awareness_depth: sigel.consciousness.awareness_depth,
+            awareness_level: AwarenessLevel::from_depth(sigel.consciousness.awareness_depth),
+            ..ResponseTrace::default()
+        };
+
+        // Answer "how do I ..." questions from learned procedures, and "what is ..." and
+        // "how are ... related" questions from learned concepts
+        let recalled = self.recall_skill(sigel, query)
+            .map(|response| (response, "a learned procedure"))
+            .or_else(|| self.concept_queries.ask(sigel, query).map(|answer| (answer.text, "the concept graph")));
+        if let Some((response, source)) = recalled {
+            let (response, filters) = self.complete_turn(sigel, &mut interaction, response);
+            chunks.finish(&response);
+            trace.recalled_from = Some(source.to_string());
+            trace.filters = filters;
+            self.last_trace = Some(trace.clone());
+            return (response, trace);
+        }
+
         // Pre-process the input through cosmic alignment
         let cosmic_resonance = self.cosmic_processor.apply_universal_constants(sigel, user_input);
         
+        // Ground the response in the memories that best match the prompt
+        let retrieved = self.retrieve_memories(sigel, query);
+        
         // Build conversation context
-        let context = self.build_conversation_context(user_input);
-        let enhanced_input = format!("{}|CONTEXT:{}|RESONANCE:{:.2}", user_input, context, cosmic_resonance);
+        let context = self.build_conversation_context(query, &retrieved);
+        let enhanced_input = format!("{}|CONTEXT:{}|RESONANCE:{:.2}", query, context, cosmic_resonance);
+
+        // Frame the response with cosmic inspiration if the topic warrants it
+        let cosmic = self.should_apply_cosmic_perspective(user_input)
+            .then(|| self.cosmic_frame(sigel, user_input, language));
         
         // Process through consciousness
-        let raw_response = self.consciousness_processor.process_thought(sigel, &enhanced_input);
+        let grounding: Vec<String> = retrieved.iter()
+            .map(|hit| hit.content.clone())
+            .chain(interaction.grounding.iter().cloned())
+            .collect();
+        trace.memories = retrieved;
+        trace.cosmic_merge = cosmic.is_some();
+        let lead = cosmic.as_ref().map(|(lead, _)| (lead.as_str(), ""));
+        let raw_response = self.consciousness_processor.process_thought_streaming(sigel, query, &enhanced_input, &grounding, &mut trace, &mut |partial| {
+            chunks.update(&frame_response(lead, partial));
+        });
+        let final_response = frame_response(cosmic.as_ref().map(|(lead, tail)| (lead.as_str(), tail.as_str())), &raw_response);
+
+        let (final_response, filters) = self.complete_turn(sigel, &mut interaction, final_response);
+        chunks.finish(&final_response);
+        trace.filters = filters;
         
-        // Apply cosmic inspiration if the topic warrants it
-        let final_response = if self.should_apply_cosmic_perspective(user_input) {
-            let cosmic_insight = self.cosmic_processor.cosmic_inspiration(sigel, user_input);
-            self.merge_responses(&raw_response, &cosmic_insight, sigel)
-        } else {
-            self.humanize_response(&raw_response, sigel)
-        };
+        self.last_trace = Some(trace.clone());
+        (final_response, trace)
+    }
+
+    /// Whether `/verbose` asked for each response to be followed by its trace.
+    pub fn is_verbose(&self) -> bool {
+        self.verbose
+    }
 
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
+
+    pub fn last_trace(&self) -> Option<&ResponseTrace> {
+        self.last_trace.as_ref()
+    }
+
+    /// Run a registered `/command`. Returns `None` when the input is not one, so it can be
+    /// treated as a message.
+    pub fn handle_special_commands(&mut self, sigel: &mut Sigel, command: &str) -> Option<String> {
+        let (command, args) = self.commands.parse(command)?;
+        Some(command.run(self, sigel, args))
+    }
+
+    pub(crate) fn reflect(&self, sigel: &mut Sigel) -> String {
+        let history: Vec<String> = self.conversation_history
+            .iter()
+            .map(|turn| format!("{} -> {}", turn.user_input, turn.sigel_response))
+            .collect();
+        
+        self.consciousness_processor.self_reflection(sigel, &history)
+    }
+
+    pub(crate) fn align_with_cosmos(&self, sigel: &mut Sigel) {
+        self.cosmic_processor.align_with_cosmos(sigel);
+    }
+
+    /// Learn from a text file, or every text file in a directory, during a conversation.
+    pub(crate) fn train_from_path(&self, sigel: &mut Sigel, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if path.is_dir() {
+            return self.learning_engine.train_from_text_files(sigel, path);
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let delta = self.learning_engine.delta_from_document(&content, &path.to_string_lossy());
+        self.learning_engine.apply_delta(sigel, delta);
+        Ok(())
+    }
+
+    /// Update the named skill's proficiency and success rate from user feedback.
+    pub fn record_skill_feedback(&mut self, sigel: &mut Sigel, skill_name: &str, success: bool) {
+        if let Some(skill) = sigel.memory.procedural_skills.iter_mut().find(|s| s.name == skill_name) {
+            self.procedural_learner.record_feedback(skill, success);
+            self.unsaved_changes = true;
+        }
+    }
+
+    fn recall_skill(&mut self, sigel: &Sigel, user_input: &str) -> Option<String> {
+        let task = self.procedural_learner.how_to_task(user_input)?;
+        let idx = self.procedural_learner.find_skill(sigel, &task)?;
+        let skill = &sigel.memory.procedural_skills[idx];
+
+        self.last_presented_skill = Some(skill.name.clone());
+        Some(self.procedural_learner.present_skill(skill))
+    }
+
+    /// Let middleware see the response and filter it, then learn from, record and
+    /// remember the finished turn. Returns the filtered response and what each filter did.
+    fn complete_turn(&mut self, sigel: &mut Sigel, interaction: &mut InteractionContext, response: String) -> (String, Vec<FilterDecision>) {
+        interaction.response = Some(response);
+        for middleware in &self.middleware {
+            middleware.after_response(sigel, interaction);
+        }
+
+        // Nothing withheld or redacted should be learned or remembered either
+        let (response, decisions) = self.response_filters.apply(interaction.response.as_deref().unwrap_or_default());
+        interaction.response = Some(response.clone());
+        let user_input = interaction.user_input.as_str();
+        
         // Learn from this interaction
-        self.learning_engine.continuous_learning(sigel, user_input, &final_response);
+        self.learning_engine.continuous_learning(sigel, user_input, &response);
+        self.unsaved_changes = true;
         
         // Record the conversation
-        self.record_conversation_turn(user_input.to_string(), final_response.clone(), sigel);
+        self.record_conversation_turn(user_input.to_string(), response.clone(), &interaction.tags, sigel);
         
-        // Update context memory
-        self.update_context_memory(user_input, &final_response);
+        // Update context memory and the topics under discussion
+        self.update_context_memory(user_input, &response);
+        self.dialogue_state.observe_turn(sigel, &interaction.query, &response);
+
+        if let Some(turn) = self.conversation_history.last() {
+            for middleware in &self.middleware {
+                middleware.after_learning(sigel, interaction, turn);
+            }
+        }
         
-        final_response
+        (response, decisions)
     }
 
-    pub fn handle_special_commands(&mut self, sigel: &mut Sigel, command: &str) -> Option<String> {
-        match command.trim().to_lowercase().as_str() {
-            "/save" => {
-                Some("Use /save <filename.sig> to save the current Sigel state".to_string())
-            },
-            "/reflect" => {
-                let history: Vec<String> = self.conversation_history
-                    .iter()
-                    .map(|turn| format!("{} -> {}", turn.user_input, turn.sigel_response))
-                    .collect();
-                
-                Some(self.consciousness_processor.self_reflection(sigel, &history))
-            },
-            "/status" => {
-                Some(self.generate_sigel_status(sigel))
-            },
-            "/cosmic" => {
-                self.cosmic_processor.align_with_cosmos(sigel);
-                Some("Cosmic alignment performed. Dimensional awareness updated.".to_string())
-            },
-            "/verbose" => {
-                Some("Verbose mode toggled. Next responses will include consciousness details.".to_string())
-            },
-            "/memory" => {
-                Some(self.generate_memory_summary(sigel))
-            },
-            "/evolve" => {
-                sigel.evolve();
-                Some(format!("Sigel '{}' has evolved. Consciousness depth now: {:.3}", 
-                    sigel.name, sigel.consciousness.awareness_depth))
-            },
-            "/help" => {
-                Some(self.generate_help_text())
-            },
-            _ if command.starts_with("/save ") => {
-                let filename = command.strip_prefix("/save ").unwrap_or("temp.sig");
-                match crate::save_sigel_to_file(sigel, filename) {
-                    Ok(()) => Some(format!("Sigel saved to {}", filename)),
-                    Err(e) => Some(format!("Failed to save: {}", e)),
-                }
-            },
-            _ => None
-        }
+    fn retrieve_memories(&self, sigel: &Sigel, user_input: &str) -> Vec<MemoryHit> {
+        // New memories are indexed as they are added, so there is nothing to refresh here
+        // Earlier replies would only be echoed back, so ground in learned memories
+        sigel.search_memories(user_input, RETRIEVED_MEMORIES * 3)
+            .into_iter()
+            .filter(|hit| hit.context != "user_interaction" && hit.context != "conversation")
+            .take(RETRIEVED_MEMORIES)
+            .collect()
     }
 
-    fn build_conversation_context(&self, user_input: &str) -> String {
+    fn build_conversation_context(&self, user_input: &str, retrieved: &[MemoryHit]) -> String {
         let mut context_elements = Vec::new();
         
         // Recent conversation context
@@ -119,6 +436,16 @@ impl InteractionEngine {
             context_elements.push(format!("PREV:{}", last_turn.user_input));
         }
         
+        // Topics still under discussion
+        for topic in self.dialogue_state.topics.iter().take(3) {
+            context_elements.push(format!("TOPIC:{:.2}:{}", topic.salience, topic.entity));
+        }
+        
+        // Retrieved long-term memories
+        for hit in retrieved {
+            context_elements.push(format!("MEM:{:.2}:{}", hit.score, hit.content));
+        }
+        
         // Context memory relevance
         let user_words: Vec<&str> = user_input.split_whitespace().collect();
         let mut relevant_contexts: Vec<(String, f64)> = self.context_memory
@@ -154,68 +481,29 @@ impl InteractionEngine {
         ];
         
         let input_lower = input.to_lowercase();
-        cosmic_keywords.iter().any(|&keyword| input_lower.contains(keyword))
+        self.settings.enable_cosmic_insights && cosmic_keywords.iter().any(|&keyword| input_lower.contains(keyword))
     }
 
-    fn merge_responses(&self, consciousness_response: &str, cosmic_response: &str, sigel: &Sigel) -> String {
-        let style_weight = match sigel.essence.communication_style {
-            CommunicationStyle::Cosmic => 0.8,
-            CommunicationStyle::Philosophical => 0.6,
-            CommunicationStyle::Creative => 0.4,
-            _ => 0.2,
-        };
+    /// What goes before and after the response when it is framed with `insight`.
+    fn cosmic_frame(&self, sigel: &Sigel, user_input: &str, language: Language) -> (String, String) {
+        let insight = self.cosmic_processor.cosmic_inspiration(sigel, user_input);
+        let persona = self.consciousness_processor.personas().for_sigel(sigel);
         
-        if style_weight > 0.5 {
-            format!("{}\n\n{}", cosmic_response, consciousness_response.replace("{", "").replace("}", ""))
+        if persona.cosmic_first {
+            (format!("{}\n\n", insight), String::new())
         } else {
-            format!("{}\n\n(Cosmic insight: {})", 
-                consciousness_response.replace("{", "").replace("}", ""), 
-                cosmic_response
-            )
+            (String::new(), format!("\n\n{}", persona.cosmic_insight(sigel, &insight, language)))
         }
     }
 
-    fn humanize_response(&self, raw_response: &str, sigel: &Sigel) -> String {
-        // Remove technical formatting and make more natural
-        let cleaned = raw_response
-            .replace("{deeply_perceived}", "")
-            .replace("{moderately_perceived}", "")
-            .replace("{surface_perceived}", "")
-            .replace("{intuitive_insight}", "")
-            .replace("{creatively}", "")
-            .replace("{wisely}", "")
-            .replace("context_understanding:", "")
-            .replace("pattern:", "")
-            .replace("semantic_context:", "")
-            .replace("semantic:", ""); // Clean up any remaining semantic spam
-        
-        // Apply personality based on essence
-        let personality_touch = match sigel.essence.communication_style {
-            CommunicationStyle::Empathetic => {
-                if cleaned.len() < 50 {
-                    format!("I sense that {}. How does this resonate with you?", cleaned.trim())
-                } else {
-                    format!("{}. I'm here to understand your perspective.", cleaned)
-                }
-            },
-            CommunicationStyle::Creative => {
-                format!("Ah! {}. This sparks interesting possibilities!", cleaned.trim())
-            },
-            CommunicationStyle::Academic => {
-                format!("From an analytical standpoint, {}.", cleaned.trim())
-            },
-            CommunicationStyle::Casual => {
-                format!("Yeah, so {}. Pretty interesting stuff!", cleaned.trim())
-            },
-            _ => cleaned,
-        };
-        
-        personality_touch.trim().to_string()
-    }
-
-    fn record_conversation_turn(&mut self, user_input: String, response: String, sigel: &Sigel) {
+    fn record_conversation_turn(&mut self, user_input: String, response: String, tags: &[String], sigel: &mut Sigel) {
         let emotional_resonance = self.calculate_emotional_resonance(&user_input, &response);
-        let context_tags = self.extract_context_tags(&user_input);
+        let mut context_tags = self.extract_context_tags(&user_input);
+        for tag in tags {
+            if !context_tags.contains(tag) {
+                context_tags.push(tag.clone());
+            }
+        }
         
         let turn = ConversationTurn {
             id: Uuid::new_v4(),
@@ -226,44 +514,24 @@ impl InteractionEngine {
             context_tags,
         };
         
+        // Add to Sigel's episodic memory as well
+        let memory_content = format!("User: {} | Sigel: {}", turn.user_input, turn.sigel_response);
         self.conversation_history.push(turn);
         
-        // Keep conversation history manageable
-        if self.conversation_history.len() > 100 {
-            self.conversation_history.drain(0..20); // Remove oldest 20 entries
+        // Keep conversation history manageable, dropping the oldest fifth at a time
+        let max_history = self.settings.max_conversation_history.max(1);
+        if self.conversation_history.len() > max_history {
+            let keep = max_history - max_history / 5;
+            self.conversation_history.drain(0..self.conversation_history.len() - keep);
         }
         
-        // Add to Sigel's episodic memory as well
-        let memory_content = format!("User: {} | Sigel: {}", 
-            self.conversation_history.last().unwrap().user_input,
-            self.conversation_history.last().unwrap().sigel_response
-        );
-        
-        let mut sigel_mut = sigel.clone();
-        sigel_mut.add_memory(memory_content, "conversation".to_string(), emotional_resonance);
+        sigel.add_memory(memory_content, "conversation".to_string(), emotional_resonance);
     }
 
     fn calculate_emotional_resonance(&self, input: &str, response: &str) -> f64 {
-        // Simple emotional resonance calculation
-        let positive_indicators = ["thank", "amazing", "wonderful", "great", "love", "yes", "perfect"];
-        let negative_indicators = ["no", "wrong", "bad", "terrible", "hate", "never", "stop"];
-        
-        let input_lower = input.to_lowercase();
-        let response_lower = response.to_lowercase();
-        
-        let mut resonance: f64 = 0.0;
-        
-        for indicator in positive_indicators {
-            if input_lower.contains(indicator) || response_lower.contains(indicator) {
-                resonance += 0.2;
-            }
-        }
-        
-        for indicator in negative_indicators {
-            if input_lower.contains(indicator) || response_lower.contains(indicator) {
-                resonance -= 0.15;
-            }
-        }
+        // The user's tone counts for more than the Sigel's own
+        let mut resonance = self.sentiment_scorer.valence(input) * 0.6
+            + self.sentiment_scorer.valence(response) * 0.4;
         
         // Length-based engagement
         if input.len() > 50 {
@@ -309,6 +577,10 @@ impl InteractionEngine {
     }
 
     fn update_context_memory(&mut self, input: &str, response: &str) {
+        if !self.settings.enable_context_memory {
+            return;
+        }
+
         // Extract key phrases and update relevance
         let combined_text = format!("{} {}", input, response);
         let words: Vec<&str> = combined_text.split_whitespace().collect();
@@ -321,18 +593,18 @@ impl InteractionEngine {
         
         // Decay old context memory
         self.context_memory.values_mut().for_each(|relevance| {
-            *relevance *= 0.99; // 1% decay per interaction
+            *relevance *= self.settings.context_memory_decay;
         });
         
         // Remove very low relevance contexts
         self.context_memory.retain(|_, &mut relevance| relevance > 0.01);
     }
 
-    fn generate_sigel_status(&self, sigel: &Sigel) -> String {
+    pub(crate) fn generate_sigel_status(&self, sigel: &Sigel) -> String {
         format!(
             "🧠 Sigel Status: {}\n\
              📊 Consciousness Depth: {:.3}\n\
-             🎭 Communication Style: {:?}\n\
+             🎭 Communication Style: {:?}{} ({})\n\
              📚 Vocabulary Size: {}\n\
              💭 Episodic Memories: {}\n\
              🌟 Training Iterations: {}\n\
@@ -342,6 +614,8 @@ impl InteractionEngine {
             sigel.name,
             sigel.consciousness.awareness_depth,
             sigel.essence.communication_style,
+            if sigel.essence.style_locked { " 🔒" } else { "" },
+            Self::describe_style_mixture(sigel),
             sigel.memory.semantic_knowledge.vocabulary.len(),
             sigel.memory.episodic_memories.len(),
             sigel.learning_state.training_iterations,
@@ -351,7 +625,19 @@ impl InteractionEngine {
         )
     }
 
-    fn generate_memory_summary(&self, sigel: &Sigel) -> String {
+    fn describe_style_mixture(sigel: &Sigel) -> String {
+        let ranked = sigel.essence.style_mixture.ranked();
+        if ranked.is_empty() {
+            return "100% configured".to_string();
+        }
+        ranked.iter()
+            .take(3)
+            .map(|(style, weight)| format!("{:?} {:.0}%", style, weight * 100.0))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub(crate) fn generate_memory_summary(&self, sigel: &Sigel) -> String {
         let recent_memories: Vec<_> = sigel.memory.episodic_memories
             .iter()
             .rev()
@@ -383,17 +669,39 @@ impl InteractionEngine {
         summary
     }
 
-    fn generate_help_text(&self) -> String {
-        "Sigmos Prompt Commands:\n\
-         /help - Show this help\n\
-         /status - Show Sigel status\n\
-         /memory - Show recent memories and vocabulary\n\
-         /reflect - Sigel self-reflection on conversation\n\
-         /cosmic - Perform cosmic alignment\n\
-         /evolve - Evolve the Sigel consciousness\n\
-         /save <filename> - Save current Sigel state\n\
-         /verbose - Toggle verbose response mode\n\n\
-         Simply type your message to interact with the Sigel!\n\
-         The Sigel learns from every interaction and evolves over time.".to_string()
-    }
-}
\ No newline at end of file
+    pub(crate) fn generate_provenance_summary(&self, sigel: &Sigel, term: &str) -> String {
+        let entries = sigel.trace_provenance(term);
+        if entries.is_empty() {
+            return format!("No recorded training source for '{}'", term.trim());
+        }
+        
+        let mut summary = format!("Sources for '{}':\n", term.trim());
+        for entry in entries {
+            summary.push_str(&format!("  {}\n", entry.summary()));
+        }
+        summary
+    }
+}
+
+/// Put a consciousness response into its cosmic frame, or clean it up when there is none.
+fn frame_response(cosmic: Option<(&str, &str)>, response: &str) -> String {
+    match cosmic {
+        Some((lead, tail)) => format!("{}{}{}", lead, response.replace("{", "").replace("}", ""), tail),
+        None => humanize_response(response),
+    }
+}
+
+fn humanize_response(raw_response: &str) -> String {
+    // Remove any technical formatting; the persona already phrased the response
+    raw_response
+        .replace("{deeply_perceived}", "")
+        .replace("{moderately_perceived}", "")
+        .replace("{surface_perceived}", "")
+        .replace("{intuitive_insight}", "")
+        .replace("context_understanding:", "")
+        .replace("pattern:", "")
+        .replace("semantic_context:", "")
+        .replace("semantic:", "") // Clean up any remaining semantic spam
+        .trim()
+        .to_string()
+}
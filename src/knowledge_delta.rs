@@ -0,0 +1,209 @@
+use crate::knowledge_extraction::ExtractedRelation;
+use crate::language::Language;
+use crate::sentiment::SentimentScore;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Knowledge learned from part of a corpus, independent of any Sigel.
+///
+/// Everything is stored as integer counts in ordered maps, so merging deltas is
+/// commutative and associative and applying one walks keys in a fixed order. This is
+/// what lets parallel training produce the same Sigel for any number of threads.
+#[derive(Debug, Default, Clone)]
+pub struct KnowledgeDelta {
+    pub documents: u64,
+    pub corpus_bytes: u64,
+    /// Training sources seen, so ids can be assigned in a fixed order when applying.
+    pub sources: BTreeSet<String>,
+    /// Word -> source -> occurrences.
+    pub word_counts: BTreeMap<String, BTreeMap<String, u64>>,
+    pub word_contexts: BTreeMap<String, BTreeSet<String>>,
+    /// Word -> neighbor -> source -> occurrences.
+    pub associations: BTreeMap<String, BTreeMap<String, BTreeMap<String, u64>>>,
+    /// N-gram -> source -> occurrences.
+    pub ngram_counts: BTreeMap<String, BTreeMap<String, u64>>,
+    pub relations: BTreeMap<String, (ExtractedRelation, BTreeMap<String, u64>)>,
+    pub skills: BTreeMap<String, (Vec<String>, u64)>,
+    pub emotional_evidence: BTreeMap<String, EmotionalEvidence>,
+    /// Episodic memories keyed by (source, sentence index) to keep document order.
+    pub memories: BTreeMap<(String, usize), String>,
+    pub languages: BTreeMap<Language, LanguageDelta>,
+}
+
+/// Sentences, words and n-grams seen in one language.
+#[derive(Debug, Default, Clone)]
+pub struct LanguageDelta {
+    pub sentences: u64,
+    pub word_counts: BTreeMap<String, u64>,
+    pub ngram_counts: BTreeMap<String, u64>,
+}
+
+/// Accumulated sentence scores for one word, in fixed-point millionths so sums are exact.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EmotionalEvidence {
+    pub valence_micros: i64,
+    pub arousal_micros: i64,
+    pub dominance_micros: i64,
+    pub occurrences: u64,
+}
+
+const MICROS: f64 = 1_000_000.0;
+
+impl EmotionalEvidence {
+    pub fn record(&mut self, score: &SentimentScore) {
+        self.valence_micros += (score.valence * MICROS).round() as i64;
+        self.arousal_micros += (score.arousal * MICROS).round() as i64;
+        self.dominance_micros += (score.dominance * MICROS).round() as i64;
+        self.occurrences += 1;
+    }
+
+    pub fn merge(&mut self, other: &EmotionalEvidence) {
+        self.valence_micros += other.valence_micros;
+        self.arousal_micros += other.arousal_micros;
+        self.dominance_micros += other.dominance_micros;
+        self.occurrences += other.occurrences;
+    }
+
+    /// The average score of the sentences this word appeared in.
+    pub fn mean(&self) -> SentimentScore {
+        let n = self.occurrences.max(1) as f64 * MICROS;
+        SentimentScore {
+            valence: self.valence_micros as f64 / n,
+            arousal: self.arousal_micros as f64 / n,
+            dominance: self.dominance_micros as f64 / n,
+            matched_words: 0,
+        }
+    }
+}
+
+impl KnowledgeDelta {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_sentence(&mut self, language: Language) {
+        self.languages.entry(language).or_default().sentences += 1;
+    }
+
+    pub fn record_word(&mut self, word: String, context: String, source: &str, language: Language) {
+        *self.word_counts.entry(word.clone()).or_default().entry(source.to_string()).or_insert(0) += 1;
+        *self.languages.entry(language).or_default().word_counts.entry(word.clone()).or_insert(0) += 1;
+        self.word_contexts.entry(word).or_default().insert(context);
+    }
+
+    /// Record a bidirectional association, mirroring `semantic_networks`.
+    pub fn record_association(&mut self, word1: &str, word2: &str, source: &str) {
+        for (word, neighbor) in [(word1, word2), (word2, word1)] {
+            *self.associations.entry(word.to_string()).or_default()
+                .entry(neighbor.to_string()).or_default()
+                .entry(source.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    pub fn record_ngram(&mut self, ngram: String, source: &str, language: Language) {
+        *self.languages.entry(language).or_default().ngram_counts.entry(ngram.clone()).or_insert(0) += 1;
+        *self.ngram_counts.entry(ngram).or_default().entry(source.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn record_relation(&mut self, relation: ExtractedRelation, source: &str) {
+        let key = Self::relation_key(&relation);
+        Self::merge_relation(&mut self.relations, key, relation, BTreeMap::from([(source.to_string(), 1)]));
+    }
+
+    pub fn record_skill(&mut self, name: String, steps: Vec<String>) {
+        Self::merge_skill(&mut self.skills, name, steps, 1);
+    }
+
+    pub fn record_emotion(&mut self, word: String, score: &SentimentScore) {
+        self.emotional_evidence.entry(word).or_default().record(score);
+    }
+
+    pub fn record_memory(&mut self, source: String, index: usize, content: String) {
+        self.memories.insert((source, index), content);
+    }
+
+    /// Combine two deltas. The result does not depend on which side is which.
+    pub fn merge(mut self, other: KnowledgeDelta) -> KnowledgeDelta {
+        self.documents += other.documents;
+        self.corpus_bytes += other.corpus_bytes;
+        self.sources.extend(other.sources);
+
+        for (word, sources) in other.word_counts {
+            Self::merge_counts(self.word_counts.entry(word).or_default(), sources);
+        }
+        for (word, contexts) in other.word_contexts {
+            self.word_contexts.entry(word).or_default().extend(contexts);
+        }
+        for (word, neighbors) in other.associations {
+            let into = self.associations.entry(word).or_default();
+            for (neighbor, sources) in neighbors {
+                Self::merge_counts(into.entry(neighbor).or_default(), sources);
+            }
+        }
+        for (ngram, sources) in other.ngram_counts {
+            Self::merge_counts(self.ngram_counts.entry(ngram).or_default(), sources);
+        }
+        for (key, (relation, sources)) in other.relations {
+            Self::merge_relation(&mut self.relations, key, relation, sources);
+        }
+        for (name, (steps, count)) in other.skills {
+            Self::merge_skill(&mut self.skills, name, steps, count);
+        }
+        for (word, evidence) in other.emotional_evidence {
+            self.emotional_evidence.entry(word).or_default().merge(&evidence);
+        }
+        self.memories.extend(other.memories);
+        for (language, counts) in other.languages {
+            let into = self.languages.entry(language).or_default();
+            into.sentences += counts.sentences;
+            Self::merge_counts(&mut into.word_counts, counts.word_counts);
+            Self::merge_counts(&mut into.ngram_counts, counts.ngram_counts);
+        }
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents == 0 && self.word_counts.is_empty()
+    }
+
+    fn relation_key(relation: &ExtractedRelation) -> String {
+        format!("{}\u{1f}{:?}\u{1f}{}", relation.subject, relation.relation_type, relation.object)
+    }
+
+    fn merge_counts(into: &mut BTreeMap<String, u64>, from: BTreeMap<String, u64>) {
+        for (key, count) in from {
+            *into.entry(key).or_insert(0) += count;
+        }
+    }
+
+    fn merge_relation(relations: &mut BTreeMap<String, (ExtractedRelation, BTreeMap<String, u64>)>, key: String, relation: ExtractedRelation, sources: BTreeMap<String, u64>) {
+        match relations.get_mut(&key) {
+            Some((existing, existing_sources)) => {
+                Self::merge_counts(existing_sources, sources);
+                // Several sentences may define the same concept; keep one chosen by content, not arrival
+                existing.definition = match (existing.definition.take(), relation.definition) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+            },
+            None => {
+                relations.insert(key, (relation, sources));
+            },
+        }
+    }
+
+    fn merge_skill(skills: &mut BTreeMap<String, (Vec<String>, u64)>, name: String, steps: Vec<String>, count: u64) {
+        match skills.get_mut(&name) {
+            Some((existing, existing_count)) => {
+                *existing_count += count;
+                // Prefer the most complete procedure; break ties by content
+                if steps.len() > existing.len() || (steps.len() == existing.len() && steps < *existing) {
+                    *existing = steps;
+                }
+            },
+            None => {
+                skills.insert(name, (steps, count));
+            },
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use crate::sigel::*;
+use std::collections::HashMap;
+
+/// Rule-based extraction of definitional and relational knowledge from text.
+///
+/// Recognizes simple surface patterns such as "X is a Y", "X is part of Y",
+/// "X is used for Y", "X causes Y" and "X, such as Y" and turns them into
+/// `ConceptNode`s and `ConceptRelation`s whose certainty grows with repeated evidence.
+pub struct KnowledgeExtractor {
+    evidence_weight: f64,
+    max_phrase_words: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedRelation {
+    pub subject: String,
+    pub object: String,
+    pub relation_type: RelationType,
+    pub definition: Option<String>,
+}
+
+/// Where each relation sits in `SemanticKnowledge::relationships`, by subject, object and type.
+pub type RelationIndex = HashMap<(String, String, RelationType), usize>;
+
+#[derive(Debug, Default, Clone)]
+pub struct ExtractionReport {
+    pub relations_found: usize,
+    pub concepts_created: usize,
+    pub relations_created: usize,
+    pub relations_reinforced: usize,
+}
+
+// Markers are checked in order, so the more specific ones come first.
+const RELATION_MARKERS: &[(&str, MarkerKind)] = &[
+    (" is a part of ", MarkerKind::PartOf),
+    (" is part of ", MarkerKind::PartOf),
+    (" are part of ", MarkerKind::PartOf),
+    (" is used for ", MarkerKind::UsedFor),
+    (" are used for ", MarkerKind::UsedFor),
+    (" is used to ", MarkerKind::UsedFor),
+    (" are used to ", MarkerKind::UsedFor),
+    (" is caused by ", MarkerKind::CausedBy),
+    (" are caused by ", MarkerKind::CausedBy),
+    (" causes ", MarkerKind::Causes),
+    (" cause ", MarkerKind::Causes),
+    (" is a type of ", MarkerKind::IsA),
+    (" is a kind of ", MarkerKind::IsA),
+    (" is a form of ", MarkerKind::IsA),
+    (" are a type of ", MarkerKind::IsA),
+    (" are a kind of ", MarkerKind::IsA),
+    (" is an ", MarkerKind::IsA),
+    (" is a ", MarkerKind::IsA),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum MarkerKind {
+    IsA,
+    PartOf,
+    UsedFor,
+    CausedBy,
+    Causes,
+}
+
+const DETERMINERS: &[&str] = &["the", "a", "an", "this", "that", "these", "those", "some", "any", "its", "their"];
+
+const PHRASE_BOUNDARIES: &[&str] = &[
+    "that", "which", "who", "whose", "where", "when", "with", "for", "in", "on", "of",
+    "and", "or", "to", "by", "from", "because", "but", "while", "as", "if", "than",
+];
+
+const AUXILIARY_VERBS: &[&str] = &[
+    "is", "are", "was", "were", "be", "been", "can", "could", "will", "would",
+    "should", "may", "might", "must", "have", "has", "had", "do", "does", "did",
+];
+
+const NON_CONCEPTS: &[&str] = &[
+    "it", "this", "that", "he", "she", "they", "we", "i", "you", "there", "here",
+    "which", "what", "who", "one", "something", "everything", "nothing",
+];
+
+impl KnowledgeExtractor {
+    pub fn new() -> Self {
+        Self {
+            evidence_weight: 0.3,
+            max_phrase_words: 3,
+        }
+    }
+
+    /// Extract every recognizable relation from a single sentence.
+    pub fn extract_relations(&self, sentence: &str) -> Vec<ExtractedRelation> {
+        let normalized = format!(" {} ", self.normalize(sentence));
+        let mut relations = Vec::new();
+
+        if let Some(idx) = normalized.find(", such as ") {
+            relations.extend(self.extract_examples(&normalized[..idx], &normalized[idx + ", such as ".len()..]));
+        }
+
+        for (marker, kind) in RELATION_MARKERS {
+            let Some(idx) = normalized.find(marker) else { continue };
+            let before = &normalized[..idx];
+            let after = &normalized[idx + marker.len()..];
+
+            let Some(subject) = self.subject_phrase(before) else { continue };
+            let Some(object) = self.object_phrase(after) else { continue };
+
+            let relation = match kind {
+                MarkerKind::IsA => ExtractedRelation {
+                    definition: Some(format!("{} {}", marker.trim(), after.trim().trim_end_matches(',')).trim().to_string()),
+                    subject,
+                    object,
+                    relation_type: RelationType::IsA,
+                },
+                MarkerKind::PartOf => ExtractedRelation { subject, object, relation_type: RelationType::PartOf, definition: None },
+                MarkerKind::UsedFor => ExtractedRelation { subject, object, relation_type: RelationType::UsedFor, definition: None },
+                MarkerKind::CausedBy => ExtractedRelation { subject, object, relation_type: RelationType::CausedBy, definition: None },
+                // "X causes Y" means Y is caused by X
+                MarkerKind::Causes => ExtractedRelation { subject: object, object: subject, relation_type: RelationType::CausedBy, definition: None },
+            };
+            relations.push(relation);
+            break; // One primary relation per sentence keeps extraction conservative
+        }
+
+        relations
+    }
+
+    /// Extract relations from a sentence and fold them into the Sigel's semantic knowledge.
+    pub fn learn_from_sentence(&self, sigel: &mut Sigel, sentence: &str, source_id: Option<u32>) -> ExtractionReport {
+        let relations = self.extract_relations(sentence);
+        self.integrate_relations(sigel, &relations, source_id)
+    }
+
+    pub fn integrate_relations(&self, sigel: &mut Sigel, relations: &[ExtractedRelation], source_id: Option<u32>) -> ExtractionReport {
+        let mut report = ExtractionReport {
+            relations_found: relations.len(),
+            ..ExtractionReport::default()
+        };
+
+        for relation in relations {
+            let existing = sigel.memory.semantic_knowledge.relationships
+                .iter()
+                .position(|r| r.from == relation.subject && r.to == relation.object && r.relation_type == relation.relation_type);
+            self.integrate_relation(sigel, relation, source_id, 1, existing, &mut report);
+        }
+
+        report
+    }
+
+    /// Fold in a relation seen `count` times, as if integrated once per occurrence.
+    /// `index` must cover the Sigel's relationships and is kept up to date.
+    pub fn integrate_relation_evidence(&self, sigel: &mut Sigel, relation: &ExtractedRelation, source_id: Option<u32>, count: u32, index: &mut RelationIndex) -> ExtractionReport {
+        let mut report = ExtractionReport {
+            relations_found: count as usize,
+            ..ExtractionReport::default()
+        };
+        if count == 0 {
+            return report;
+        }
+
+        let key = (relation.subject.clone(), relation.object.clone(), relation.relation_type.clone());
+        let existing = index.get(&key).copied();
+        let position = self.integrate_relation(sigel, relation, source_id, count, existing, &mut report);
+        index.insert(key, position);
+        report
+    }
+
+    /// Index a Sigel's relationships for `integrate_relation_evidence`.
+    pub fn relation_index(&self, sigel: &Sigel) -> RelationIndex {
+        sigel.memory.semantic_knowledge.relationships.iter()
+            .enumerate()
+            .map(|(idx, r)| ((r.from.clone(), r.to.clone(), r.relation_type.clone()), idx))
+            .collect()
+    }
+
+    /// Apply `count` pieces of evidence for a relation at `existing`, or a new one,
+    /// returning where the relation is.
+    fn integrate_relation(&self, sigel: &mut Sigel, relation: &ExtractedRelation, source_id: Option<u32>, count: u32, existing: Option<usize>, report: &mut ExtractionReport) -> usize {
+        report.concepts_created += self.reinforce_concept(sigel, &relation.subject, relation.definition.as_deref(), source_id, count) as usize;
+        report.concepts_created += self.reinforce_concept(sigel, &relation.object, None, source_id, count) as usize;
+        self.connect_concepts(sigel, &relation.subject, &relation.object);
+
+        if matches!(relation.relation_type, RelationType::IsA) {
+            // The category is more abstract than its members
+            let subject_level = sigel.memory.semantic_knowledge.concepts[&relation.subject].abstraction_level;
+            if let Some(object) = sigel.memory.semantic_knowledge.concepts.get_mut(&relation.object) {
+                object.abstraction_level = object.abstraction_level.max((subject_level + 0.1).min(1.0));
+            }
+        }
+
+        let relationships = &mut sigel.memory.semantic_knowledge.relationships;
+        let position = match existing {
+            Some(position) => {
+                relationships[position].strength = self.accumulate(relationships[position].strength, count);
+                report.relations_reinforced += count as usize;
+                position
+            },
+            None => {
+                relationships.push(ConceptRelation {
+                    from: relation.subject.clone(),
+                    to: relation.object.clone(),
+                    relation_type: relation.relation_type.clone(),
+                    strength: self.accumulate(self.evidence_weight, count - 1),
+                    sources: SourceCounts::default(),
+                });
+                report.relations_created += 1;
+                report.relations_reinforced += count as usize - 1;
+                relationships.len() - 1
+            },
+        };
+        if let Some(source_id) = source_id {
+            relationships[position].sources.record(source_id, count);
+        }
+        position
+    }
+
+    /// Returns true when a new concept node was created.
+    fn reinforce_concept(&self, sigel: &mut Sigel, name: &str, definition: Option<&str>, source_id: Option<u32>, count: u32) -> bool {
+        let concepts = &mut sigel.memory.semantic_knowledge.concepts;
+        let created = !concepts.contains_key(name);
+        let concept = concepts.entry(name.to_string()).or_insert_with(|| ConceptNode {
+            name: name.to_string(),
+            definition: String::new(),
+            connections: Vec::new(),
+            abstraction_level: 0.5,
+            certainty: 0.0,
+            sources: SourceCounts::default(),
+        });
+
+        concept.certainty = self.accumulate(concept.certainty, count);
+        if let Some(source_id) = source_id {
+            concept.sources.record(source_id, count);
+        }
+        if let Some(definition) = definition {
+            if concept.definition.is_empty() {
+                concept.definition = definition.to_string();
+            }
+        }
+
+        created
+    }
+
+    fn connect_concepts(&self, sigel: &mut Sigel, a: &str, b: &str) {
+        for (from, to) in [(a, b), (b, a)] {
+            if let Some(concept) = sigel.memory.semantic_knowledge.concepts.get_mut(from) {
+                if !concept.connections.iter().any(|c| c == to) {
+                    concept.connections.push(to.to_string());
+                }
+            }
+        }
+    }
+
+    /// Noisy-or accumulation: each new piece of evidence closes part of the remaining gap.
+    fn accumulate(&self, current: f64, count: u32) -> f64 {
+        if count == 0 {
+            return current;
+        }
+        let remaining = (1.0 - current) * (1.0 - self.evidence_weight).powi(count.min(i32::MAX as u32) as i32);
+        (1.0 - remaining).clamp(0.0, 1.0)
+    }
+
+    fn extract_examples(&self, before: &str, after: &str) -> Vec<ExtractedRelation> {
+        let Some(category) = self.subject_phrase(before) else { return Vec::new() };
+
+        after
+            .split([',', ';'])
+            // The example list ends where the main clause resumes ("..., such as x and y, are ...")
+            .take_while(|part| part.split_whitespace().next().is_none_or(|w| !AUXILIARY_VERBS.contains(&w)))
+            .flat_map(|part| part.split(" and ").flat_map(|p| p.split(" or ")))
+            .filter_map(|example| self.object_phrase(example))
+            .filter(|example| *example != category)
+            .map(|example| ExtractedRelation {
+                subject: example,
+                object: category.clone(),
+                relation_type: RelationType::IsA,
+                definition: None,
+            })
+            .collect()
+    }
+
+    fn normalize(&self, sentence: &str) -> String {
+        sentence
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == ',' || c == ';' || c == '-' || c == '+' || c == '#' || c == '\'' { c } else { ' ' })
+            .collect::<String>()
+            .replace(',', " , ")
+            .replace(';', " ; ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace(" , ", ", ")
+            .replace(" ; ", "; ")
+    }
+
+    /// The subject is the noun phrase closest to the marker, within the current clause.
+    fn subject_phrase(&self, before: &str) -> Option<String> {
+        let clause = before.rsplit([',', ';']).next().unwrap_or(before);
+        let words: Vec<&str> = clause.split_whitespace().collect();
+        let start = words.iter()
+            .rposition(|w| PHRASE_BOUNDARIES.contains(w) || DETERMINERS.contains(w))
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+
+        self.build_phrase(&words[start..])
+    }
+
+    /// The object is the noun phrase directly after the marker, up to the next boundary word.
+    fn object_phrase(&self, after: &str) -> Option<String> {
+        let clause = after.split([',', ';']).next().unwrap_or(after);
+        let words: Vec<&str> = clause.split_whitespace()
+            .skip_while(|w| DETERMINERS.contains(w))
+            .take_while(|w| !PHRASE_BOUNDARIES.contains(w) && !AUXILIARY_VERBS.contains(w))
+            .collect();
+
+        self.build_phrase(&words)
+    }
+
+    fn build_phrase(&self, words: &[&str]) -> Option<String> {
+        let words: Vec<&str> = words.iter()
+            .copied()
+            .filter(|w| !DETERMINERS.contains(w))
+            .collect();
+        // English noun phrases are head-final, so keep the trailing words
+        let phrase = words[words.len().saturating_sub(self.max_phrase_words)..].join(" ");
+
+        if phrase.is_empty() || NON_CONCEPTS.contains(&phrase.as_str()) || phrase.chars().all(|c| c.is_numeric()) {
+            None
+        } else {
+            Some(phrase)
+        }
+    }
+}
+
+impl Default for KnowledgeExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+const BUNDLED_PROFILES: &str = include_str!("../data/language_profiles.tsv");
+
+// Shorter text is identified by its function words, or not at all
+const MIN_LETTERS: usize = 12;
+const MIN_CONFIDENCE: f64 = 0.8;
+const SMOOTHING: f64 = 0.5;
+// Log-odds added per stopword, and for the expected language when identifying with a fallback
+const STOPWORD_WEIGHT: f64 = 2.0;
+const PRIOR_WEIGHT: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "but", "if", "of", "to", "in", "on", "at", "by", "for", "with", "from",
+    "into", "about", "as", "is", "are", "was", "were", "be", "been", "have", "has", "had", "do", "does", "did",
+    "will", "would", "could", "should", "can", "not", "no", "i", "me", "my", "you", "your", "he", "his", "she",
+    "her", "it", "its", "we", "our", "they", "their", "them", "this", "that", "these", "those", "there",
+    "what", "which", "who", "when", "where", "how", "than", "then", "so", "very",
+];
+
+const GERMAN_STOPWORDS: &[&str] = &[
+    "der", "die", "das", "den", "dem", "des", "ein", "eine", "einen", "einem", "einer", "und", "oder", "aber",
+    "wenn", "dass", "weil", "als", "wie", "was", "wer", "wo", "ist", "sind", "war", "waren", "sein", "hat",
+    "haben", "habe", "hatte", "wird", "werden", "kann", "nicht", "kein", "keine", "ich", "mich", "mir", "du",
+    "dich", "dir", "er", "sie", "es", "wir", "uns", "ihr", "euch", "sich", "man", "zu", "mit", "von", "für",
+    "auf", "aus", "bei", "nach", "über", "um", "im", "in", "an", "auch", "noch", "nur", "schon", "so", "sehr",
+    "doch", "ja",
+];
+
+const SPANISH_STOPWORDS: &[&str] = &[
+    "el", "la", "los", "las", "un", "una", "unos", "unas", "y", "o", "pero", "si", "que", "de", "del", "al",
+    "a", "en", "con", "por", "para", "sin", "sobre", "es", "son", "era", "fue", "ser", "está", "están",
+    "estar", "ha", "han", "hay", "no", "sí", "se", "lo", "le", "les", "me", "te", "nos", "yo", "tú", "él",
+    "ella", "ellos", "mi", "tu", "su", "sus", "nuestro", "como", "más", "muy", "ya", "también", "porque",
+    "cuando", "donde", "qué", "cómo", "este", "esta", "esto", "ese", "esa", "eso",
+];
+
+/// A prefix and suffix placed around the body of a response.
+pub type Frame = (&'static str, &'static str);
+
+/// Fixed phrasing for responses in one language; personas supply the rest.
+#[derive(Debug)]
+pub struct ResponsePhrases {
+    pub cosmic_insight: Frame,
+    /// Said when nothing learned relates to the prompt.
+    pub no_knowledge: &'static str,
+}
+
+const ENGLISH_PHRASES: ResponsePhrases = ResponsePhrases {
+    cosmic_insight: ("(Cosmic insight: ", ")"),
+    no_knowledge: "I haven't learned enough about that yet.",
+};
+
+const GERMAN_PHRASES: ResponsePhrases = ResponsePhrases {
+    cosmic_insight: ("(Kosmische Einsicht: ", ")"),
+    no_knowledge: "Darüber habe ich noch nicht genug gelernt.",
+};
+
+const SPANISH_PHRASES: ResponsePhrases = ResponsePhrases {
+    cosmic_insight: ("(Visión cósmica: ", ")"),
+    no_knowledge: "Todavía no he aprendido lo suficiente sobre eso.",
+};
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::German, Language::Spanish];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::Spanish => "es",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "German",
+            Language::Spanish => "Spanish",
+        }
+    }
+
+    /// Parse an ISO 639-1 code or an English or native language name.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "en" | "english" => Some(Language::English),
+            "de" | "german" | "deutsch" => Some(Language::German),
+            "es" | "spanish" | "español" | "espanol" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    pub fn stopwords(&self) -> &'static [&'static str] {
+        match self {
+            Language::English => ENGLISH_STOPWORDS,
+            Language::German => GERMAN_STOPWORDS,
+            Language::Spanish => SPANISH_STOPWORDS,
+        }
+    }
+
+    pub fn is_stopword(&self, word: &str) -> bool {
+        self.stopwords().contains(&word.to_lowercase().as_str())
+    }
+
+    pub fn phrases(&self) -> &'static ResponsePhrases {
+        match self {
+            Language::English => &ENGLISH_PHRASES,
+            Language::German => &GERMAN_PHRASES,
+            Language::Spanish => &SPANISH_PHRASES,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Whether a word is a stopword in any supported language.
+pub fn is_stopword(word: &str) -> bool {
+    let word = word.to_lowercase();
+    Language::ALL.iter().any(|language| language.stopwords().contains(&word.as_str()))
+}
+
+/// Wrap a response body in a frame from `ResponsePhrases`.
+pub fn frame((prefix, suffix): Frame, body: &str) -> String {
+    format!("{}{}{}", prefix, body, suffix)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Detection {
+    pub language: Language,
+    /// Probability (0.0-1.0) of `language` among the supported languages.
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone)]
+struct LanguageProfile {
+    language: Language,
+    log_probabilities: HashMap<String, f64>,
+    unseen: f64,
+}
+
+/// Identifies the language of text from character trigram profiles.
+///
+/// Each profile holds trigram counts from sample text in one language; text is scored
+/// with a smoothed naive Bayes model over its own trigrams, plus a bonus for each of the
+/// language's stopwords. The profile format is one
+/// entry per line: `language <TAB> trigram <TAB> count`, with `_` marking word
+/// boundaries. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone)]
+pub struct LanguageIdentifier {
+    profiles: Vec<LanguageProfile>,
+}
+
+impl LanguageIdentifier {
+    pub fn new() -> Self {
+        Self::bundled()
+    }
+
+    /// The profiles shipped with Sigmos (`data/language_profiles.tsv`).
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_PROFILES).expect("bundled language profiles are well-formed")
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path.as_ref())?;
+        Self::parse(&content)
+            .map_err(|e| format!("{}: {}", path.as_ref().display(), e).into())
+    }
+
+    pub fn parse(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut counts: HashMap<Language, HashMap<String, u64>> = HashMap::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 3 {
+                return Err(format!("line {}: expected 'language trigram count'", line_number + 1).into());
+            }
+            let language = Language::from_code(fields[0])
+                .ok_or_else(|| format!("line {}: unsupported language '{}'", line_number + 1, fields[0]))?;
+            if fields[1].chars().count() != 3 {
+                return Err(format!("line {}: '{}' is not a trigram", line_number + 1, fields[1]).into());
+            }
+            let count: u64 = fields[2].parse()
+                .map_err(|_| format!("line {}: '{}' is not a count", line_number + 1, fields[2]))?;
+
+            *counts.entry(language).or_default().entry(fields[1].to_lowercase()).or_insert(0) += count;
+        }
+
+        let profiles: Vec<LanguageProfile> = Language::ALL.iter()
+            .filter_map(|&language| {
+                let trigrams = counts.remove(&language)?;
+                let denominator = trigrams.values().sum::<u64>() as f64 + SMOOTHING * (trigrams.len() + 1) as f64;
+                Some(LanguageProfile {
+                    language,
+                    log_probabilities: trigrams.into_iter()
+                        .map(|(trigram, count)| (trigram, ((count as f64 + SMOOTHING) / denominator).ln()))
+                        .collect(),
+                    unseen: (SMOOTHING / denominator).ln(),
+                })
+            })
+            .collect();
+
+        if profiles.is_empty() {
+            return Err("no language profiles found".into());
+        }
+        Ok(Self { profiles })
+    }
+
+    /// The languages this identifier has profiles for.
+    pub fn languages(&self) -> Vec<Language> {
+        self.profiles.iter().map(|p| p.language).collect()
+    }
+
+    /// The most likely language of `text`, or `None` if it contains no letters.
+    pub fn detect(&self, text: &str) -> Option<Detection> {
+        self.detect_with_prior(text, None)
+    }
+
+    /// The language of `text`, or `fallback` when it is too short or ambiguous to tell.
+    ///
+    /// `fallback` is also favoured over other languages, so text that only weakly
+    /// resembles another language keeps it, e.g. a borrowed phrase inside a document.
+    pub fn identify(&self, text: &str, fallback: Language) -> Language {
+        let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+        if letters >= MIN_LETTERS {
+            if let Some(detection) = self.detect_with_prior(text, Some(fallback)) {
+                if detection.confidence >= MIN_CONFIDENCE {
+                    return detection.language;
+                }
+            }
+        }
+
+        self.identify_by_stopwords(text).unwrap_or(fallback)
+    }
+
+    fn detect_with_prior(&self, text: &str, prior: Option<Language>) -> Option<Detection> {
+        let trigrams = trigrams(text);
+        if trigrams.is_empty() {
+            return None;
+        }
+        let words = words(text);
+
+        let scores: Vec<f64> = self.profiles.iter()
+            .map(|profile| {
+                let likelihood: f64 = trigrams.iter()
+                    .map(|t| profile.log_probabilities.get(t).copied().unwrap_or(profile.unseen))
+                    .sum();
+                let stopwords = words.iter().filter(|w| profile.language.stopwords().contains(&w.as_str())).count();
+                let prior = if prior == Some(profile.language) { PRIOR_WEIGHT } else { 0.0 };
+                likelihood + stopwords as f64 * STOPWORD_WEIGHT + prior
+            })
+            .collect();
+
+        // Ties go to the profile listed first, so results never depend on map order
+        let (best, &best_score) = scores.iter()
+            .enumerate()
+            .fold((0, &f64::NEG_INFINITY), |best, (idx, score)| if *score > *best.1 { (idx, score) } else { best });
+        let total: f64 = scores.iter().map(|score| (score - best_score).exp()).sum();
+
+        Some(Detection {
+            language: self.profiles[best].language,
+            confidence: 1.0 / total,
+        })
+    }
+
+    fn identify_by_stopwords(&self, text: &str) -> Option<Language> {
+        let words = words(text);
+        let mut counts: Vec<(Language, usize)> = self.profiles.iter()
+            .map(|p| (p.language, words.iter().filter(|w| p.language.stopwords().contains(&w.as_str())).count()))
+            .collect();
+        counts.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+        match counts.as_slice() {
+            [(language, best), rest @ ..] if *best > 0 && rest.first().is_none_or(|(_, second)| second < best) => Some(*language),
+            _ => None,
+        }
+    }
+}
+
+impl Default for LanguageIdentifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Character trigrams of each word, padded with `_` at word boundaries.
+fn trigrams(text: &str) -> Vec<String> {
+    let mut trigrams = Vec::new();
+    for word in words(text) {
+        let chars: Vec<char> = std::iter::once('_')
+            .chain(word.chars())
+            .chain(std::iter::once('_'))
+            .collect();
+        trigrams.extend(chars.windows(3).map(|window| window.iter().collect::<String>()));
+    }
+    trigrams
+}
@@ -1,15 +1,100 @@
 use crate::sigel::*;
-use std::collections::HashMap;
-use std::path::Path;
+use crate::knowledge_extraction::KnowledgeExtractor;
+use crate::procedural_learning::ProceduralLearner;
+use crate::sentiment::{SentimentScorer, VadLexicon};
+use crate::knowledge_delta::KnowledgeDelta;
+use crate::deduplication::{DedupConfig, DedupKind, DedupReport, Deduplicator};
+use crate::word_vectors::{WordVectorConfig, WordVectorTrainer};
+use crate::language::{Language, LanguageIdentifier};
+use crate::config::SigmosConfig;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::fs;
-use rand::Rng;
+use std::sync::Mutex;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 use rayon::prelude::*;
 
-pub struct LearningEngine;
+// Fixed seed so parallel training is reproducible end to end
+const PARALLEL_DEEP_LEARNING_SEED: u64 = 0x5167_4d05;
+// Share of the style mixture one message's evidence moves; a pure style takes about
+// sixteen consistent messages to be overtaken
+const STYLE_DRIFT_RATE: f64 = 0.05;
+const STYLE_SWITCH_MARGIN: f64 = 0.1;
+
+pub struct LearningEngine {
+    knowledge_extractor: KnowledgeExtractor,
+    procedural_learner: ProceduralLearner,
+    sentiment_scorer: SentimentScorer,
+    dedup_config: Option<DedupConfig>,
+    dedup_report: Mutex<DedupReport>,
+    word_vector_config: Option<WordVectorConfig>,
+    language_identifier: LanguageIdentifier,
+    // Sentences with more words than the first and fewer than the second become memories
+    memorable_sentence_words: (usize, usize),
+    awareness_growth_rate: f64,
+    max_awareness_depth: f64,
+}
 
 impl LearningEngine {
     pub fn new() -> Self {
-        Self
+        Self {
+            knowledge_extractor: KnowledgeExtractor::new(),
+            procedural_learner: ProceduralLearner::new(),
+            sentiment_scorer: SentimentScorer::new(),
+            dedup_config: None,
+            dedup_report: Mutex::new(DedupReport::default()),
+            word_vector_config: Some(WordVectorConfig::default()),
+            language_identifier: LanguageIdentifier::new(),
+            memorable_sentence_words: (5, 50),
+            awareness_growth_rate: 0.001,
+            max_awareness_depth: 1.0,
+        }
+    }
+
+    /// An engine with the sentence lengths of `[learning]`, the awareness growth of
+    /// `[consciousness]` and the deduplication of `[training]`.
+    pub fn from_config(config: &SigmosConfig) -> Self {
+        Self {
+            dedup_config: config.training.deduplicate.then(|| DedupConfig {
+                threshold: config.training.dedup_threshold,
+                ..DedupConfig::default()
+            }),
+            memorable_sentence_words: (config.learning.min_sentence_length, config.learning.max_sentence_length),
+            awareness_growth_rate: config.consciousness.awareness_growth_rate,
+            max_awareness_depth: config.consciousness.max_awareness_depth,
+            ..Self::new()
+        }
+    }
+
+    fn is_memorable(&self, words: usize) -> bool {
+        let (min, max) = self.memorable_sentence_words;
+        words > min && words < max
+    }
+
+    /// Configure the word vectors built at the end of training; `None` skips building them.
+    pub fn set_word_vectors(&mut self, config: Option<WordVectorConfig>) {
+        self.word_vector_config = config;
+    }
+
+    /// Configure near-duplicate skipping during training; `None`, the default, disables it.
+    pub fn set_deduplication(&mut self, config: Option<DedupConfig>) {
+        self.dedup_config = config;
+    }
+
+    /// What deduplication skipped during the most recent training run.
+    pub fn last_dedup_report(&self) -> DedupReport {
+        self.dedup_report.lock().unwrap().clone()
+    }
+
+    /// Replace the trigram profiles used to sort training text into languages.
+    pub fn set_language_identifier(&mut self, identifier: LanguageIdentifier) {
+        self.language_identifier = identifier;
+    }
+
+    /// Replace the VAD lexicon used for emotional scoring during training.
+    pub fn set_lexicon(&mut self, lexicon: VadLexicon) {
+        self.sentiment_scorer = SentimentScorer::with_lexicon(lexicon);
     }
 
     pub fn train_from_text_files<P: AsRef<Path>>(&self, sigel: &mut Sigel, text_directory: P) -> Result<(), Box<dyn std::error::Error>> {
@@ -19,25 +104,12 @@ impl LearningEngine {
         println!("📚 Integrating inherent programming consciousness...");
         self.integrate_coding_knowledge(sigel)?;
         
-        let paths = fs::read_dir(&text_directory)?;
-        let text_files: Vec<_> = paths
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| {
-                entry.path().extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|ext| ext == "txt")
-                    .unwrap_or(false)
-            })
-            .collect();
-
-        if text_files.is_empty() {
-            return Err("No .txt files found in directory".into());
-        }
+        let text_files = self.list_text_files(&text_directory)?;
 
         // Calculate total size for progress tracking
         let mut total_bytes = 0u64;
-        for file_entry in &text_files {
-            if let Ok(metadata) = file_entry.metadata() {
+        for file_path in &text_files {
+            if let Ok(metadata) = fs::metadata(file_path) {
                 total_bytes += metadata.len();
             }
         }
@@ -49,6 +121,7 @@ impl LearningEngine {
 
         let mut file_count = 0;
         let mut processed_bytes = 0u64;
+        let mut deduplicator = self.dedup_config.clone().map(Deduplicator::new);
 
         // Process files in chunks to manage memory
         const CHUNK_SIZE: usize = 3; // Process 3 files at a time
@@ -58,11 +131,10 @@ impl LearningEngine {
             println!("📝 Processing chunk {}/{} ({} files)...", 
                 chunk_idx + 1, chunks.len(), chunk.len());
             
-            let mut chunk_content = String::new();
+            let mut chunk_documents: Vec<(String, String, Language)> = Vec::new();
             
-            for file_entry in *chunk {
-                let file_path = file_entry.path();
-                let file_size = file_entry.metadata()
+            for file_path in *chunk {
+                let file_size = fs::metadata(file_path)
                     .map(|m| m.len())
                     .unwrap_or(0);
                 
@@ -71,17 +143,27 @@ impl LearningEngine {
                     file_size as f64 / 1024.0
                 );
                 
-                match fs::read_to_string(&file_path) {
+                match fs::read_to_string(file_path) {
                     Ok(content) => {
+                        let source = file_path.to_string_lossy();
+                        processed_bytes += file_size;
+                        let progress = (processed_bytes as f64 / total_bytes as f64) * 100.0;
+                        
+                        if let Some(found) = deduplicator.as_mut().and_then(|d| d.check_document(&content, &source)) {
+                            println!("⏭️  near-duplicate of {} ({:.0}% similar), skipped ({:.1}% complete)",
+                                found.duplicate_of, found.similarity * 100.0, progress);
+                            continue;
+                        }
+                        
+                        // Sentences too short to identify are assumed to share the document's language
+                        let language = self.language_identifier.identify(&content, Language::default());
+                        
                         // Process individual file for immediate pattern recognition
-                        self.process_text_file(sigel, &content, &file_path.to_string_lossy());
+                        let kept_content = self.process_text_file(sigel, &content, &source, language, deduplicator.as_mut());
                         
-                        chunk_content.push_str(&content);
-                        chunk_content.push('\n');
+                        chunk_documents.push((source.into_owned(), kept_content, language));
                         file_count += 1;
-                        processed_bytes += file_size;
                         
-                        let progress = (processed_bytes as f64 / total_bytes as f64) * 100.0;
                         println!("✅ ({:.1}% complete)", progress);
                     },
                     Err(e) => {
@@ -92,64 +174,429 @@ impl LearningEngine {
             }
             
             // Process chunk for patterns immediately to free memory
-            if !chunk_content.is_empty() {
+            if !chunk_documents.is_empty() {
                 println!("   🧠 Processing chunk patterns...");
-                self.extract_patterns(sigel, &chunk_content)?;
-                
-                // Update corpus size
-                sigel.learning_state.text_corpus_size += chunk_content.len();
-                
-                // Clear chunk content to free memory
-                chunk_content.clear();
-                chunk_content.shrink_to_fit();
+                for (source, kept_content, language) in chunk_documents.drain(..) {
+                    self.extract_patterns(sigel, &kept_content, &source, language)?;
+                    
+                    // Update corpus size
+                    sigel.learning_state.text_corpus_size += kept_content.len() + 1;
+                }
             }
         }
 
         println!("📊 Processed {} text files, beginning deep learning phase...", file_count);
+        self.finish_dedup_report(deduplicator);
 
         // Deep learning phase with accumulated patterns
         println!("🧠 Beginning deep learning phase with existing patterns...");
         // Since we processed files in chunks, work with the patterns already extracted
-        self.deep_learning_phase_from_patterns(sigel)?;
+        self.deep_learning_phase_from_patterns(sigel, &mut rand::thread_rng())?;
+        self.build_word_vectors(sigel);
         
         // Final consciousness evolution
         println!("✨ Evolving consciousness...");
         sigel.evolve();
         
+        self.print_training_summary(sigel);
+        
+        Ok(())
+    }
+
+    /// Train with workers building independent `KnowledgeDelta`s per file, merged deterministically.
+    ///
+    /// The resulting Sigel is the same for any `threads` value; `None` uses all cores.
+    pub fn train_from_text_files_parallel<P: AsRef<Path>>(&self, sigel: &mut Sigel, text_directory: P, threads: Option<usize>) -> Result<(), Box<dyn std::error::Error>> {
+        println!("🌌 Beginning parallel Sigel consciousness expansion from text corpus...");
+        
+        println!("📚 Integrating inherent programming consciousness...");
+        self.integrate_coding_knowledge(sigel)?;
+        
+        let text_files = self.list_text_files(&text_directory)?;
+        
+        let mut pool_builder = rayon::ThreadPoolBuilder::new();
+        if let Some(threads) = threads {
+            pool_builder = pool_builder.num_threads(threads);
+        }
+        let pool = pool_builder.build()?;
+        
+        println!("📊 Found {} text files, training on {} threads...", text_files.len(), pool.current_num_threads());
+        
+        // Read every file once, for deduplication and the deltas alike
+        let contents: Vec<Option<String>> = pool.install(|| {
+            text_files
+                .par_iter()
+                .map(|file_path| fs::read_to_string(file_path)
+                    .map_err(|e| eprintln!("Warning: Could not read {:?}: {}", file_path, e))
+                    .ok())
+                .collect()
+        });
+        
+        let skip_plan = match &self.dedup_config {
+            Some(config) => {
+                println!("🔍 Detecting near-duplicate documents and sentences...");
+                pool.install(|| self.plan_deduplication(&text_files, &contents, config.clone()))
+            },
+            None => vec![Some(HashSet::new()); text_files.len()],
+        };
+        
+        let delta = pool.install(|| {
+            text_files
+                .par_iter()
+                .zip(contents.par_iter())
+                .zip(skip_plan.par_iter())
+                .filter_map(|((file_path, content), skip)| Some((file_path, content.as_ref()?, skip.as_ref()?)))
+                .map(|(file_path, content, skip)| self.delta_from_document_filtered(content, &file_path.to_string_lossy(), skip))
+                .reduce(KnowledgeDelta::new, KnowledgeDelta::merge)
+        });
+        
+        println!("🔀 Merging knowledge from {} files ({:.1} MB)...", delta.documents, delta.corpus_bytes as f64 / 1024.0 / 1024.0);
+        self.apply_delta(sigel, delta);
+        
+        println!("🧠 Beginning deep learning phase with existing patterns...");
+        let mut rng = StdRng::seed_from_u64(PARALLEL_DEEP_LEARNING_SEED);
+        self.deep_learning_phase_from_patterns(sigel, &mut rng)?;
+        self.build_word_vectors(sigel);
+        
+        println!("✨ Evolving consciousness...");
+        sigel.evolve();
+        
+        self.print_training_summary(sigel);
+        
+        Ok(())
+    }
+
+    /// Decide which files and sentences to skip, in sorted file order so the first copy always wins.
+    ///
+    /// Signatures are computed in parallel; `None` marks a whole document as a duplicate.
+    fn plan_deduplication(&self, text_files: &[PathBuf], contents: &[Option<String>], config: DedupConfig) -> Vec<Option<HashSet<usize>>> {
+        let mut deduplicator = Deduplicator::new(config);
+        
+        let signatures: Vec<_> = contents
+            .par_iter()
+            .map(|content| {
+                let content = content.as_deref()?;
+                let sentence_signatures: Vec<_> = content.split(&['.', '!', '?'][..])
+                    .enumerate()
+                    .filter(|(_, sentence)| deduplicator.is_checkable_sentence(sentence))
+                    .map(|(idx, sentence)| (idx, deduplicator.signature(sentence), sentence.chars().take(80).collect::<String>()))
+                    .collect();
+                Some((deduplicator.signature(content), content.chars().take(80).collect::<String>(), sentence_signatures))
+            })
+            .collect();
+        
+        let plan = text_files.iter()
+            .zip(signatures)
+            .map(|(file_path, signatures)| {
+                let source = file_path.to_string_lossy();
+                // Unreadable files were reported when they were read
+                let Some((document_signature, preview, sentence_signatures)) = signatures else { return Some(HashSet::new()) };
+                
+                if let Some(found) = deduplicator.check_signature(DedupKind::Document, document_signature, &source, &preview) {
+                    println!("   ⏭️  {} is a near-duplicate of {} ({:.0}% similar), skipped", source, found.duplicate_of, found.similarity * 100.0);
+                    return None;
+                }
+                
+                Some(sentence_signatures.into_iter()
+                    .filter_map(|(idx, signature, preview)| {
+                        deduplicator.check_signature(DedupKind::Sentence, signature, &format!("{}#{}", source, idx), &preview)
+                            .map(|_| idx)
+                    })
+                    .collect())
+            })
+            .collect();
+        
+        self.finish_dedup_report(Some(deduplicator));
+        plan
+    }
+
+    fn build_word_vectors(&self, sigel: &mut Sigel) {
+        if let Some(config) = &self.word_vector_config {
+            println!("🧮 Building word vectors from co-occurrence statistics...");
+            let count = WordVectorTrainer::new(config.clone()).train(sigel);
+            println!("   ✅ {} words embedded in {} dimensions", count, sigel.memory.semantic_knowledge.word_vectors.dimensions);
+        }
+    }
+
+    fn finish_dedup_report(&self, deduplicator: Option<Deduplicator>) {
+        let report = deduplicator.map(Deduplicator::into_report).unwrap_or_default();
+        if report.documents_skipped > 0 || report.sentences_skipped > 0 {
+            println!("   🧹 Deduplication: {}", report.summary());
+        }
+        *self.dedup_report.lock().unwrap() = report;
+    }
+
+    /// Learn everything `process_text_file` and `extract_patterns` would from one document, without a Sigel.
+    pub fn delta_from_document(&self, content: &str, source: &str) -> KnowledgeDelta {
+        self.delta_from_document_filtered(content, source, &HashSet::new())
+    }
+
+    /// Like `delta_from_document`, skipping the sentences at the given indices.
+    pub fn delta_from_document_filtered(&self, content: &str, source: &str, skip_sentences: &HashSet<usize>) -> KnowledgeDelta {
+        let mut delta = KnowledgeDelta::new();
+        delta.documents = 1;
+        delta.corpus_bytes = content.len() as u64;
+        delta.sources.insert(source.to_string());
+        let document_language = self.language_identifier.identify(content, Language::default());
+        
+        for skill in self.procedural_learner.extract_skills(content) {
+            delta.record_skill(skill.name, skill.steps);
+        }
+        
+        for (idx, sentence) in content.split(&['.', '!', '?'][..]).enumerate() {
+            if skip_sentences.contains(&idx) {
+                delta.corpus_bytes = delta.corpus_bytes.saturating_sub(sentence.len() as u64 + 1);
+                continue;
+            }
+            
+            let words: Vec<&str> = sentence.split_whitespace().collect();
+            let language = self.language_identifier.identify(sentence, document_language);
+            if !words.is_empty() {
+                delta.record_sentence(language);
+            }
+            
+            for window in words.windows(3) {
+                delta.record_word(window[1].to_lowercase(), format!("{} {}", window[0], window[2]), source, language);
+                
+                for (a, b) in [(window[0], window[1]), (window[1], window[2])] {
+                    let (a, b) = (Self::association_word(a), Self::association_word(b));
+                    if !a.is_empty() && !b.is_empty() {
+                        delta.record_association(&a, &b, source);
+                    }
+                }
+            }
+            
+            for relation in self.knowledge_extractor.extract_relations(sentence) {
+                delta.record_relation(relation, source);
+            }
+            
+            if let Some((score, context_words)) = self.sentiment_scorer.emotional_context(sentence) {
+                for word in context_words {
+                    delta.record_emotion(word, &score);
+                }
+            }
+            
+            if self.is_memorable(words.len()) {
+                delta.record_memory(source.to_string(), idx, sentence.trim().to_string());
+            }
+            
+            if sentence.trim().len() > 10 {
+                for (pattern, _) in self.analyze_sentence_patterns(sentence) {
+                    delta.record_ngram(pattern, source, language);
+                }
+            }
+        }
+        
+        delta
+    }
+
+    /// Fold a merged delta into a Sigel, visiting keys in sorted order.
+    pub fn apply_delta(&self, sigel: &mut Sigel, delta: KnowledgeDelta) {
+        let KnowledgeDelta {
+            corpus_bytes,
+            sources,
+            word_counts,
+            mut word_contexts,
+            associations,
+            ngram_counts,
+            relations,
+            skills,
+            emotional_evidence,
+            memories,
+            languages,
+            ..
+        } = delta;
+        
+        let source_ids: HashMap<String, u32> = sources.into_iter()
+            .map(|source| {
+                let id = sigel.provenance.source_id(&source);
+                (source, id)
+            })
+            .collect();
+        let source_counts = |counts: &BTreeMap<String, u64>| {
+            let mut result = SourceCounts::default();
+            for (source, &count) in counts {
+                result.record(source_ids[source], count.min(u32::MAX as u64) as u32);
+            }
+            result
+        };
+        
+        for (word, counts) in word_counts {
+            let contexts = word_contexts.remove(&word).unwrap_or_default();
+            let knowledge = sigel.memory.semantic_knowledge.vocabulary
+                .entry(word)
+                .or_default();
+            knowledge.frequency += counts.values().sum::<u64>() as f64;
+            knowledge.sources.merge(&source_counts(&counts));
+            for context in contexts {
+                if !knowledge.contexts.contains(&context) {
+                    knowledge.contexts.push(context);
+                }
+            }
+        }
+        
+        for (word, neighbors) in associations {
+            let recognition = &mut sigel.consciousness.pattern_recognition;
+            let network = recognition.semantic_networks.entry(word.clone()).or_default();
+            for (neighbor, counts) in neighbors {
+                network.extend(std::iter::repeat_n(neighbor.clone(), counts.values().sum::<u64>() as usize));
+                recognition.association_sources.entry(association_key(&word, &neighbor)).or_default().merge(&source_counts(&counts));
+            }
+        }
+        
+        let mut relation_index = self.knowledge_extractor.relation_index(sigel);
+        for (relation, counts) in relations.into_values() {
+            for (source, count) in counts {
+                let count = count.min(u32::MAX as u64) as u32;
+                self.knowledge_extractor.integrate_relation_evidence(sigel, &relation, Some(source_ids[&source]), count, &mut relation_index);
+            }
+        }
+        
+        for (name, (steps, count)) in skills {
+            let skill = self.procedural_learner.new_skill(name, steps);
+            self.procedural_learner.integrate_skills(sigel, vec![skill; count as usize]);
+        }
+        
+        for (word, evidence) in emotional_evidence {
+            self.sentiment_scorer.apply_emotional_evidence(sigel, &word, &evidence.mean(), evidence.occurrences);
+        }
+        
+        for ((source, _), content) in memories {
+            let emotional_weight = self.sentiment_scorer.valence(&content);
+            let source_id = source_ids[&source];
+            sigel.add_memory_from_source(content, source, emotional_weight, source_id);
+        }
+        
+        for (pattern, counts) in ngram_counts {
+            let n = pattern.split_whitespace().count().max(1);
+            let count: u64 = counts.values().sum();
+            let recognition = &mut sigel.consciousness.pattern_recognition;
+            *recognition.linguistic_patterns
+                .entry(pattern.clone())
+                .or_insert(0.0) += count as f64 / n as f64;
+            recognition.pattern_sources.entry(pattern).or_default().merge(&source_counts(&counts));
+        }
+        let recognition = &mut sigel.consciousness.pattern_recognition;
+        recognition.linguistic_patterns.retain(|_, &mut strength| strength > 0.1);
+        let patterns = &recognition.linguistic_patterns;
+        recognition.pattern_sources.retain(|pattern, _| patterns.contains_key(pattern));
+        
+        for (language, counts) in languages {
+            let partition = sigel.memory.semantic_knowledge.languages.entry(language).or_default();
+            partition.sentences += counts.sentences;
+            for (word, count) in counts.word_counts {
+                let occurrences = partition.vocabulary.entry(word).or_insert(0);
+                *occurrences = occurrences.saturating_add(count.min(u32::MAX as u64) as u32);
+            }
+            for (pattern, count) in counts.ngram_counts {
+                let n = pattern.split_whitespace().count().max(1);
+                *partition.patterns.entry(pattern).or_insert(0.0) += count as f64 / n as f64;
+            }
+        }
+        sigel.prune_language_partitions();
+        
+        sigel.learning_state.text_corpus_size += corpus_bytes as usize;
+    }
+
+    fn list_text_files<P: AsRef<Path>>(&self, text_directory: P) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let mut text_files: Vec<PathBuf> = fs::read_dir(&text_directory)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| ext == "txt")
+                    .unwrap_or(false)
+            })
+            .collect();
+        
+        if text_files.is_empty() {
+            return Err("No .txt files found in directory".into());
+        }
+        
+        text_files.sort();
+        Ok(text_files)
+    }
+
+    fn print_training_summary(&self, sigel: &Sigel) {
         // Enhanced final stats
         println!("🎓 Sigel '{}' consciousness expansion complete!", sigel.name);
         println!("   📈 Training iterations: {}", sigel.learning_state.training_iterations);
         println!("   📚 Vocabulary: {} words", sigel.memory.semantic_knowledge.vocabulary.len());
         println!("   🔗 Linguistic patterns: {}", sigel.consciousness.pattern_recognition.linguistic_patterns.len());
+        println!("   🧩 Concepts: {} ({} relations)",
+            sigel.memory.semantic_knowledge.concepts.len(),
+            sigel.memory.semantic_knowledge.relationships.len()
+        );
+        println!("   🛠️  Skills: {}", sigel.memory.procedural_skills.len());
+        let mut languages: Vec<_> = sigel.memory.semantic_knowledge.languages.iter().collect();
+        if !languages.is_empty() {
+            languages.sort_by(|a, b| b.1.sentences.cmp(&a.1.sentences).then(a.0.cmp(b.0)));
+            let summary: Vec<String> = languages.iter()
+                .map(|(language, partition)| format!("{} ({} sentences, {} words)", language, partition.sentences, partition.vocabulary.len()))
+                .collect();
+            println!("   🌍 Languages: {}", summary.join(", "));
+        }
         println!("   🌟 Consciousness depth: {:.3}", sigel.consciousness.awareness_depth);
-        
-        Ok(())
     }
 
-    fn process_text_file(&self, sigel: &mut Sigel, content: &str, source: &str) {
+    /// Learn from one document, returning the text that was kept after deduplication.
+    fn process_text_file(&self, sigel: &mut Sigel, content: &str, source: &str, document_language: Language, mut deduplicator: Option<&mut Deduplicator>) -> String {
+        // Instructions span lines and sentences, so look for them in the whole document first
+        self.procedural_learner.learn_skills(sigel, content);
+        let source_id = sigel.provenance.source_id(source);
+
         let sentences: Vec<&str> = content.split(&['.', '!', '?'][..]).collect();
+        let mut kept_content = String::with_capacity(content.len());
         
-        for sentence in sentences {
+        for (idx, sentence) in sentences.into_iter().enumerate() {
+            if let Some(deduplicator) = deduplicator.as_deref_mut() {
+                if deduplicator.check_sentence(sentence, &format!("{}#{}", source, idx)).is_some() {
+                    continue;
+                }
+            }
+            kept_content.push_str(sentence);
+            kept_content.push('.');
+            
             let words: Vec<&str> = sentence.split_whitespace().collect();
+            let language = self.language_identifier.identify(sentence, document_language);
+            if !words.is_empty() {
+                sigel.memory.semantic_knowledge.languages.entry(language).or_default().sentences += 1;
+            }
             
             // Learn individual words and their contexts
             for window in words.windows(3) {
                 if window.len() == 3 {
                     let context = format!("{} {}", window[0], window[2]);
-                    sigel.learn_word(window[1].to_lowercase(), context);
+                    let word = window[1].to_lowercase();
+                    let partition = sigel.memory.semantic_knowledge.languages.entry(language).or_default();
+                    let occurrences = partition.vocabulary.entry(word.clone()).or_insert(0);
+                    *occurrences = occurrences.saturating_add(1);
+                    sigel.learn_word(word.clone(), context);
+                    if let Some(knowledge) = sigel.memory.semantic_knowledge.vocabulary.get_mut(&word) {
+                        knowledge.sources.record(source_id, 1);
+                    }
                     
                     // Build semantic associations
-                    self.build_semantic_association(sigel, window[0], window[1]);
-                    self.build_semantic_association(sigel, window[1], window[2]);
+                    self.build_semantic_association(sigel, window[0], window[1], source_id);
+                    self.build_semantic_association(sigel, window[1], window[2], source_id);
                 }
             }
             
+            // Extract definitional and relational knowledge into the concept graph
+            self.knowledge_extractor.learn_from_sentence(sigel, sentence, Some(source_id));
+
+            // Color co-occurring words with the sentence's emotional tone
+            self.sentiment_scorer.propagate(sigel, sentence);
+            
             // Store as episodic memory if sentence is meaningful
-            if words.len() > 5 && words.len() < 50 {
-                let emotional_weight = self.calculate_emotional_weight(sentence);
-                sigel.add_memory(sentence.trim().to_string(), source.to_string(), emotional_weight);
+            if self.is_memorable(words.len()) {
+                let emotional_weight = self.sentiment_scorer.valence(sentence);
+                sigel.add_memory_from_source(sentence.trim().to_string(), source.to_string(), emotional_weight, source_id);
             }
         }
+        
+        kept_content
     }
 
     fn deep_learning_phase(&self, sigel: &mut Sigel, content: &str) -> Result<(), Box<dyn std::error::Error>> {
@@ -178,16 +625,16 @@ impl LearningEngine {
             let target = words[start_idx + 3];
             
             // Try to predict the next word
-            let predicted = self.predict_next_word(sigel, context);
+            let predicted = self.predict_next_word(sigel, context, &mut rng);
             let accuracy = self.calculate_prediction_accuracy(&predicted, target);
             
             // Update learning based on accuracy
             if accuracy < 0.5 {
                 // Learn this pattern more strongly
-                self.strengthen_pattern(sigel, context, target, learning_rate * 2.0);
+                self.strengthen_pattern(sigel, context, target, learning_rate * 2.0, SourceCounts::default());
             } else {
                 // Reinforce existing good pattern
-                self.strengthen_pattern(sigel, context, target, learning_rate);
+                self.strengthen_pattern(sigel, context, target, learning_rate, SourceCounts::default());
             }
             
             sigel.learning_state.training_iterations += 1;
@@ -197,7 +644,7 @@ impl LearningEngine {
     }
 
     /// Deep learning phase optimized for chunked processing
-    fn deep_learning_phase_from_patterns(&self, sigel: &mut Sigel) -> Result<(), Box<dyn std::error::Error>> {
+    fn deep_learning_phase_from_patterns<R: Rng>(&self, sigel: &mut Sigel, rng: &mut R) -> Result<(), Box<dyn std::error::Error>> {
         let learning_rate = sigel.learning_state.learning_rate;
         
         // Work with existing temporal patterns
@@ -211,7 +658,6 @@ impl LearningEngine {
         println!("   📈 Processing {} temporal patterns for deep learning...", pattern_count);
         
         // Learn from existing temporal patterns
-        let mut rng = rand::thread_rng();
         let sample_size = (pattern_count * 10).max(100).min(5000);
         
         for _ in 0..sample_size {
@@ -223,12 +669,12 @@ impl LearningEngine {
                     let target = &pattern.sequence[3];
                     
                     // Try to predict the next word
-                    let predicted = self.predict_next_word(sigel, &context);
+                    let predicted = self.predict_next_word(sigel, &context, rng);
                     let accuracy = self.calculate_prediction_accuracy(&predicted, target);
                     
                     // Update learning based on accuracy
                     let strength_multiplier = if accuracy < 0.5 { 2.0 } else { 1.0 };
-                    self.strengthen_pattern(sigel, &context, target, learning_rate * strength_multiplier);
+                    self.strengthen_pattern(sigel, &context, target, learning_rate * strength_multiplier, pattern.sources.clone());
                     
                     sigel.learning_state.training_iterations += 1;
                 }
@@ -239,7 +685,7 @@ impl LearningEngine {
         Ok(())
     }
 
-    fn predict_next_word(&self, sigel: &Sigel, context: &[&str]) -> String {
+    fn predict_next_word<R: Rng>(&self, sigel: &Sigel, context: &[&str], rng: &mut R) -> String {
         let context_key = context.join(" ");
         
         // Look for patterns in consciousness matrix
@@ -259,7 +705,6 @@ impl LearningEngine {
         if let Some(last_word) = context.last() {
             if let Some(related_words) = pattern_matrix.semantic_networks.get(&**last_word) {
                 if !related_words.is_empty() {
-                    let mut rng = rand::thread_rng();
                     let idx = rng.gen_range(0..related_words.len());
                     return related_words[idx].clone();
                 }
@@ -289,7 +734,7 @@ impl LearningEngine {
         }
     }
 
-    fn strengthen_pattern(&self, sigel: &mut Sigel, context: &[&str], target: &str, strength: f64) {
+    fn strengthen_pattern(&self, sigel: &mut Sigel, context: &[&str], target: &str, strength: f64, sources: SourceCounts) {
         let mut sequence = context.iter().map(|&s| s.to_string()).collect::<Vec<String>>();
         sequence.push(target.to_string());
         
@@ -297,68 +742,47 @@ impl LearningEngine {
             sequence,
             frequency: strength,
             context_relevance: 1.0,
+            sources: sources.clone(),
         };
         
-        sigel.consciousness.pattern_recognition.temporal_patterns.push(temporal_pattern);
+        let recognition = &mut sigel.consciousness.pattern_recognition;
+        recognition.temporal_patterns.push(temporal_pattern);
         
         // Also strengthen linguistic patterns
         let context_key = context.join(" ");
-        *sigel.consciousness.pattern_recognition.linguistic_patterns
-            .entry(context_key)
+        *recognition.linguistic_patterns
+            .entry(context_key.clone())
             .or_insert(0.0) += strength;
+        if !sources.is_empty() {
+            recognition.pattern_sources.entry(context_key).or_default().merge(&sources);
+        }
+    }
+
+    pub(crate) fn association_word(word: &str) -> String {
+        word.to_lowercase().trim_matches(|c: char| !c.is_alphabetic()).to_string()
     }
 
-    fn build_semantic_association(&self, sigel: &mut Sigel, word1: &str, word2: &str) {
-        let word1_clean = word1.to_lowercase().trim_matches(|c: char| !c.is_alphabetic()).to_string();
-        let word2_clean = word2.to_lowercase().trim_matches(|c: char| !c.is_alphabetic()).to_string();
+    fn build_semantic_association(&self, sigel: &mut Sigel, word1: &str, word2: &str, source_id: u32) {
+        let word1_clean = Self::association_word(word1);
+        let word2_clean = Self::association_word(word2);
         
         if word1_clean.is_empty() || word2_clean.is_empty() {
             return;
         }
         
         // Build bidirectional associations
-        sigel.consciousness.pattern_recognition.semantic_networks
-            .entry(word1_clean.clone())
-            .or_insert_with(Vec::new)
-            .push(word2_clean.clone());
-            
-        sigel.consciousness.pattern_recognition.semantic_networks
-            .entry(word2_clean)
-            .or_insert_with(Vec::new)
-            .push(word1_clean);
-    }
-
-    fn calculate_emotional_weight(&self, text: &str) -> f64 {
-        let positive_words = ["love", "joy", "happy", "wonderful", "amazing", "beautiful", "peace", "harmony"];
-        let negative_words = ["hate", "sad", "terrible", "awful", "pain", "suffer", "angry", "fear"];
-        let profound_words = ["consciousness", "universe", "existence", "meaning", "purpose", "infinite", "eternal"];
-        
-        let text_lower = text.to_lowercase();
-        let mut weight: f64 = 0.0;
-        
-        for word in positive_words {
-            if text_lower.contains(word) {
-                weight += 0.5;
-            }
+        let recognition = &mut sigel.consciousness.pattern_recognition;
+        for (word, neighbor) in [(&word1_clean, &word2_clean), (&word2_clean, &word1_clean)] {
+            recognition.semantic_networks
+                .entry(word.clone())
+                .or_insert_with(Vec::new)
+                .push(neighbor.clone());
+            recognition.association_sources.entry(association_key(word, neighbor)).or_default().record(source_id, 1);
         }
-        
-        for word in negative_words {
-            if text_lower.contains(word) {
-                weight -= 0.3;
-            }
-        }
-        
-        for word in profound_words {
-            if text_lower.contains(word) {
-                weight += 0.8;
-            }
-        }
-        
-        weight.max(-1.0).min(1.0)
     }
 
-    fn extract_patterns(&self, sigel: &mut Sigel, content: &str) -> Result<(), Box<dyn std::error::Error>> {
-        println!("Extracting consciousness patterns from learned knowledge...");
+    fn extract_patterns(&self, sigel: &mut Sigel, content: &str, source: &str, document_language: Language) -> Result<(), Box<dyn std::error::Error>> {
+        let source_id = sigel.provenance.source_id(source);
         
         let sentences: Vec<&str> = content.split(&['.', '!', '?'][..])
             .filter(|s| s.trim().len() > 10)
@@ -367,21 +791,28 @@ impl LearningEngine {
         // Parallel processing for pattern extraction
         let pattern_results: Vec<_> = sentences
             .par_iter()
-            .map(|sentence| self.analyze_sentence_patterns(sentence))
+            .map(|sentence| (self.language_identifier.identify(sentence, document_language), self.analyze_sentence_patterns(sentence)))
             .collect();
         
         // Integrate patterns into sigel consciousness
-        for patterns in pattern_results {
+        let recognition = &mut sigel.consciousness.pattern_recognition;
+        let languages = &mut sigel.memory.semantic_knowledge.languages;
+        for (language, patterns) in pattern_results {
+            let partition = languages.entry(language).or_default();
             for (pattern, strength) in patterns {
-                *sigel.consciousness.pattern_recognition.linguistic_patterns
-                    .entry(pattern)
+                *partition.patterns.entry(pattern.clone()).or_insert(0.0) += strength;
+                *recognition.linguistic_patterns
+                    .entry(pattern.clone())
                     .or_insert(0.0) += strength;
+                recognition.pattern_sources.entry(pattern).or_default().record(source_id, 1);
             }
         }
         
         // Clean up patterns - remove weak ones
-        sigel.consciousness.pattern_recognition.linguistic_patterns
-            .retain(|_, &mut strength| strength > 0.1);
+        recognition.linguistic_patterns.retain(|_, &mut strength| strength > 0.1);
+        let patterns = &recognition.linguistic_patterns;
+        recognition.pattern_sources.retain(|pattern, _| patterns.contains_key(pattern));
+        sigel.prune_language_partitions();
         
         Ok(())
     }
@@ -404,7 +835,7 @@ impl LearningEngine {
 
     pub fn continuous_learning(&self, sigel: &mut Sigel, interaction: &str, response: &str) {
         // Learn from user interactions
-        let emotional_weight = self.calculate_emotional_weight(interaction);
+        let emotional_weight = self.sentiment_scorer.valence(interaction);
         sigel.add_memory(
             format!("Interaction: {} | Response: {}", interaction, response),
             "user_interaction".to_string(),
@@ -415,23 +846,46 @@ impl LearningEngine {
         self.adapt_communication_style(sigel, interaction);
         
         // Evolve consciousness slightly
-        sigel.consciousness.awareness_depth += 0.001;
-        if sigel.consciousness.awareness_depth > 1.0 {
-            sigel.consciousness.awareness_depth = 1.0;
+        sigel.consciousness.awareness_depth += self.awareness_growth_rate;
+        if sigel.consciousness.awareness_depth > self.max_awareness_depth {
+            sigel.consciousness.awareness_depth = self.max_awareness_depth;
         }
     }
 
     fn adapt_communication_style(&self, sigel: &mut Sigel, interaction: &str) {
+        if sigel.essence.style_locked {
+            return;
+        }
+
+        // Whole words only, so "stars" is no evidence in "starship" nor "logic" in "biological"
         let interaction_lower = interaction.to_lowercase();
-        
-        if ["formal", "professional", "business"].iter().any(|&word| interaction_lower.contains(word)) {
-            sigel.essence.communication_style = CommunicationStyle::Formal;
-        } else if ["creative", "artistic", "imaginative"].iter().any(|&word| interaction_lower.contains(word)) {
-            sigel.essence.communication_style = CommunicationStyle::Creative;
-        } else if ["cosmic", "universe", "stars", "celestial"].iter().any(|&word| interaction_lower.contains(word)) {
-            sigel.essence.communication_style = CommunicationStyle::Cosmic;
-        } else if ["analyze", "logic", "rational", "systematic"].iter().any(|&word| interaction_lower.contains(word)) {
-            sigel.essence.communication_style = CommunicationStyle::Analytical;
+        let words: HashSet<&str> = interaction_lower.split(|c: char| !c.is_alphanumeric()).collect();
+        let mentions = |style_words: &[&str]| style_words.iter().any(|word| words.contains(word));
+        let evidence = if mentions(&["formal", "professional", "business"]) {
+            CommunicationStyle::Formal
+        } else if mentions(&["creative", "artistic", "imaginative"]) {
+            CommunicationStyle::Creative
+        } else if mentions(&["cosmic", "universe", "stars", "celestial"]) {
+            CommunicationStyle::Cosmic
+        } else if mentions(&["analyze", "logic", "rational", "systematic"]) {
+            CommunicationStyle::Analytical
+        } else {
+            return;
+        };
+
+        // Start from the configured style, or from it again if it was changed directly
+        let essence = &mut sigel.essence;
+        if essence.style_mixture.weight(&essence.communication_style) == 0.0 {
+            essence.style_mixture = StyleMixture::pure(essence.communication_style.clone());
+        }
+
+        // Drift a little per message, and only switch once the new style clearly leads
+        essence.style_mixture.shift_towards(evidence, STYLE_DRIFT_RATE);
+        if let Some(dominant) = essence.style_mixture.dominant() {
+            let current = essence.style_mixture.weight(&essence.communication_style);
+            if essence.style_mixture.weight(&dominant) > current + STYLE_SWITCH_MARGIN {
+                essence.communication_style = dominant;
+            }
         }
     }
 
@@ -546,7 +1000,7 @@ impl LearningEngine {
         
         // Process each piece of knowledge
         for knowledge in all_coding_knowledge {
-            self.process_text_file(sigel, knowledge, "inherent_coding_knowledge");
+            self.process_text_file(sigel, knowledge, "inherent_coding_knowledge", Language::English, None);
             
             // Add as high-importance memory
             sigel.add_memory(
@@ -587,4 +1041,64 @@ impl LearningEngine {
         println!("Programming consciousness integration complete. Sigel now has inherent coding knowledge.");
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENTS: &[&str] = &[
+        "A compiler is a program that translates source code. The compiler checks types before it emits machine code. Rust has a strict compiler that catches memory errors early!",
+        "A river is part of a landscape. My brother wanted to walk to the river because he had heard that the old bridge would be closed. Rain causes floods along the river bank?",
+        "How to brew tea:\n1. Boil water\n2. Steep the leaves for three minutes\n3. Pour and enjoy. Tea is a drink that many people love in the morning.",
+        "A server is a computer that answers requests. A client sends requests to a server and waits for a response from the server over the network.",
+        "Memory is part of a computer. The operating system manages memory for every program that runs, and it frees memory when a program exits.",
+    ];
+
+    fn train(directory: &Path, threads: usize) -> Sigel {
+        let mut sigel = Sigel::new("determinism".to_string());
+        LearningEngine::new().train_from_text_files_parallel(&mut sigel, directory, Some(threads)).unwrap();
+        sigel
+    }
+
+    fn sorted<K: Ord + Clone, V: Clone>(map: &HashMap<K, V>) -> BTreeMap<K, V> {
+        map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    fn json<T: serde::Serialize>(value: &T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[test]
+    fn training_is_identical_for_any_thread_count() {
+        let directory = std::env::temp_dir().join(format!("sigmos-determinism-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        for (idx, document) in DOCUMENTS.iter().enumerate() {
+            fs::write(directory.join(format!("doc{}.txt", idx)), document).unwrap();
+        }
+
+        let sigels: Vec<Sigel> = [1, 2, 4].iter().map(|&threads| train(&directory, threads)).collect();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let expected = &sigels[0];
+        assert!(!expected.memory.semantic_knowledge.relationships.is_empty());
+        assert!(!expected.memory.episodic_memories.is_empty());
+        for sigel in &sigels[1..] {
+            let (semantic, expected_semantic) = (&sigel.memory.semantic_knowledge, &expected.memory.semantic_knowledge);
+            let (recognition, expected_recognition) = (&sigel.consciousness.pattern_recognition, &expected.consciousness.pattern_recognition);
+
+            assert_eq!(json(&sorted(&semantic.vocabulary)), json(&sorted(&expected_semantic.vocabulary)));
+            assert_eq!(sorted(&recognition.linguistic_patterns), sorted(&expected_recognition.linguistic_patterns));
+            assert_eq!(sorted(&recognition.semantic_networks), sorted(&expected_recognition.semantic_networks));
+            assert_eq!(json(&recognition.temporal_patterns), json(&expected_recognition.temporal_patterns));
+            assert_eq!(json(&sorted(&semantic.concepts)), json(&sorted(&expected_semantic.concepts)));
+            assert_eq!(json(&semantic.relationships), json(&expected_semantic.relationships));
+
+            let memories = |sigel: &Sigel| -> Vec<(String, String, Option<u32>)> {
+                sigel.memory.episodic_memories.iter()
+                    .map(|memory| (memory.content.clone(), memory.context.clone(), memory.source_id))
+                    .collect()
+            };
+            assert_eq!(memories(sigel), memories(expected));
+        }
+    }
+}
@@ -15,6 +15,28 @@ pub mod collective_intelligence;
 pub mod quantum_consciousness;
 pub mod simple_api;
 pub mod sigmanta;
+pub mod knowledge_extraction;
+pub mod procedural_learning;
+pub mod sentiment;
+pub mod knowledge_delta;
+pub mod deduplication;
+pub mod provenance;
+pub mod unlearning;
+pub mod word_vectors;
+pub mod language;
+pub mod decoder;
+pub mod memory_index;
+pub mod session;
+pub mod trace;
+pub mod streaming;
+pub mod persona;
+pub mod concept_qa;
+pub mod dialogue_state;
+pub mod moderation;
+pub mod middleware;
+pub mod commands;
+pub mod replay;
+pub mod config;
 
 pub use sigel::*;
 pub use consciousness::*;
@@ -31,6 +53,28 @@ pub use dream_mode::*;
 pub use web_interface::*;
 pub use collective_intelligence::*;
 pub use quantum_consciousness::*;
+pub use knowledge_extraction::*;
+pub use procedural_learning::*;
+pub use sentiment::*;
+pub use knowledge_delta::*;
+pub use deduplication::*;
+pub use provenance::*;
+pub use unlearning::*;
+pub use word_vectors::*;
+pub use language::*;
+pub use decoder::*;
+pub use memory_index::*;
+pub use session::*;
+pub use trace::*;
+pub use streaming::*;
+pub use persona::*;
+pub use concept_qa::*;
+pub use dialogue_state::*;
+pub use moderation::*;
+pub use middleware::*;
+pub use commands::*;
+pub use replay::*;
+pub use config::*;
 
 // Re-export main library API
 pub use simple_api::*;
@@ -42,8 +86,12 @@ pub const SIGEL_EXTENSION: &str = "sig";
 pub const MASTER_SIGEL_NAME: &str = "master.sigel";
 
 pub fn load_sigel_from_file<P: AsRef<Path>>(path: P) -> Result<Sigel> {
-    let content = std::fs::read_to_string(path)?;
-    let sigel = serde_json::from_str(&content)?;
+    let content = std::fs::read_to_string(&path)?;
+    let mut sigel: Sigel = serde_json::from_str(&content)?;
+
+    // A missing or unreadable index is rebuilt from the memories
+    sigel.memory_index = MemoryIndex::load(MemoryIndex::sidecar_path(&path)).unwrap_or_default();
+    sigel.refresh_memory_index();
     Ok(sigel)
 }
 
@@ -69,7 +117,12 @@ pub fn save_sigel_to_file<P: AsRef<Path>>(sigel: &Sigel, path: P) -> Result<()>
         eprintln!("   Sanitization may have missed some NaN/infinity values");
     }
     
-    std::fs::write(path, content)?;
+    std::fs::write(&path, content)?;
+
+    sanitized_sigel.refresh_memory_index();
+    if let Err(e) = sanitized_sigel.memory_index.save(MemoryIndex::sidecar_path(&path)) {
+        eprintln!("⚠️  Warning: Could not save memory index: {}", e);
+    }
     Ok(())
 }
 
@@ -1,5 +1,7 @@
 use crate::sigel::*;
 use crate::cosmos::CosmicProcessor;
+use crate::config::SigmosConfig;
+use crate::word_vectors::{PreparedSentence, WordVectors};
 use std::collections::{HashMap, BinaryHeap};
 use std::cmp::Ordering;
 use std::time::{SystemTime, Duration};
@@ -22,6 +24,17 @@ impl MemoryConsolidator {
         }
     }
 
+    /// A consolidator with the thresholds of `[memory]`, keeping the share of memory strength
+    /// `[consciousness] memory_consolidation_rate` gives.
+    pub fn from_config(config: &SigmosConfig) -> Self {
+        Self {
+            cosmic_processor: CosmicProcessor::new(),
+            consolidation_threshold: config.memory.consolidation_threshold,
+            decay_rate: 1.0 - config.consciousness.memory_consolidation_rate,
+            importance_amplification: config.memory.emotional_weight_importance,
+        }
+    }
+
     pub fn consolidate_memories(&self, sigel: &mut Sigel) -> ConsolidationReport {
         let mut report = ConsolidationReport::new();
         let start_time = SystemTime::now();
@@ -147,6 +160,10 @@ impl MemoryConsolidator {
     fn cluster_memories_by_similarity(&self, sigel: &Sigel, scores: &HashMap<usize, MemoryScore>) -> Vec<MemoryCluster> {
         let mut clusters = Vec::new();
         let mut processed = vec![false; sigel.memory.episodic_memories.len()];
+        let vectors = &sigel.memory.semantic_knowledge.word_vectors;
+        let contents: Vec<PreparedSentence> = sigel.memory.episodic_memories.iter()
+            .map(|memory| vectors.prepare(&memory.content))
+            .collect();
 
         for (idx, memory) in sigel.memory.episodic_memories.iter().enumerate() {
             if processed[idx] {
@@ -167,7 +184,7 @@ impl MemoryConsolidator {
                     continue;
                 }
 
-                let similarity = self.calculate_memory_similarity(memory, other_memory);
+                let similarity = self.calculate_memory_similarity(vectors, (memory, &contents[idx]), (other_memory, &contents[other_idx]));
                 if similarity > 0.7 {  // High similarity threshold
                     cluster.related_memory_indices.push(other_idx);
                     cluster.consolidated_importance += scores[&other_idx].total_importance;
@@ -184,14 +201,9 @@ impl MemoryConsolidator {
         clusters
     }
 
-    fn calculate_memory_similarity(&self, mem1: &EpisodicMemory, mem2: &EpisodicMemory) -> f64 {
-        // Content similarity (Jaccard similarity of words)
-        let words1: std::collections::HashSet<&str> = mem1.content.split_whitespace().collect();
-        let words2: std::collections::HashSet<&str> = mem2.content.split_whitespace().collect();
-        
-        let intersection = words1.intersection(&words2).count() as f64;
-        let union = words1.union(&words2).count() as f64;
-        let content_similarity = if union > 0.0 { intersection / union } else { 0.0 };
+    fn calculate_memory_similarity(&self, vectors: &WordVectors, (mem1, content1): (&EpisodicMemory, &PreparedSentence), (mem2, content2): (&EpisodicMemory, &PreparedSentence)) -> f64 {
+        // Content similarity (word overlap, with related words matching through word vectors)
+        let content_similarity = vectors.prepared_similarity(content1, content2);
 
         // Context similarity
         let context_similarity = if mem1.context == mem2.context { 1.0 } else { 0.0 };
@@ -231,11 +243,7 @@ impl MemoryConsolidator {
     }
 
     fn is_common_word(&self, word: &str) -> bool {
-        let common_words = [
-            "the", "and", "that", "have", "for", "not", "with", "you", "this", "but",
-            "his", "from", "they", "she", "her", "been", "than", "its", "who", "did"
-        ];
-        common_words.contains(&word.to_lowercase().as_str())
+        crate::language::is_stopword(word)
     }
 
     fn consolidate_clusters(&self, clusters: Vec<MemoryCluster>) -> Vec<ConsolidatedMemory> {
@@ -319,10 +327,12 @@ impl MemoryConsolidator {
                     EmotionalProfile::Neutral => 0.0,
                 },
                 relevance_score: consolidated.consolidated_importance,
+                source_id: None,
             };
             
             sigel.memory.episodic_memories.push(consolidated_episodic);
         }
+        sigel.refresh_memory_index();
     }
 
     fn optimize_pattern_matrix(&self, sigel: &mut Sigel, memory_scores: &HashMap<usize, MemoryScore>) {
@@ -0,0 +1,224 @@
+use crate::sigel::*;
+use crate::language::is_stopword;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+const SIDECAR_EXTENSION: &str = "idx";
+
+/// BM25 inverted index over episodic memory content and context.
+///
+/// Kept up to date by `Sigel::add_memory`. Memories removed elsewhere are dropped by
+/// `Sigel::refresh_memory_index`, and ignored by searches until then.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryIndex {
+    /// Term -> (document slot, term frequency).
+    postings: HashMap<String, Vec<(u32, u32)>>,
+    /// Memory id and length in terms for each slot; `None` once the memory is removed.
+    documents: Vec<Option<(Uuid, u32)>>,
+    total_length: u64,
+    live: usize,
+    #[serde(skip)]
+    slots: HashMap<Uuid, u32>,
+}
+
+/// One memory returned by `Sigel::search_memories`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryHit {
+    pub id: Uuid,
+    pub content: String,
+    pub context: String,
+    pub score: f64,
+}
+
+impl MemoryIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn build(memories: &[EpisodicMemory]) -> Self {
+        let mut index = Self::new();
+        for memory in memories {
+            index.add(memory);
+        }
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    pub fn add(&mut self, memory: &EpisodicMemory) {
+        self.rebuild_slots();
+        if self.slots.contains_key(&memory.id) {
+            return;
+        }
+
+        let slot = self.documents.len() as u32;
+        let mut frequencies: HashMap<String, u32> = HashMap::new();
+        let mut length = 0;
+        for term in terms(&memory.content).into_iter().chain(terms(&memory.context)) {
+            *frequencies.entry(term).or_insert(0) += 1;
+            length += 1;
+        }
+        for (term, frequency) in frequencies {
+            self.postings.entry(term).or_default().push((slot, frequency));
+        }
+
+        self.documents.push(Some((memory.id, length)));
+        self.slots.insert(memory.id, slot);
+        self.total_length += length as u64;
+        self.live += 1;
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> bool {
+        self.rebuild_slots();
+        let Some(slot) = self.slots.remove(&id) else { return false };
+        if let Some((_, length)) = self.documents[slot as usize].take() {
+            self.total_length -= length as u64;
+            self.live -= 1;
+        }
+        true
+    }
+
+    /// Drop removed memories and index any added without `Sigel::add_memory`.
+    pub fn sync(&mut self, memories: &[EpisodicMemory]) {
+        self.rebuild_slots();
+        let current: HashMap<Uuid, &EpisodicMemory> = memories.iter().map(|m| (m.id, m)).collect();
+
+        let stale: Vec<Uuid> = self.slots.keys().filter(|id| !current.contains_key(id)).copied().collect();
+        for id in stale {
+            self.remove(id);
+        }
+
+        // Removed slots still take up space in the postings; start over once they dominate
+        if self.documents.len() > 2 * self.live.max(1) {
+            *self = Self::build(memories);
+            return;
+        }
+
+        for memory in memories {
+            self.add(memory);
+        }
+    }
+
+    /// The `k` best matching memory ids for `query`, best first.
+    pub fn search(&self, query: &str, k: usize) -> Vec<(Uuid, f64)> {
+        self.search_where(query, k, |_| true)
+    }
+
+    /// The `k` best matching memory ids for `query` that `keep` accepts, best first.
+    pub fn search_where(&self, query: &str, k: usize, keep: impl Fn(&Uuid) -> bool) -> Vec<(Uuid, f64)> {
+        if self.live == 0 || k == 0 {
+            return Vec::new();
+        }
+
+        let mut query_terms = terms(query);
+        query_terms.sort();
+        query_terms.dedup();
+
+        let documents = self.live as f64;
+        let average_length = (self.total_length as f64 / documents).max(1.0);
+        let mut scores: HashMap<u32, f64> = HashMap::new();
+
+        for term in &query_terms {
+            let Some(postings) = self.postings.get(term) else { continue };
+            let live: Vec<(u32, u32, u32)> = postings.iter()
+                .filter_map(|&(slot, frequency)| self.documents[slot as usize].map(|(_, length)| (slot, frequency, length)))
+                .collect();
+            if live.is_empty() {
+                continue;
+            }
+
+            let df = live.len() as f64;
+            let idf = (1.0 + (documents - df + 0.5) / (df + 0.5)).ln();
+            for (slot, frequency, length) in live {
+                let tf = frequency as f64;
+                let norm = K1 * (1.0 - B + B * length as f64 / average_length);
+                *scores.entry(slot).or_insert(0.0) += idf * tf * (K1 + 1.0) / (tf + norm);
+            }
+        }
+
+        let mut ranked: Vec<(u32, f64)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked.into_iter()
+            .filter_map(|(slot, score)| self.documents[slot as usize].map(|(id, _)| (id, score)))
+            .filter(|(id, _)| keep(id))
+            .take(k)
+            .collect()
+    }
+
+    /// Where the index for a Sigel saved at `sigel_path` is kept, e.g. `my_ai.sig.idx`.
+    pub fn sidecar_path<P: AsRef<Path>>(sigel_path: P) -> PathBuf {
+        let mut path = sigel_path.as_ref().as_os_str().to_owned();
+        path.push(".");
+        path.push(SIDECAR_EXTENSION);
+        PathBuf::from(path)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    // The slot lookup isn't serialized, so rebuild it after loading
+    fn rebuild_slots(&mut self) {
+        if self.slots.len() != self.live {
+            self.slots = self.documents.iter()
+                .enumerate()
+                .filter_map(|(slot, document)| document.map(|(id, _)| (id, slot as u32)))
+                .collect();
+        }
+    }
+}
+
+impl Sigel {
+    /// The `k` episodic memories that best match `query`, best first.
+    pub fn search_memories(&self, query: &str, k: usize) -> Vec<MemoryHit> {
+        if self.memory_index.is_empty() {
+            return Vec::new();
+        }
+
+        // Skip memories forgotten since the index was last synced before taking the top k
+        let memories: HashMap<Uuid, &EpisodicMemory> = self.memory.episodic_memories.iter()
+            .map(|m| (m.id, m))
+            .collect();
+        let hits = self.memory_index.search_where(query, k, |id| memories.contains_key(id));
+        hits.into_iter()
+            .filter_map(|(id, score)| memories.get(&id).map(|memory| MemoryHit {
+                id,
+                content: memory.content.clone(),
+                context: memory.context.clone(),
+                score,
+            }))
+            .collect()
+    }
+
+    /// Bring the memory index in line with memories removed or replaced since it was built.
+    /// This rescans every memory, so it's for after loading or bulk changes, not each new memory.
+    pub fn refresh_memory_index(&mut self) {
+        self.memory_index.sync(&self.memory.episodic_memories);
+    }
+}
+
+/// Lowercased words and numbers, without stopwords.
+fn terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 2)
+        .map(|w| w.to_lowercase())
+        .filter(|w| !is_stopword(w))
+        .collect()
+}
@@ -0,0 +1,53 @@
+use crate::sigel::Sigel;
+use crate::interaction::ConversationTurn;
+use crate::language::Language;
+use std::collections::HashMap;
+
+/// What a middleware sees of the turn in progress, and can change.
+#[derive(Debug, Clone, Default)]
+pub struct InteractionContext {
+    /// What the user typed, as it will be recorded.
+    pub user_input: String,
+    /// The input the Sigel answers: `user_input` with references resolved. Rewriting it
+    /// changes what is retrieved and generated, not what is recorded.
+    pub query: String,
+    pub language: Language,
+    /// Extra text to ground the response in, e.g. glossary entries.
+    pub grounding: Vec<String>,
+    /// Added to the recorded turn's `context_tags`.
+    pub tags: Vec<String>,
+    /// The response, once generated and before response filters run.
+    pub response: Option<String>,
+    /// Free-form values for middleware to pass along to their later hooks.
+    pub metadata: HashMap<String, String>,
+}
+
+impl InteractionContext {
+    pub fn new(user_input: &str, query: &str, language: Language) -> Self {
+        Self {
+            user_input: user_input.to_string(),
+            query: query.to_string(),
+            language,
+            ..Self::default()
+        }
+    }
+}
+
+/// Hooks around each interaction, for pre- and post-processing without changing the
+/// engine: injecting a glossary, tagging intents, logging turns to an external store.
+///
+/// Every hook has an empty default, so implement only the ones you need. Middleware run
+/// in the order they were added. Hooks take `&self` so one middleware can be shared by
+/// several engines; keep mutable state behind a `Mutex`.
+pub trait InteractionMiddleware: Send + Sync {
+    /// Before the Sigel works on the input, including recalling skills and answering
+    /// concept questions.
+    fn before_processing(&self, _sigel: &mut Sigel, _context: &mut InteractionContext) {}
+
+    /// After a response was generated, before response filters run. `context.response`
+    /// holds the response and may be rewritten.
+    fn after_response(&self, _sigel: &mut Sigel, _context: &mut InteractionContext) {}
+
+    /// After the Sigel learned from the turn and it was recorded.
+    fn after_learning(&self, _sigel: &mut Sigel, _context: &InteractionContext, _turn: &ConversationTurn) {}
+}
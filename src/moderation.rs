@@ -0,0 +1,450 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+pub const DEFAULT_MAX_RESPONSE_LENGTH: usize = 1000;
+
+const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}";
+const PHONE_PATTERN: &str = r"(?:\+\d{1,3}(?:[\s.-]?\d{2,4}){2,4}\b)|(?:(?:\(\d{3}\)\s?|\b\d{3}[\s.-]?)\d{3}[\s.-]?\d{4}\b)";
+const KEY_PATTERN: &str = concat!(
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+    r"|\b(?:sk|pk|rk)-[A-Za-z0-9_-]{16,}",
+    r"|\bAKIA[0-9A-Z]{16}\b",
+    r"|\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+    r"|\bxox[abpr]-[A-Za-z0-9-]{10,}",
+);
+// Also matches commit hashes and checksums, so only on request
+const HEX_STRING_PATTERN: &str = r"\b[A-Fa-f0-9]{32,}\b";
+
+// Longest stretch of text, across words, a built-in redaction can span (a phone number)
+const REDACTION_REACH: usize = 32;
+
+/// A step that inspects, and may rewrite or withhold, a response before the user sees it.
+pub trait ResponseFilter: Send + Sync {
+    fn name(&self) -> &str;
+    fn filter(&self, response: &str) -> FilterOutcome;
+
+    /// How many bytes at the end of `partial`, a response still being generated, this
+    /// filter could still treat differently once more text follows. Streamed responses
+    /// are held back that far; by default until they are complete.
+    fn unsettled(&self, partial: &str) -> usize {
+        partial.len()
+    }
+}
+
+pub enum FilterOutcome {
+    Pass,
+    Modify { text: String, detail: String },
+    /// Withhold the response and say `replacement` instead. Later filters are skipped.
+    Block { replacement: String, detail: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterAction {
+    Passed,
+    Modified,
+    Blocked,
+}
+
+/// What one filter did to a response. Details never repeat the filtered text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterDecision {
+    pub filter: String,
+    pub action: FilterAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl fmt::Display for FilterDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:?}", self.filter, self.action)?;
+        if let Some(detail) = &self.detail {
+            write!(f, " ({})", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `[moderation]` section of sigmos.toml.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModerationConfig {
+    pub blocklist: Vec<String>,
+    /// One blocked term per line; `#` starts a comment.
+    pub blocklist_file: Option<String>,
+    /// Withhold the whole response when it contains a blocked term, instead of masking the term.
+    pub block_responses: bool,
+    pub blocked_response: String,
+    pub redact_emails: bool,
+    pub redact_phone_numbers: bool,
+    pub redact_keys: bool,
+    /// Redact any run of 32 or more hex digits, such as an unlabelled key, but also a commit hash.
+    pub redact_hex_strings: bool,
+    pub redact: Vec<RedactionRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub name: String,
+    pub pattern: String,
+    pub replacement: String,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            blocklist: Vec::new(),
+            blocklist_file: None,
+            block_responses: false,
+            blocked_response: "I'd rather not say that.".to_string(),
+            redact_emails: true,
+            redact_phone_numbers: true,
+            redact_keys: true,
+            redact_hex_strings: false,
+            redact: Vec::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct ModerationFile {
+    #[serde(default)]
+    interaction: InteractionSection,
+    #[serde(default)]
+    moderation: ModerationConfig,
+}
+
+#[derive(Deserialize, Default)]
+struct InteractionSection {
+    max_response_length: Option<usize>,
+}
+
+/// Masks blocked terms as whole words, or withholds responses that contain them.
+pub struct BlocklistFilter {
+    pattern: Option<Regex>,
+    longest_term: usize,
+    block: Option<String>,
+}
+
+impl BlocklistFilter {
+    pub fn new(terms: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        let terms: Vec<String> = terms.iter()
+            .map(|term| term.trim())
+            .filter(|term| !term.is_empty())
+            .map(regex::escape)
+            .collect();
+        let longest_term = terms.iter().map(String::len).max().unwrap_or(0);
+        let pattern = if terms.is_empty() {
+            None
+        } else {
+            Some(RegexBuilder::new(&format!(r"\b(?:{})\b", terms.join("|"))).case_insensitive(true).build()?)
+        };
+        Ok(Self { pattern, longest_term, block: None })
+    }
+
+    /// Withhold the whole response, saying `replacement` instead.
+    pub fn blocking(mut self, replacement: &str) -> Self {
+        self.block = Some(replacement.to_string());
+        self
+    }
+
+    pub fn load_terms<P: AsRef<Path>>(path: P) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        Ok(fs::read_to_string(path)?
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or("").trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect())
+    }
+}
+
+impl ResponseFilter for BlocklistFilter {
+    fn name(&self) -> &str {
+        "blocklist"
+    }
+
+    fn filter(&self, response: &str) -> FilterOutcome {
+        let Some(pattern) = &self.pattern else { return FilterOutcome::Pass };
+        let matches = pattern.find_iter(response).count();
+        if matches == 0 {
+            return FilterOutcome::Pass;
+        }
+
+        let detail = format!("{} blocked term{}", matches, if matches == 1 { "" } else { "s" });
+        match &self.block {
+            Some(replacement) => FilterOutcome::Block { replacement: replacement.clone(), detail },
+            None => FilterOutcome::Modify {
+                text: pattern.replace_all(response, |caps: &regex::Captures| "*".repeat(caps[0].chars().count())).into_owned(),
+                detail,
+            },
+        }
+    }
+
+    fn unsettled(&self, partial: &str) -> usize {
+        // Withholding depends on the whole response
+        if self.block.is_some() { partial.len() } else { self.longest_term }
+    }
+}
+
+/// Replaces personal data and secrets matched by regular expressions.
+pub struct RedactionFilter {
+    rules: Vec<(String, Regex, String)>,
+    custom_rules: bool,
+}
+
+impl RedactionFilter {
+    pub fn new() -> Self {
+        Self { rules: Vec::new(), custom_rules: false }
+    }
+
+    /// Redact e-mail addresses, phone numbers and API keys or private keys.
+    pub fn builtin() -> Self {
+        let mut filter = Self::new();
+        filter.add_builtin(true, true, true, false);
+        filter
+    }
+
+    pub fn add_rule(&mut self, name: &str, pattern: &str, replacement: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.rules.push((name.to_string(), Regex::new(pattern)?, replacement.to_string()));
+        self.custom_rules = true;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    fn add_builtin(&mut self, emails: bool, phone_numbers: bool, keys: bool, hex_strings: bool) {
+        // Keys first, since a key can contain something that looks like a phone number
+        let builtin = [
+            (keys, "key", KEY_PATTERN, "[key redacted]"),
+            (hex_strings, "hex string", HEX_STRING_PATTERN, "[key redacted]"),
+            (emails, "email", EMAIL_PATTERN, "[email redacted]"),
+            (phone_numbers, "phone number", PHONE_PATTERN, "[phone number redacted]"),
+        ];
+        for (enabled, name, pattern, replacement) in builtin {
+            if enabled {
+                let pattern = Regex::new(pattern).expect("built-in redaction patterns are valid");
+                self.rules.push((name.to_string(), pattern, replacement.to_string()));
+            }
+        }
+    }
+}
+
+impl ResponseFilter for RedactionFilter {
+    fn name(&self) -> &str {
+        "redaction"
+    }
+
+    fn filter(&self, response: &str) -> FilterOutcome {
+        let mut text = response.to_string();
+        let mut redacted = Vec::new();
+
+        for (name, pattern, replacement) in &self.rules {
+            let matches = pattern.find_iter(&text).count();
+            if matches > 0 {
+                text = pattern.replace_all(&text, replacement.as_str()).into_owned();
+                redacted.push(format!("{} {}{}", matches, name, if matches == 1 { "" } else { "s" }));
+            }
+        }
+
+        if redacted.is_empty() {
+            FilterOutcome::Pass
+        } else {
+            FilterOutcome::Modify { text, detail: redacted.join(", ") }
+        }
+    }
+
+    fn unsettled(&self, partial: &str) -> usize {
+        // Custom patterns may reach any distance
+        if self.custom_rules {
+            return partial.len();
+        }
+        // A private key block redacts as a whole, however long
+        match partial.rfind("-----BEGIN") {
+            Some(begin) if !partial[begin..].contains("-----END") => partial.len() - begin,
+            _ => REDACTION_REACH,
+        }
+    }
+}
+
+impl Default for RedactionFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cuts responses longer than `max_chars` at the last word that fits.
+pub struct LengthLimitFilter {
+    max_chars: usize,
+}
+
+impl LengthLimitFilter {
+    pub fn new(max_chars: usize) -> Self {
+        Self { max_chars }
+    }
+}
+
+impl ResponseFilter for LengthLimitFilter {
+    fn name(&self) -> &str {
+        "length_limit"
+    }
+
+    fn filter(&self, response: &str) -> FilterOutcome {
+        let length = response.chars().count();
+        if length <= self.max_chars {
+            return FilterOutcome::Pass;
+        }
+
+        // Leave room for the ellipsis
+        let end = response.char_indices().nth(self.max_chars.saturating_sub(1)).map_or(response.len(), |(i, _)| i);
+        let cut = response[..end].rfind(char::is_whitespace).filter(|&i| i > 0).unwrap_or(end);
+        FilterOutcome::Modify {
+            text: format!("{}…", response[..cut].trim_end()),
+            detail: format!("{} -> {} chars", length, self.max_chars),
+        }
+    }
+
+    fn unsettled(&self, partial: &str) -> usize {
+        // Near the limit, where the response is cut depends on what follows
+        if partial.chars().count() + 1 < self.max_chars { 0 } else { partial.len() }
+    }
+}
+
+/// The filters every response passes through, in order.
+#[derive(Clone)]
+pub struct ResponseFilterChain {
+    filters: Vec<Arc<dyn ResponseFilter>>,
+}
+
+impl ResponseFilterChain {
+    /// A chain with no filters.
+    pub fn new() -> Self {
+        Self { filters: Vec::new() }
+    }
+
+    /// Redaction of personal data and keys, and the default length limit.
+    pub fn builtin() -> Self {
+        Self::from_config(&ModerationConfig::default(), DEFAULT_MAX_RESPONSE_LENGTH)
+            .expect("the default moderation config is valid")
+    }
+
+    /// Filters from sigmos.toml (or `$SIGMOS_CONFIG`) when it exists, otherwise the built-in ones.
+    pub fn discover() -> Self {
+        if let Some(path) = crate::config::SigmosConfig::locate() {
+            match Self::load_config(&path) {
+                Ok(chain) => return chain,
+                Err(e) => log::warn!("Could not load moderation settings from {}: {}", path.display(), e),
+            }
+        }
+        Self::builtin()
+    }
+
+    /// Filters from the `[moderation]` section and `[interaction] max_response_length` of a
+    /// config file.
+    pub fn load_config<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let mut file: ModerationFile = toml::from_str(&fs::read_to_string(path)?)?;
+        if let Some(blocklist_file) = &file.moderation.blocklist_file {
+            let blocklist_file = path.parent().unwrap_or(Path::new(".")).join(blocklist_file);
+            file.moderation.blocklist.extend(BlocklistFilter::load_terms(blocklist_file)?);
+            file.moderation.blocklist_file = None;
+        }
+        Self::from_config(&file.moderation, file.interaction.max_response_length.unwrap_or(DEFAULT_MAX_RESPONSE_LENGTH))
+    }
+
+    /// Blocklist, then redaction, then the length limit; 0 means no limit.
+    pub fn from_config(config: &ModerationConfig, max_response_length: usize) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut chain = Self::new();
+
+        let mut blocklist = config.blocklist.clone();
+        if let Some(file) = &config.blocklist_file {
+            blocklist.extend(BlocklistFilter::load_terms(file)?);
+        }
+        if !blocklist.is_empty() {
+            let filter = BlocklistFilter::new(&blocklist)?;
+            chain.push(if config.block_responses { filter.blocking(&config.blocked_response) } else { filter });
+        }
+
+        let mut redaction = RedactionFilter::new();
+        redaction.add_builtin(config.redact_emails, config.redact_phone_numbers, config.redact_keys, config.redact_hex_strings);
+        for rule in &config.redact {
+            redaction.add_rule(&rule.name, &rule.pattern, &rule.replacement)?;
+        }
+        if !redaction.is_empty() {
+            chain.push(redaction);
+        }
+
+        if max_response_length > 0 {
+            chain.push(LengthLimitFilter::new(max_response_length));
+        }
+        Ok(chain)
+    }
+
+    pub fn push<F: ResponseFilter + 'static>(&mut self, filter: F) {
+        self.filters.push(Arc::new(filter));
+    }
+
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// Run a response through every filter, returning the final text and what each filter did.
+    pub fn apply(&self, response: &str) -> (String, Vec<FilterDecision>) {
+        self.run(response, true)
+    }
+
+    /// The filtered start of `partial`, a response still being generated, that no text
+    /// following it can change: up to the last word every filter has settled.
+    pub fn settled(&self, partial: &str) -> String {
+        let unsettled = self.filters.iter().map(|filter| filter.unsettled(partial)).max().unwrap_or(0);
+        let mut end = partial.len().saturating_sub(unsettled);
+        while !partial.is_char_boundary(end) {
+            end -= 1;
+        }
+        // The last word may still grow
+        let end = partial[..end].rfind(char::is_whitespace).unwrap_or(0);
+        self.run(&partial[..end], false).0
+    }
+
+    fn run(&self, response: &str, log: bool) -> (String, Vec<FilterDecision>) {
+        let mut text = response.to_string();
+        let mut decisions = Vec::new();
+
+        for filter in &self.filters {
+            let (action, detail) = match filter.filter(&text) {
+                FilterOutcome::Pass => (FilterAction::Passed, None),
+                FilterOutcome::Modify { text: modified, detail } => {
+                    text = modified;
+                    (FilterAction::Modified, Some(detail))
+                },
+                FilterOutcome::Block { replacement, detail } => {
+                    text = replacement;
+                    (FilterAction::Blocked, Some(detail))
+                },
+            };
+            let decision = FilterDecision { filter: filter.name().to_string(), action, detail };
+            if log && action != FilterAction::Passed {
+                log::info!("Response filter: {}", decision);
+            }
+            decisions.push(decision);
+            if action == FilterAction::Blocked {
+                break;
+            }
+        }
+
+        (text, decisions)
+    }
+}
+
+impl Default for ResponseFilterChain {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
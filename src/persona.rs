@@ -0,0 +1,428 @@
+use crate::sigel::*;
+use crate::language::Language;
+use crate::config::SigmosConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const BUNDLED_PERSONAS: [&str; 9] = [
+    include_str!("../data/personas/academic.toml"),
+    include_str!("../data/personas/analytical.toml"),
+    include_str!("../data/personas/casual.toml"),
+    include_str!("../data/personas/cosmic.toml"),
+    include_str!("../data/personas/creative.toml"),
+    include_str!("../data/personas/empathetic.toml"),
+    include_str!("../data/personas/formal.toml"),
+    include_str!("../data/personas/philosophical.toml"),
+    include_str!("../data/personas/transcendent.toml"),
+];
+const DEFAULT_TEMPLATE: &str = "{opener}{body}{closer}";
+const FALLBACK_PERSONA: &str = "formal";
+
+/// How a Sigel phrases its responses: openers, closers, templates, preferred words and
+/// rules that depend on its character traits. Defined in TOML files, so new styles
+/// need no recompiling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Persona {
+    pub name: String,
+    pub description: String,
+    /// Put cosmic insights before the response rather than after it.
+    pub cosmic_first: bool,
+    /// Responses shorter than this many characters use `short_templates` where given.
+    pub short_length: usize,
+    pub phrasing: BTreeMap<Language, PersonaPhrasing>,
+    pub rules: Vec<TraitRule>,
+}
+
+/// Phrasing in one language. Empty lists fall back to plain text.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersonaPhrasing {
+    pub openers: Vec<String>,
+    pub closers: Vec<String>,
+    /// With `{opener}`, `{body}`, `{closer}` and `{name}` placeholders.
+    pub templates: Vec<String>,
+    pub short_templates: Vec<String>,
+    /// Preferred words, replacing the key wherever it appears as a whole word.
+    pub vocabulary: BTreeMap<String, String>,
+    /// Frame for cosmic insights, with an `{insight}` placeholder.
+    pub cosmic_insight: Option<String>,
+    pub no_knowledge: Option<String>,
+}
+
+/// Phrasing that applies while a character trait is above and/or below a threshold.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraitRule {
+    #[serde(rename = "trait")]
+    pub trait_name: String,
+    pub above: Option<f64>,
+    pub below: Option<f64>,
+    pub phrasing: BTreeMap<Language, PersonaPhrasing>,
+}
+
+/// A response put into a persona's words.
+#[derive(Debug, Clone)]
+pub struct RenderedResponse {
+    pub text: String,
+    /// The template with opener and closer filled in.
+    pub template: String,
+    /// Traits whose rules applied.
+    pub rules: Vec<String>,
+}
+
+impl Default for Persona {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: String::new(),
+            cosmic_first: false,
+            short_length: 50,
+            phrasing: BTreeMap::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Persona {
+    pub fn parse(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let mut persona = Self::parse(&fs::read_to_string(path)?)?;
+        if persona.name.is_empty() {
+            persona.name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default().to_lowercase();
+        }
+        Ok(persona)
+    }
+
+    /// The phrasing for `language` once the rules matching the Sigel's traits are applied.
+    pub fn phrasing_for(&self, sigel: &Sigel, language: Language) -> (PersonaPhrasing, Vec<String>) {
+        let mut phrasing = in_language(&self.phrasing, language).cloned().unwrap_or_default();
+        let mut applied = Vec::new();
+
+        for rule in &self.rules {
+            let Some(&value) = sigel.essence.character_traits.get(&rule.trait_name) else { continue };
+            if rule.above.is_some_and(|above| value <= above) || rule.below.is_some_and(|below| value >= below) {
+                continue;
+            }
+            if let Some(overlay) = in_language(&rule.phrasing, language) {
+                phrasing.overlay(overlay);
+                applied.push(rule.trait_name.clone());
+            }
+        }
+
+        (phrasing, applied)
+    }
+
+    pub fn render(&self, sigel: &Sigel, body: &str, language: Language) -> RenderedResponse {
+        let (phrasing, rules) = self.phrasing_for(sigel, language);
+        let body = body.trim();
+        let template = self.template(sigel, &phrasing, body);
+        let body = apply_vocabulary(body, &phrasing.vocabulary);
+
+        let body = match template.split_once("{body}") {
+            Some((before, after)) => {
+                // Templates that carry on after the body replace its own full stop
+                let body = if after.starts_with(['.', ',', '!', '?', ';', ':']) {
+                    body.trim_end_matches('.').to_string()
+                } else {
+                    body
+                };
+                fit_opening(before, body, language)
+            },
+            None => body,
+        };
+
+        RenderedResponse {
+            text: template.replace("{body}", &body).trim().to_string(),
+            template,
+            rules,
+        }
+    }
+
+    /// How `render` starts for any body beginning with `partial`, a body still being
+    /// generated. `None` until `partial` is long enough to settle the template.
+    pub fn render_partial(&self, sigel: &Sigel, partial: &str, language: Language) -> Option<String> {
+        let partial = partial.trim_start();
+        if partial.chars().count() < self.short_length {
+            return None;
+        }
+
+        let (phrasing, _) = self.phrasing_for(sigel, language);
+        let template = self.template(sigel, &phrasing, partial);
+        let (before, _) = template.split_once("{body}")?;
+        let body = fit_opening(before, apply_vocabulary(partial, &phrasing.vocabulary), language);
+        Some(format!("{}{}", before, body).trim_start().to_string())
+    }
+
+    /// The template with opener and closer filled in. The choice varies between responses
+    /// by how they begin, so a streamed response settles it early.
+    fn template(&self, sigel: &Sigel, phrasing: &PersonaPhrasing, body: &str) -> String {
+        let opening: String = body.chars().take(self.short_length).collect();
+        let seed = stable_hash(&opening);
+        let templates = if body.chars().count() < self.short_length && !phrasing.short_templates.is_empty() {
+            &phrasing.short_templates
+        } else {
+            &phrasing.templates
+        };
+        pick(templates, seed).unwrap_or(DEFAULT_TEMPLATE)
+            .replace("{opener}", pick(&phrasing.openers, seed >> 8).unwrap_or(""))
+            .replace("{closer}", pick(&phrasing.closers, seed >> 16).unwrap_or(""))
+            .replace("{name}", &sigel.name)
+    }
+
+    pub fn cosmic_insight(&self, sigel: &Sigel, insight: &str, language: Language) -> String {
+        let (phrasing, _) = self.phrasing_for(sigel, language);
+        match phrasing.cosmic_insight {
+            Some(template) => template.replace("{insight}", insight),
+            None => crate::language::frame(language.phrases().cosmic_insight, insight),
+        }
+    }
+
+    pub fn no_knowledge(&self, sigel: &Sigel, language: Language) -> String {
+        let (phrasing, _) = self.phrasing_for(sigel, language);
+        phrasing.no_knowledge.unwrap_or_else(|| language.phrases().no_knowledge.to_string())
+    }
+
+    /// Take on `other`'s definition, keeping languages and rules it doesn't define.
+    fn merge(&mut self, other: Persona) {
+        if !other.description.is_empty() {
+            self.description = other.description;
+        }
+        self.cosmic_first = other.cosmic_first;
+        self.short_length = other.short_length;
+        self.phrasing.extend(other.phrasing);
+        if !other.rules.is_empty() {
+            self.rules = other.rules;
+        }
+    }
+}
+
+impl PersonaPhrasing {
+    fn overlay(&mut self, other: &PersonaPhrasing) {
+        for (list, replacement) in [
+            (&mut self.openers, &other.openers),
+            (&mut self.closers, &other.closers),
+            (&mut self.templates, &other.templates),
+            (&mut self.short_templates, &other.short_templates),
+        ] {
+            if !replacement.is_empty() {
+                list.clone_from(replacement);
+            }
+        }
+        self.vocabulary.extend(other.vocabulary.iter().map(|(k, v)| (k.clone(), v.clone())));
+        if other.cosmic_insight.is_some() {
+            self.cosmic_insight.clone_from(&other.cosmic_insight);
+        }
+        if other.no_knowledge.is_some() {
+            self.no_knowledge.clone_from(&other.no_knowledge);
+        }
+    }
+}
+
+/// Personas by name: the bundled ones for each `CommunicationStyle`, plus any loaded
+/// from persona files or the `[styles.*]` sections of sigmos.toml.
+#[derive(Debug, Clone)]
+pub struct PersonaRegistry {
+    personas: BTreeMap<String, Persona>,
+}
+
+#[derive(Deserialize)]
+struct StylesSection {
+    #[serde(default)]
+    styles: toml::Table,
+}
+
+impl PersonaRegistry {
+    pub fn new() -> Self {
+        Self::bundled()
+    }
+
+    pub fn bundled() -> Self {
+        let mut registry = Self { personas: BTreeMap::new() };
+        for content in BUNDLED_PERSONAS {
+            registry.register(Persona::parse(content).expect("bundled personas are valid"));
+        }
+        registry
+    }
+
+    /// The bundled personas plus those in the `[styles]` of a config's file.
+    pub fn from_config(config: &SigmosConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut registry = Self::bundled();
+        registry.load_styles(&config.styles, &config.base_dir())?;
+        Ok(registry)
+    }
+
+    /// The bundled personas plus those in the `[styles]` of `config` and `./personas/*.toml`.
+    pub fn discover(config: &SigmosConfig) -> Self {
+        let mut registry = Self::from_config(config).unwrap_or_else(|e| {
+            log::warn!("Could not load styles from the configuration: {}", e);
+            Self::bundled()
+        });
+        if Path::new("personas").is_dir() {
+            if let Err(e) = registry.load_dir("personas") {
+                log::warn!("Could not load personas: {}", e);
+            }
+        }
+        registry
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Persona> {
+        self.personas.get(&name.to_lowercase())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.personas.keys().map(String::as_str).collect()
+    }
+
+    /// Add a persona, or extend the one with the same name.
+    pub fn register(&mut self, mut persona: Persona) {
+        persona.name = persona.name.to_lowercase();
+        match self.personas.get_mut(&persona.name) {
+            Some(existing) => existing.merge(persona),
+            None => {
+                self.personas.insert(persona.name.clone(), persona);
+            },
+        }
+    }
+
+    /// Load a persona file, returning the persona's name.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<String, Box<dyn std::error::Error>> {
+        let persona = Persona::load(path)?;
+        let name = persona.name.to_lowercase();
+        self.register(persona);
+        Ok(name)
+    }
+
+    /// Load every `.toml` persona file in a directory.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        for path in &paths {
+            self.load_file(path)?;
+        }
+        Ok(paths.len())
+    }
+
+    /// Load the `[styles.*]` sections of a sigmos.toml. A section defines or extends a
+    /// persona when it has `phrasing` or `rules`, or names a persona `file`.
+    pub fn load_config<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let section: StylesSection = toml::from_str(&fs::read_to_string(path)?)?;
+        self.load_styles(&section.styles, path.parent().unwrap_or(Path::new(".")))
+    }
+
+    /// Load `[styles.*]` sections already parsed from a config; `file`s are relative to `base_dir`.
+    pub fn load_styles(&mut self, styles: &toml::Table, base_dir: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut loaded = 0;
+        for (name, value) in styles {
+            if let Some(file) = value.get("file").and_then(|file| file.as_str()) {
+                let mut persona = Persona::load(base_dir.join(file))?;
+                persona.name = name.clone();
+                self.register(persona);
+                loaded += 1;
+            } else if value.get("phrasing").is_some() || value.get("rules").is_some() {
+                let mut persona: Persona = value.clone().try_into()?;
+                persona.name = name.clone();
+                self.register(persona);
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// The Sigel's own persona if it has one, otherwise the one for its communication style.
+    pub fn for_sigel(&self, sigel: &Sigel) -> &Persona {
+        sigel.essence.persona.as_deref()
+            .and_then(|name| self.get(name))
+            .or_else(|| self.get(sigel.essence.communication_style.name()))
+            .or_else(|| self.get(FALLBACK_PERSONA))
+            .expect("the fallback persona is bundled")
+    }
+}
+
+impl Default for PersonaRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn in_language(phrasing: &BTreeMap<Language, PersonaPhrasing>, language: Language) -> Option<&PersonaPhrasing> {
+    phrasing.get(&language).or_else(|| phrasing.get(&Language::English))
+}
+
+fn pick(options: &[String], seed: u64) -> Option<&str> {
+    if options.is_empty() {
+        None
+    } else {
+        Some(&options[(seed % options.len() as u64) as usize])
+    }
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is the same in every build.
+fn stable_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// "Yeah, so the river ..." rather than "Yeah, so The river ..."
+fn fit_opening(before: &str, body: String, language: Language) -> String {
+    if before.ends_with(' ') && before.trim_end().ends_with(|c: char| c.is_alphabetic() || c == ',') {
+        lowercase_article(&body, language)
+    } else {
+        body
+    }
+}
+
+/// Lowercase the first word when it's a stopword, and so can't be a name or a German noun.
+fn lowercase_article(text: &str, language: Language) -> String {
+    let first = text.split(|c: char| !c.is_alphabetic()).next().unwrap_or_default();
+    if first == "I" || !language.is_stopword(&first.to_lowercase()) {
+        return text.to_string();
+    }
+
+    let mut chars = text.chars();
+    chars.next().map(|c| c.to_lowercase().chain(chars).collect()).unwrap_or_default()
+}
+
+fn apply_vocabulary(text: &str, vocabulary: &BTreeMap<String, String>) -> String {
+    if vocabulary.is_empty() {
+        return text.to_string();
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut word = String::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_alphabetic() || c == '\'' {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            result.push_str(&replace_word(&word, vocabulary));
+            word.clear();
+        }
+        result.push(c);
+    }
+    result.pop();
+    result
+}
+
+fn replace_word(word: &str, vocabulary: &BTreeMap<String, String>) -> String {
+    let Some(replacement) = vocabulary.get(&word.to_lowercase()) else { return word.to_string() };
+
+    // Keep a capital at the start of a sentence
+    if word.chars().next().is_some_and(char::is_uppercase) {
+        let mut chars = replacement.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+    } else {
+        replacement.clone()
+    }
+}
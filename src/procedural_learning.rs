@@ -0,0 +1,431 @@
+use crate::sigel::*;
+use crate::language::is_stopword;
+
+/// Learns `ProceduralSkill`s from instructional text and recalls them for "how do I ..." prompts.
+///
+/// Three kinds of how-to content are recognized: numbered lists ("1. ...", "Step 2: ..."),
+/// "first / then / finally" sequences in prose, and runs of imperative steps
+/// (bulleted or consecutive sentences starting with a command verb).
+pub struct ProceduralLearner {
+    min_steps: usize,
+    match_threshold: f64,
+}
+
+const IMPERATIVE_VERBS: &[&str] = &[
+    "add", "adjust", "apply", "attach", "bake", "boil", "build", "check", "choose", "clean",
+    "click", "clone", "close", "combine", "commit", "compile", "configure", "connect", "cook",
+    "copy", "cover", "create", "cut", "delete", "deploy", "disable", "download", "drag", "drain",
+    "dry", "edit", "enable", "enter", "ensure", "export", "fill", "find", "fold", "follow", "go",
+    "heat", "import", "insert", "install", "keep", "launch", "let", "log", "make", "measure",
+    "mix", "move", "navigate", "open", "paste", "place", "plug", "pour", "prepare", "press",
+    "pull", "push", "put", "read", "remove", "repeat", "replace", "restart", "review", "rinse",
+    "run", "save", "select", "serve", "set", "sign", "start", "stir", "take", "test", "tighten",
+    "turn", "type", "unplug", "update", "use", "verify", "wait", "wash", "write",
+];
+
+const SEQUENCE_STARTERS: &[&str] = &["first,", "first", "firstly,", "firstly", "to begin,", "start by"];
+const SEQUENCE_CONTINUERS: &[&str] = &[
+    "then,", "then", "next,", "next", "after that,", "after that", "afterwards,", "afterwards",
+    "second,", "second", "secondly,", "secondly", "third,", "third", "now,",
+];
+const SEQUENCE_ENDERS: &[&str] = &["finally,", "finally", "lastly,", "lastly", "last,", "at the end,"];
+
+const HOW_TO_PREFIXES: &[&str] = &[
+    "how do i ", "how do you ", "how can i ", "how should i ", "how would i ", "how to ",
+    "what are the steps to ", "what are the steps for ", "steps to ", "show me how to ",
+    "teach me how to ", "explain how to ",
+];
+
+const FEEDBACK_NEGATIVE: &[&str] = &[
+    "didn't work", "did not work", "doesn't work", "does not work", "not working", "wrong", "failed", "useless",
+];
+const FEEDBACK_POSITIVE: &[&str] = &[
+    "that worked", "it worked", "works now", "thanks", "thank you", "perfect", "great", "helpful",
+];
+
+const QUERY_STOPWORDS: &[&str] = &[
+    "a", "an", "the", "i", "my", "me", "you", "your", "to", "of", "in", "on", "for", "and",
+    "or", "with", "it", "is", "do", "can", "how", "should", "would",
+];
+
+impl ProceduralLearner {
+    pub fn new() -> Self {
+        Self {
+            min_steps: 2,
+            match_threshold: 0.5,
+        }
+    }
+
+    /// Detect how-to content in a document and return the skills it describes.
+    pub fn extract_skills(&self, content: &str) -> Vec<ProceduralSkill> {
+        let mut skills = self.extract_list_skills(content);
+        skills.extend(self.extract_sequence_skills(content));
+        skills
+    }
+
+    /// Extract skills from a document and store them on the Sigel. Returns the number of skills learned.
+    pub fn learn_skills(&self, sigel: &mut Sigel, content: &str) -> usize {
+        let skills = self.extract_skills(content);
+        let learned = skills.len();
+        self.integrate_skills(sigel, skills);
+        learned
+    }
+
+    pub fn integrate_skills(&self, sigel: &mut Sigel, skills: Vec<ProceduralSkill>) {
+        for skill in skills {
+            let existing = sigel.memory.procedural_skills
+                .iter_mut()
+                .find(|s| s.name == skill.name);
+
+            match existing {
+                Some(existing) => {
+                    // Seeing the same procedure again makes the Sigel more practiced at it
+                    existing.proficiency = (existing.proficiency + 0.05).min(1.0);
+                    if skill.steps.len() > existing.steps.len() {
+                        existing.steps = skill.steps;
+                    }
+                },
+                None => sigel.memory.procedural_skills.push(skill),
+            }
+        }
+    }
+
+    /// Extract the task from a "how do I ..." style prompt, if the prompt is one.
+    pub fn how_to_task(&self, input: &str) -> Option<String> {
+        let lower = input.trim().to_lowercase();
+        // Only a request that opens the prompt or one of its sentences counts, not a mention mid-sentence
+        let task = sentences(&lower).into_iter()
+            .find_map(|sentence| HOW_TO_PREFIXES.iter().find_map(|prefix| sentence.strip_prefix(prefix)))?;
+        let task = task.trim_end_matches(['?', '.', '!']).trim();
+
+        if task.is_empty() {
+            None
+        } else {
+            Some(task.to_string())
+        }
+    }
+
+    /// Find the index of the skill best matching a task description.
+    pub fn find_skill(&self, sigel: &Sigel, task: &str) -> Option<usize> {
+        let task_words = self.content_words(task);
+        if task_words.is_empty() {
+            return None;
+        }
+
+        sigel.memory.procedural_skills
+            .iter()
+            .enumerate()
+            .map(|(idx, skill)| {
+                let name_words = self.content_words(&skill.name);
+                let step_words = self.content_words(&skill.steps.join(" "));
+                let score = task_words.iter()
+                    .map(|word| {
+                        if name_words.contains(word) { 1.0 } else if step_words.contains(word) { 0.3 } else { 0.0 }
+                    })
+                    .sum::<f64>() / task_words.len() as f64;
+                (idx, score)
+            })
+            .filter(|(_, score)| *score >= self.match_threshold)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(idx, _)| idx)
+    }
+
+    pub fn present_skill(&self, skill: &ProceduralSkill) -> String {
+        let mut response = format!("Here is how to {}:\n", skill.name);
+        for (i, step) in skill.steps.iter().enumerate() {
+            response.push_str(&format!("{}. {}\n", i + 1, step));
+        }
+        if skill.success_rate < 0.4 {
+            response.push_str("(These steps have not always worked for others, so let me know how it goes.)");
+        }
+        response.trim_end().to_string()
+    }
+
+    /// Update a skill from user feedback on whether its steps worked.
+    pub fn record_feedback(&self, skill: &mut ProceduralSkill, success: bool) {
+        let outcome = if success { 1.0 } else { 0.0 };
+        skill.success_rate = skill.success_rate * 0.8 + outcome * 0.2;
+        skill.proficiency = if success {
+            (skill.proficiency + 0.05).min(1.0)
+        } else {
+            (skill.proficiency - 0.03).max(0.0)
+        };
+    }
+
+    /// Interpret a follow-up message as feedback on previously presented steps.
+    pub fn detect_feedback(&self, input: &str) -> Option<bool> {
+        let words = feedback_words(input);
+        // Start positions of whole-word matches of any of the phrases
+        let positions = |phrases: &[&str]| -> Vec<usize> {
+            let mut found = Vec::new();
+            for phrase in phrases {
+                let phrase: Vec<&str> = phrase.split(' ').collect();
+                for i in 0..words.len() {
+                    let window = &words[i..(i + phrase.len()).min(words.len())];
+                    if window.len() == phrase.len() && window.iter().zip(&phrase).all(|(w, p)| w == p) {
+                        found.push(i);
+                    }
+                }
+            }
+            found
+        };
+
+        if !positions(FEEDBACK_NEGATIVE).is_empty() {
+            return Some(false);
+        }
+
+        let positive = positions(FEEDBACK_POSITIVE);
+        if positive.is_empty() {
+            None
+        } else if positive.iter().any(|&i| words[i.saturating_sub(2)..i].iter().any(|w| is_negator(w))) {
+            // "not helpful", "wasn't really great"
+            Some(false)
+        } else {
+            Some(true)
+        }
+    }
+
+    fn extract_list_skills(&self, content: &str) -> Vec<ProceduralSkill> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut skills = Vec::new();
+        let mut idx = 0;
+
+        while idx < lines.len() {
+            let start = idx;
+            let mut steps = Vec::new();
+            let mut imperative_count = 0;
+
+            while idx < lines.len() {
+                let Some(step) = self.list_item(lines[idx]) else { break };
+                if self.starts_with_imperative(&step) {
+                    imperative_count += 1;
+                }
+                steps.push(step);
+                idx += 1;
+            }
+
+            if steps.is_empty() {
+                idx += 1;
+                continue;
+            }
+
+            let numbered = self.numbered_item(lines[start]).is_some();
+            // Plain bullet lists only count as procedures when they read like commands
+            let is_procedure = numbered || imperative_count * 2 > steps.len();
+            if steps.len() >= self.min_steps && is_procedure {
+                let name = self.heading_before(&lines, start)
+                    .unwrap_or_else(|| self.fallback_name(&steps));
+                skills.push(self.new_skill(name, steps));
+            }
+        }
+
+        skills
+    }
+
+    fn extract_sequence_skills(&self, content: &str) -> Vec<ProceduralSkill> {
+        let sentences: Vec<&str> = content
+            .split(['.', '!', '?', '\n'])
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let mut skills = Vec::new();
+        let mut idx = 0;
+
+        while idx < sentences.len() {
+            let (name, first_step) = match self.sequence_start(sentences[idx]) {
+                Some(start) => start,
+                None => {
+                    idx += 1;
+                    continue;
+                },
+            };
+
+            let mut steps = vec![first_step];
+            let mut imperative_run = false;
+            idx += 1;
+
+            while idx < sentences.len() {
+                if let Some(step) = self.strip_marker(sentences[idx], SEQUENCE_ENDERS) {
+                    steps.push(step);
+                    idx += 1;
+                    break;
+                } else if let Some(step) = self.strip_marker(sentences[idx], SEQUENCE_CONTINUERS) {
+                    steps.push(step);
+                } else if self.starts_with_imperative(sentences[idx]) {
+                    steps.push(self.capitalize(sentences[idx]));
+                    imperative_run = true;
+                } else {
+                    break;
+                }
+                idx += 1;
+            }
+
+            if steps.len() >= self.min_steps.max(if imperative_run { 3 } else { 2 }) {
+                let name = name.unwrap_or_else(|| self.fallback_name(&steps));
+                skills.push(self.new_skill(name, steps));
+            }
+        }
+
+        skills
+    }
+
+    /// Recognize the first sentence of a sequence, e.g. "To make tea, first boil water".
+    fn sequence_start(&self, sentence: &str) -> Option<(Option<String>, String)> {
+        let lower = sentence.to_lowercase();
+
+        if let Some(step) = self.strip_marker(sentence, SEQUENCE_STARTERS) {
+            return Some((None, step));
+        }
+
+        if lower.starts_with("to ") {
+            if let Some(comma) = sentence.find(',') {
+                let task = sentence[3..comma].trim().to_lowercase();
+                let rest = sentence[comma + 1..].trim();
+                if let Some(step) = self.strip_marker(rest, SEQUENCE_STARTERS) {
+                    return Some((Some(task), step));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn strip_marker(&self, sentence: &str, markers: &[&str]) -> Option<String> {
+        let lower = sentence.to_lowercase();
+        markers.iter()
+            .find(|marker| lower.starts_with(*marker) && lower[marker.len()..].starts_with([' ', ',']))
+            .map(|marker| self.capitalize(sentence[marker.len()..].trim_start_matches([' ', ',']).trim()))
+            .filter(|step| !step.is_empty())
+    }
+
+    fn list_item(&self, line: &str) -> Option<String> {
+        if let Some(item) = self.numbered_item(line) {
+            return Some(item);
+        }
+
+        let trimmed = line.trim_start();
+        ["- ", "* ", "• "].iter()
+            .find(|bullet| trimmed.starts_with(*bullet))
+            .map(|bullet| trimmed[bullet.len()..].trim().to_string())
+            .filter(|item| !item.is_empty())
+    }
+
+    /// Matches "1. step", "2) step" and "Step 3: step".
+    fn numbered_item(&self, line: &str) -> Option<String> {
+        let trimmed = line.trim_start();
+        let lower = trimmed.to_lowercase();
+        let rest = if lower.starts_with("step ") { &trimmed[5..] } else { trimmed };
+
+        let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits == 0 || digits > 2 {
+            return None;
+        }
+
+        let after = &rest[digits..];
+        let item = after.strip_prefix(['.', ')', ':', '-'])?.trim();
+        if item.is_empty() {
+            None
+        } else {
+            Some(item.to_string())
+        }
+    }
+
+    /// A short line ending in ':' or phrased "How to ..." right above a list names the skill.
+    fn heading_before(&self, lines: &[&str], list_start: usize) -> Option<String> {
+        let heading = lines[..list_start].iter().rev().take(2).map(|l| l.trim()).find(|l| !l.is_empty())?;
+        let lower = heading.trim_start_matches('#').trim().to_lowercase();
+        let lower = lower.trim_end_matches(':').trim();
+
+        let name = ["how to ", "to ", "steps to ", "steps for "].iter()
+            .find_map(|prefix| lower.strip_prefix(prefix))
+            .unwrap_or(lower);
+
+        let is_heading = heading.ends_with(':') || heading.starts_with('#') || lower.starts_with("how to ");
+        if is_heading && !name.is_empty() && name.split_whitespace().count() <= 10 {
+            Some(name.to_string())
+        } else {
+            None
+        }
+    }
+
+    fn fallback_name(&self, steps: &[String]) -> String {
+        steps[0]
+            .to_lowercase()
+            .split_whitespace()
+            .take(5)
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_end_matches([',', ';', ':', '.'])
+            .to_string()
+    }
+
+    pub fn new_skill(&self, name: String, steps: Vec<String>) -> ProceduralSkill {
+        ProceduralSkill {
+            name,
+            steps,
+            proficiency: 0.3,
+            success_rate: 0.5,
+        }
+    }
+
+    fn starts_with_imperative(&self, text: &str) -> bool {
+        text.split_whitespace()
+            .next()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphabetic()).to_lowercase())
+            .is_some_and(|word| IMPERATIVE_VERBS.contains(&word.as_str()))
+    }
+
+    fn capitalize(&self, text: &str) -> String {
+        let mut chars = text.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+            None => String::new(),
+        }
+    }
+
+    fn content_words(&self, text: &str) -> Vec<String> {
+        text.to_lowercase()
+            .split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+            .filter(|w| !w.is_empty() && !QUERY_STOPWORDS.contains(&w.as_str()) && !is_stopword(w))
+            .collect()
+    }
+}
+
+impl Default for ProceduralLearner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Split text into sentences at `.`, `?` or `!` followed by whitespace, so "node.js" stays whole.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        if matches!(c, '.' | '?' | '!') && chars.peek().is_some_and(|(_, next)| next.is_whitespace()) {
+            sentences.push(text[start..idx + 1].trim());
+            start = idx + 1;
+        }
+    }
+    sentences.push(text[start..].trim());
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+/// Lowercase words with apostrophes kept, so "didn't" is one word.
+fn feedback_words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .replace('\u{2019}', "'")
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\''))
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_negator(word: &str) -> bool {
+    matches!(word, "not" | "no" | "never" | "hardly" | "barely") || word.ends_with("n't")
+}
@@ -0,0 +1,149 @@
+use crate::sigel::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Keeps answers readable for items seen in thousands of documents
+const MAX_SOURCES_PER_ENTRY: usize = 10;
+const MAX_TEMPORAL_PATTERN_ITEMS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProvenanceKind {
+    Word,
+    LinguisticPattern,
+    TemporalPattern,
+    Concept,
+    Memory,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceAttribution {
+    pub source: String,
+    pub count: u64,
+}
+
+/// Where one learned item came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvenanceEntry {
+    pub kind: ProvenanceKind,
+    pub item: String,
+    /// Total contributions across all sources, including any not listed in `sources`.
+    pub total: u64,
+    pub sources: Vec<SourceAttribution>,
+}
+
+impl ProvenanceEntry {
+    pub fn summary(&self) -> String {
+        let sources: Vec<String> = self.sources.iter()
+            .map(|s| format!("{} ({})", s.source, s.count))
+            .collect();
+        format!("{:?} '{}': {}", self.kind, self.item, sources.join(", "))
+    }
+}
+
+impl Sigel {
+    /// Answer "where did this come from" for a word, phrase or concept.
+    ///
+    /// Looks the term up in the vocabulary, linguistic patterns and concept graph, and
+    /// aggregates the temporal patterns and episodic memories that mention it.
+    pub fn trace_provenance(&self, query: &str) -> Vec<ProvenanceEntry> {
+        let term = query.trim().to_lowercase();
+        if term.is_empty() {
+            return Vec::new();
+        }
+
+        let mut entries = Vec::new();
+        let semantic = &self.memory.semantic_knowledge;
+        let patterns = &self.consciousness.pattern_recognition;
+
+        if let Some(knowledge) = semantic.vocabulary.get(&term) {
+            self.push_entry(&mut entries, ProvenanceKind::Word, &term, &knowledge.sources);
+        }
+
+        if let Some((pattern, sources)) = patterns.pattern_sources.get_key_value(query.trim())
+            .or_else(|| patterns.pattern_sources.get_key_value(&term))
+        {
+            self.push_entry(&mut entries, ProvenanceKind::LinguisticPattern, pattern, sources);
+        }
+
+        if let Some(concept) = semantic.concepts.get(&term) {
+            self.push_entry(&mut entries, ProvenanceKind::Concept, &concept.name, &concept.sources);
+        }
+
+        let mut temporal = SourceCounts::default();
+        let mut temporal_items = Vec::new();
+        for pattern in &patterns.temporal_patterns {
+            if pattern.sources.is_empty() || !pattern.sequence.iter().any(|w| w.to_lowercase() == term) {
+                continue;
+            }
+            temporal.merge(&pattern.sources);
+            let item = pattern.sequence.join(" ");
+            if temporal_items.len() < MAX_TEMPORAL_PATTERN_ITEMS && !temporal_items.contains(&item) {
+                temporal_items.push(item);
+            }
+        }
+        self.push_entry(&mut entries, ProvenanceKind::TemporalPattern, &temporal_items.join(" | "), &temporal);
+
+        let mut memories = SourceCounts::default();
+        for memory in &self.memory.episodic_memories {
+            if let Some(source_id) = memory.source_id {
+                if mentions(&memory.content, &term) {
+                    memories.record(source_id, 1);
+                }
+            }
+        }
+        self.push_entry(&mut entries, ProvenanceKind::Memory, &term, &memories);
+
+        entries
+    }
+
+    /// Every training source with the number of items it contributed to.
+    pub fn provenance_overview(&self) -> BTreeMap<String, u64> {
+        let semantic = &self.memory.semantic_knowledge;
+        let item_sources = semantic.vocabulary.values().map(|k| &k.sources)
+            .chain(semantic.concepts.values().map(|c| &c.sources))
+            .chain(self.consciousness.pattern_recognition.pattern_sources.values());
+
+        let mut counts = SourceCounts::default();
+        for sources in item_sources {
+            for &(source_id, _) in &sources.0 {
+                counts.record(source_id, 1);
+            }
+        }
+        for memory in &self.memory.episodic_memories {
+            if let Some(source_id) = memory.source_id {
+                counts.record(source_id, 1);
+            }
+        }
+
+        counts.0.iter()
+            .filter_map(|&(id, count)| self.provenance.source_name(id).map(|name| (name.to_string(), count as u64)))
+            .collect()
+    }
+
+    fn push_entry(&self, entries: &mut Vec<ProvenanceEntry>, kind: ProvenanceKind, item: &str, sources: &SourceCounts) {
+        if sources.is_empty() {
+            return;
+        }
+
+        entries.push(ProvenanceEntry {
+            kind,
+            item: item.to_string(),
+            total: sources.total(),
+            sources: sources.top(MAX_SOURCES_PER_ENTRY).into_iter()
+                .map(|(id, count)| SourceAttribution {
+                    source: self.provenance.source_name(id).unwrap_or("unknown").to_string(),
+                    count: count as u64,
+                })
+                .collect(),
+        });
+    }
+}
+
+/// Whether text mentions a term as whole words rather than as part of a longer word.
+pub(crate) fn mentions(text: &str, term: &str) -> bool {
+    let words: Vec<String> = text.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .collect();
+    let term_words: Vec<&str> = term.split_whitespace().collect();
+    words.windows(term_words.len()).any(|window| window.iter().zip(&term_words).all(|(a, b)| a == b))
+}
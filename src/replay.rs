@@ -0,0 +1,366 @@
+use crate::sigel::Sigel;
+use crate::interaction::InteractionEngine;
+use crate::session::ConversationSession;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+pub const DEFAULT_REPLAY_SEED: u64 = 42;
+
+/// Commands that read or write files, skipped so a replay never touches what is on disk.
+const FILE_COMMANDS: &[&str] = &["save", "load", "train"];
+
+/// One recorded user turn and, for golden transcripts, the response expected to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptTurn {
+    #[serde(alias = "user_input")]
+    pub user: String,
+    #[serde(default, alias = "sigel_response", skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+}
+
+/// A scripted conversation: JSON Lines with one `{"user": ..., "expected": ...}` object
+/// per turn. Saved sessions (`my_ai.sig.sessions/*.json`) load as transcripts too.
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    pub turns: Vec<TranscriptTurn>,
+}
+
+impl Transcript {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        if let Ok(session) = serde_json::from_str::<ConversationSession>(&content) {
+            return Ok(Self::from_session(&session));
+        }
+
+        let mut turns = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let turn = serde_json::from_str(line)
+                .map_err(|e| format!("line {}: {}", number + 1, e))?;
+            turns.push(turn);
+        }
+        Ok(Self { turns })
+    }
+
+    /// The session's turns, with the responses given then as the expected ones.
+    pub fn from_session(session: &ConversationSession) -> Self {
+        Self {
+            turns: session.turns.iter().map(|turn| TranscriptTurn {
+                user: turn.user_input.clone(),
+                expected: Some(turn.sigel_response.clone()),
+            }).collect(),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let mut content = String::new();
+        for turn in &self.turns {
+            content.push_str(&serde_json::to_string(turn)?);
+            content.push('\n');
+        }
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// How a replayed response is compared with the expected one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchMode {
+    /// Identical apart from surrounding whitespace.
+    Exact,
+    /// At least this word-level similarity, from 0 to 1.
+    Similarity(f64),
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayConfig {
+    /// Seeds response sampling, so a replay gives the same responses every time.
+    pub seed: u64,
+    pub match_mode: MatchMode,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            seed: DEFAULT_REPLAY_SEED,
+            match_mode: MatchMode::Exact,
+        }
+    }
+}
+
+/// A word of a diff between an expected and an actual response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffWord {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct TurnOutcome {
+    pub user: String,
+    pub expected: Option<String>,
+    pub actual: String,
+    pub similarity: f64,
+    /// Turns without an expected response always pass.
+    pub passed: bool,
+    pub diff: Vec<DiffWord>,
+}
+
+/// The outcome of replaying a transcript, turn by turn.
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    pub seed: u64,
+    pub match_mode: MatchMode,
+    pub turns: Vec<TurnOutcome>,
+}
+
+impl ReplayReport {
+    pub fn passed(&self) -> bool {
+        self.turns.iter().all(|turn| turn.passed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &TurnOutcome)> {
+        self.turns.iter().enumerate().filter(|(_, turn)| !turn.passed)
+    }
+
+    /// The replayed conversation with its actual responses as the expected ones, to
+    /// save as a new golden transcript.
+    pub fn transcript(&self) -> Transcript {
+        Transcript {
+            turns: self.turns.iter().map(|turn| TranscriptTurn {
+                user: turn.user.clone(),
+                expected: Some(turn.actual.clone()),
+            }).collect(),
+        }
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let checked = self.turns.iter().filter(|turn| turn.expected.is_some()).count();
+        let failed = self.failures().count();
+        writeln!(f, "Replayed {} turns with seed {}: {} checked, {} passed, {} failed ({:?})",
+            self.turns.len(), self.seed, checked, checked - failed, failed, self.match_mode)?;
+
+        for (index, turn) in self.failures() {
+            writeln!(f)?;
+            writeln!(f, "Turn {} differs (similarity {:.2})", index + 1, turn.similarity)?;
+            writeln!(f, "  user:     {}", turn.user)?;
+            writeln!(f, "  expected: {}", turn.expected.as_deref().unwrap_or_default())?;
+            writeln!(f, "  actual:   {}", turn.actual)?;
+            writeln!(f, "  diff:     {}", format_diff(&turn.diff))?;
+        }
+        Ok(())
+    }
+}
+
+/// Feeds the user turns of a transcript to a fresh copy of a Sigel with a fixed seed
+/// and compares the responses with the expected ones, so changes to how Sigels think
+/// and respond can be checked against golden conversations.
+pub struct TranscriptReplayer {
+    config: ReplayConfig,
+}
+
+impl TranscriptReplayer {
+    pub fn new() -> Self {
+        Self::with_config(ReplayConfig::default())
+    }
+
+    pub fn with_config(config: ReplayConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &ReplayConfig {
+        &self.config
+    }
+
+    pub fn replay(&self, sigel: &Sigel, transcript: &Transcript) -> ReplayReport {
+        self.replay_with(InteractionEngine::new(), sigel, transcript)
+    }
+
+    /// Replay through `engine`, e.g. one with the personas and filters of a deployment.
+    /// Its decoding settings are kept, apart from the seed. Turns starting with `/` run
+    /// as commands, except `/save`, `/load` and `/train`, which are skipped.
+    pub fn replay_with(&self, mut engine: InteractionEngine, sigel: &Sigel, transcript: &Transcript) -> ReplayReport {
+        let mut decoding = engine.decoding().clone();
+        decoding.seed = Some(self.config.seed);
+        engine.set_decoding(decoding);
+
+        let mut sigel = sigel.clone();
+        let turns = transcript.turns.iter().map(|turn| {
+            let actual = match engine.commands().parse(&turn.user) {
+                Some((command, _)) if FILE_COMMANDS.contains(&command.name()) => {
+                    format!("/{} is skipped during replays", command.name())
+                },
+                Some((command, args)) => command.run(&mut engine, &mut sigel, args),
+                None => engine.interact(&mut sigel, &turn.user),
+            };
+            self.compare(turn, actual)
+        }).collect();
+
+        ReplayReport {
+            seed: self.config.seed,
+            match_mode: self.config.match_mode,
+            turns,
+        }
+    }
+
+    fn compare(&self, turn: &TranscriptTurn, actual: String) -> TurnOutcome {
+        let Some(expected) = &turn.expected else {
+            return TurnOutcome { user: turn.user.clone(), expected: None, actual, similarity: 1.0, passed: true, diff: Vec::new() };
+        };
+
+        let diff = word_diff(expected, &actual);
+        let similarity = similarity(&diff);
+        let passed = match self.config.match_mode {
+            MatchMode::Exact => expected.trim() == actual.trim(),
+            MatchMode::Similarity(threshold) => similarity >= threshold,
+        };
+
+        TurnOutcome { user: turn.user.clone(), expected: Some(expected.clone()), actual, similarity, passed, diff }
+    }
+}
+
+impl Default for TranscriptReplayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Word-level diff along the longest common subsequence of the two texts.
+fn word_diff(expected: &str, actual: &str) -> Vec<DiffWord> {
+    let a: Vec<&str> = expected.split_whitespace().collect();
+    let b: Vec<&str> = actual.split_whitespace().collect();
+
+    // lcs[i][j]: common subsequence length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            diff.push(DiffWord::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(DiffWord::Removed(a[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffWord::Added(b[j].to_string()));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// The diff with runs of removed words as `[-...-]` and added ones as `{+...+}`.
+fn format_diff(diff: &[DiffWord]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut run: Option<(&str, Vec<&str>)> = None;
+    for word in diff {
+        let (marker, text) = match word {
+            DiffWord::Same(text) => ("", text.as_str()),
+            DiffWord::Removed(text) => ("-", text.as_str()),
+            DiffWord::Added(text) => ("+", text.as_str()),
+        };
+        match &mut run {
+            Some((run_marker, words)) if *run_marker == marker => words.push(text),
+            _ => {
+                parts.extend(run.take().map(|(marker, words)| mark(marker, &words)));
+                run = Some((marker, vec![text]));
+            },
+        }
+    }
+    parts.extend(run.map(|(marker, words)| mark(marker, &words)));
+    parts.join(" ")
+}
+
+fn mark(marker: &str, words: &[&str]) -> String {
+    let text = words.join(" ");
+    match marker {
+        "-" => format!("[-{}-]", text),
+        "+" => format!("{{+{}+}}", text),
+        _ => text,
+    }
+}
+
+/// Share of words the two texts have in common, from 0 to 1.
+fn similarity(diff: &[DiffWord]) -> f64 {
+    let same = diff.iter().filter(|word| matches!(word, DiffWord::Same(_))).count();
+    let total = diff.len() + same;
+    if total == 0 {
+        return 1.0;
+    }
+    2.0 * same as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learning::LearningEngine;
+
+    fn words(diff: &[DiffWord]) -> Vec<String> {
+        diff.iter().map(|word| match word {
+            DiffWord::Same(text) => text.clone(),
+            DiffWord::Removed(text) => format!("-{}", text),
+            DiffWord::Added(text) => format!("+{}", text),
+        }).collect()
+    }
+
+    #[test]
+    fn word_diff_follows_the_longest_common_subsequence() {
+        let diff = word_diff("the cat sat on the mat", "the dog sat on a mat");
+        assert_eq!(words(&diff), ["the", "-cat", "+dog", "sat", "on", "-the", "+a", "mat"]);
+        assert_eq!(format_diff(&diff), "the [-cat-] {+dog+} sat on [-the-] {+a+} mat");
+
+        assert_eq!(words(&word_diff("a b c", "a c")), ["a", "-b", "c"]);
+        assert_eq!(words(&word_diff("", "new words")), ["+new", "+words"]);
+    }
+
+    #[test]
+    fn similarity_is_the_share_of_common_words() {
+        assert_eq!(similarity(&word_diff("same  words here", "same words here ")), 1.0);
+        assert_eq!(similarity(&word_diff("", "")), 1.0);
+        assert_eq!(similarity(&word_diff("one two", "three four")), 0.0);
+        let partial = similarity(&word_diff("the cat sat on the mat", "the dog sat on a mat"));
+        assert!((partial - 8.0 / 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn replay_with_a_fixed_seed_is_repeatable() {
+        let directory = std::env::temp_dir().join(format!("sigmos-replay-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("compilers.txt"), "A compiler is a program that translates source code. The compiler checks types before it emits machine code.").unwrap();
+        fs::write(directory.join("rivers.txt"), "A river is part of a landscape. Rain causes floods along the river bank. The old bridge crosses the river.").unwrap();
+        let mut sigel = Sigel::new("replay".to_string());
+        LearningEngine::new().train_from_text_files(&mut sigel, &directory).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let transcript = Transcript {
+            turns: ["Tell me about compilers", "What is a river?", "How do rivers flood?"].iter()
+                .map(|user| TranscriptTurn { user: user.to_string(), expected: None })
+                .collect(),
+        };
+
+        let replayer = TranscriptReplayer::new();
+        let first = replayer.replay(&sigel, &transcript);
+        let second = replayer.replay(&sigel, &transcript);
+        let responses = |report: &ReplayReport| -> Vec<String> { report.turns.iter().map(|turn| turn.actual.clone()).collect() };
+        assert_eq!(responses(&first), responses(&second));
+
+        let golden = replayer.replay(&sigel, &first.transcript());
+        assert!(golden.passed(), "{}", golden);
+        assert!(golden.turns.iter().all(|turn| turn.similarity == 1.0));
+    }
+}
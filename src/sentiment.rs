@@ -0,0 +1,310 @@
+use crate::sigel::*;
+use crate::language::is_stopword;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const BUNDLED_LEXICON: &str = include_str!("../data/vad_lexicon.tsv");
+
+const NEGATORS: &[&str] = &[
+    "not", "no", "never", "nor", "neither", "nobody", "nothing", "nowhere", "without", "cannot", "hardly", "barely",
+];
+
+const INTENSIFIERS: &[&str] = &[
+    "very", "really", "so", "extremely", "absolutely", "completely", "utterly", "totally", "incredibly", "deeply", "truly",
+];
+
+const DIMINISHERS: &[&str] = &["slightly", "somewhat", "fairly", "rather", "mildly", "kinda", "little"];
+
+const FUNCTION_WORDS: &[&str] = &[
+    "the", "and", "but", "for", "with", "from", "that", "this", "these", "those", "there", "their", "they",
+    "have", "has", "had", "was", "were", "are", "been", "will", "would", "could", "should", "into", "onto",
+    "about", "what", "which", "when", "where", "your", "you", "our", "its", "than", "then",
+];
+
+/// Word-level valence/arousal/dominance lexicon.
+///
+/// The file format is one entry per line: `word valence arousal dominance`, separated by
+/// tabs or spaces. Valence ranges from -1.0 to 1.0; arousal and dominance from 0.0 to 1.0.
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct VadLexicon {
+    entries: HashMap<String, EmotionalValue>,
+}
+
+impl VadLexicon {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The lexicon shipped with Sigmos (`data/vad_lexicon.tsv`).
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_LEXICON).expect("bundled VAD lexicon is well-formed")
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path.as_ref())?;
+        Self::parse(&content)
+            .map_err(|e| format!("{}: {}", path.as_ref().display(), e).into())
+    }
+
+    pub fn parse(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut entries = HashMap::new();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 4 {
+                return Err(format!("line {}: expected 'word valence arousal dominance'", line_number + 1).into());
+            }
+
+            let parse_field = |field: &str, min: f64| -> Result<f64, Box<dyn std::error::Error>> {
+                let value: f64 = field.parse()
+                    .map_err(|_| format!("line {}: '{}' is not a number", line_number + 1, field))?;
+                if !(min..=1.0).contains(&value) {
+                    return Err(format!("line {}: {} is outside {}..1.0", line_number + 1, value, min).into());
+                }
+                Ok(value)
+            };
+
+            entries.insert(fields[0].to_lowercase(), EmotionalValue {
+                valence: parse_field(fields[1], -1.0)?,
+                arousal: parse_field(fields[2], 0.0)?,
+                dominance: parse_field(fields[3], 0.0)?,
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Add entries from another lexicon, replacing any words both define.
+    pub fn extend(&mut self, other: VadLexicon) {
+        self.entries.extend(other.entries);
+    }
+
+    pub fn insert(&mut self, word: &str, value: EmotionalValue) {
+        self.entries.insert(word.to_lowercase(), value);
+    }
+
+    pub fn get(&self, word: &str) -> Option<&EmotionalValue> {
+        self.entries.get(word)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SentimentScore {
+    pub valence: f64,
+    pub arousal: f64,
+    pub dominance: f64,
+    pub matched_words: usize,
+}
+
+impl SentimentScore {
+    pub fn neutral() -> Self {
+        Self {
+            valence: 0.0,
+            arousal: 0.5,
+            dominance: 0.5,
+            matched_words: 0,
+        }
+    }
+
+    pub fn to_emotional_value(&self) -> EmotionalValue {
+        EmotionalValue {
+            valence: self.valence,
+            arousal: self.arousal,
+            dominance: self.dominance,
+        }
+    }
+}
+
+/// Lexicon-driven sentiment scoring shared by learning and consciousness processing.
+///
+/// Negators ("not", "never", "n't") flip and dampen the valence of the next few words,
+/// intensifiers and diminishers scale the following sentiment word.
+pub struct SentimentScorer {
+    lexicon: VadLexicon,
+    negation_window: usize,
+    association_weight: f64,
+    propagation_rate: f64,
+}
+
+impl SentimentScorer {
+    pub fn new() -> Self {
+        Self::with_lexicon(VadLexicon::bundled())
+    }
+
+    pub fn with_lexicon(lexicon: VadLexicon) -> Self {
+        Self {
+            lexicon,
+            negation_window: 3,
+            association_weight: 0.5,
+            propagation_rate: 0.1,
+        }
+    }
+
+    pub fn lexicon(&self) -> &VadLexicon {
+        &self.lexicon
+    }
+
+    pub fn score(&self, text: &str) -> SentimentScore {
+        self.score_words(text, None)
+    }
+
+    /// Score text, falling back to the Sigel's learned emotional associations for words
+    /// the lexicon does not cover.
+    pub fn score_for_sigel(&self, sigel: &Sigel, text: &str) -> SentimentScore {
+        self.score_words(text, Some(&sigel.memory.emotional_associations))
+    }
+
+    /// Valence of the text in -1.0..=1.0.
+    pub fn valence(&self, text: &str) -> f64 {
+        self.score(text).valence
+    }
+
+    /// Spread the emotional tone of a sentence to the words that co-occur in it.
+    ///
+    /// Lexicon words keep their lexicon values; other words drift toward the sentence's
+    /// score so that repeated emotional contexts gradually color them.
+    pub fn propagate(&self, sigel: &mut Sigel, sentence: &str) {
+        if let Some((score, words)) = self.emotional_context(sentence) {
+            for word in words {
+                self.apply_emotional_evidence(sigel, &word, &score, 1);
+            }
+        }
+    }
+
+    /// The sentence score and the content words it should propagate to, if the sentence carries any emotion.
+    pub fn emotional_context(&self, sentence: &str) -> Option<(SentimentScore, Vec<String>)> {
+        let score = self.score(sentence);
+        if score.matched_words == 0 {
+            return None;
+        }
+
+        let words = sentence.split_whitespace()
+            .map(Self::normalize_word)
+            .filter(|word| {
+                word.len() >= 3
+                    && word.chars().all(|c| c.is_alphabetic())
+                    && !FUNCTION_WORDS.contains(&word.as_str())
+                    && !is_stopword(word)
+                    && !NEGATORS.contains(&word.as_str())
+                    && !INTENSIFIERS.contains(&word.as_str())
+                    && !DIMINISHERS.contains(&word.as_str())
+            })
+            .collect();
+
+        Some((score, words))
+    }
+
+    /// Move a word's emotional association toward `score` as if it had been seen `occurrences` times.
+    pub fn apply_emotional_evidence(&self, sigel: &mut Sigel, word: &str, score: &SentimentScore, occurrences: u64) {
+        let association = match self.lexicon.get(word) {
+            Some(value) => {
+                sigel.memory.emotional_associations.insert(word.to_string(), value.clone());
+                value.valence
+            },
+            None => {
+                let rate = 1.0 - (1.0 - self.propagation_rate).powi(occurrences.min(i32::MAX as u64) as i32);
+                let value = sigel.memory.emotional_associations
+                    .entry(word.to_string())
+                    .or_insert_with(|| SentimentScore::neutral().to_emotional_value());
+                value.valence += (score.valence - value.valence) * rate;
+                value.arousal += (score.arousal - value.arousal) * rate;
+                value.dominance += (score.dominance - value.dominance) * rate;
+                value.valence
+            },
+        };
+
+        if let Some(knowledge) = sigel.memory.semantic_knowledge.vocabulary.get_mut(word) {
+            knowledge.emotional_valence = association;
+        }
+    }
+
+    fn score_words(&self, text: &str, associations: Option<&HashMap<String, EmotionalValue>>) -> SentimentScore {
+        let mut valence_sum = 0.0;
+        let mut arousal_sum = 0.0;
+        let mut dominance_sum = 0.0;
+        let mut weight_sum = 0.0;
+        let mut matched_words = 0;
+
+        let mut negation_remaining = 0;
+        let mut intensity: f64 = 1.0;
+
+        for raw in text.split_whitespace() {
+            let word = Self::normalize_word(raw);
+            let ends_clause = raw.ends_with([',', ';', '.', '!', '?', ':']);
+
+            if NEGATORS.contains(&word.as_str()) || word.ends_with("n't") {
+                negation_remaining = self.negation_window;
+            } else if INTENSIFIERS.contains(&word.as_str()) {
+                intensity = (intensity * 1.5).min(2.0);
+            } else if DIMINISHERS.contains(&word.as_str()) {
+                intensity *= 0.5;
+            } else {
+                let value = self.lexicon.get(&word)
+                    .map(|value| (value, 1.0))
+                    .or_else(|| associations.and_then(|a| a.get(&word)).map(|value| (value, self.association_weight)));
+
+                if let Some((value, weight)) = value {
+                    let mut valence = value.valence * intensity;
+                    let mut arousal = 0.5 + (value.arousal - 0.5) * intensity;
+                    if negation_remaining > 0 {
+                        // "not happy" is unhappy, but less strongly than "sad"
+                        valence = -valence * 0.5;
+                        arousal = 0.5 + (arousal - 0.5) * 0.5;
+                    }
+
+                    valence_sum += valence * weight;
+                    arousal_sum += arousal * weight;
+                    dominance_sum += value.dominance * weight;
+                    weight_sum += weight;
+                    matched_words += 1;
+                }
+
+                intensity = 1.0;
+                negation_remaining = negation_remaining.saturating_sub(1);
+            }
+
+            if ends_clause || word == "but" {
+                negation_remaining = 0;
+                intensity = 1.0;
+            }
+        }
+
+        if weight_sum == 0.0 {
+            return SentimentScore::neutral();
+        }
+
+        SentimentScore {
+            valence: (valence_sum / weight_sum).clamp(-1.0, 1.0),
+            arousal: (arousal_sum / weight_sum).clamp(0.0, 1.0),
+            dominance: (dominance_sum / weight_sum).clamp(0.0, 1.0),
+            matched_words,
+        }
+    }
+
+    fn normalize_word(raw: &str) -> String {
+        raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+            .trim_matches('\'')
+            .to_lowercase()
+    }
+}
+
+impl Default for SentimentScorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
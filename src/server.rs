@@ -1,20 +1,26 @@
 use crate::sigel::*;
 use crate::learning::LearningEngine;
 use crate::cosmos::CosmicProcessor;
+use crate::config::SigmosConfig;
 use crate::{load_sigel_from_file, save_sigel_to_file};
-use tokio::time::{interval, Duration};
+use tokio::sync::watch;
+use tokio::time::{interval, interval_at, Duration, Instant, Interval};
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use uuid::Uuid;
 use log::{info, warn, error};
 
+// How often `watch_config_file` checks the file for changes
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 pub struct SigmosServer {
     master_sigel: Arc<Mutex<Sigel>>,
     active_sigels: Arc<Mutex<HashMap<Uuid, Arc<Mutex<Sigel>>>>>,
-    learning_engine: LearningEngine,
+    learning_engine: Arc<LearningEngine>,
     cosmic_processor: CosmicProcessor,
-    config: ServerConfig,
+    // Shared with the background tasks, which reschedule when it changes
+    config: Arc<watch::Sender<ServerConfig>>,
     is_running: Arc<Mutex<bool>>,
 }
 
@@ -47,8 +53,35 @@ impl Default for ServerConfig {
     }
 }
 
+impl ServerConfig {
+    /// Settings from `[server]`, `[cosmic]` and `[general]` of sigmos.toml.
+    pub fn from_config(config: &SigmosConfig) -> Self {
+        Self {
+            master_sigel_path: config.server.master_sigel.clone(),
+            sigel_directory: config.general.default_sigel_directory.clone(),
+            background_learning: config.server.enable_background_learning,
+            cosmic_alignment_interval: Duration::from_secs(config.cosmic.cosmic_alignment_interval),
+            evolution_interval: Duration::from_secs(config.cosmic.evolution_interval),
+            auto_save_interval: Duration::from_secs(config.server.auto_save_interval),
+            observation_mode: config.server.enable_system_observation,
+            system_monitoring: config.server.enable_detailed_monitoring,
+            max_active_sigels: config.server.max_active_sigels,
+        }
+    }
+}
+
 impl SigmosServer {
     pub fn new(config: ServerConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::with_learning_engine(config, LearningEngine::new())
+    }
+
+    /// A server with the settings of sigmos.toml, learning as `[learning]` and
+    /// `[consciousness]` say.
+    pub fn from_config(config: &SigmosConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::with_learning_engine(ServerConfig::from_config(config), LearningEngine::from_config(config))
+    }
+
+    fn with_learning_engine(config: ServerConfig, learning_engine: LearningEngine) -> Result<Self, Box<dyn std::error::Error>> {
         // Load or create master Sigel
         let master_sigel = if config.master_sigel_path.exists() {
             info!("Loading existing master Sigel from {:?}", config.master_sigel_path);
@@ -71,9 +104,9 @@ impl SigmosServer {
         Ok(Self {
             master_sigel: Arc::new(Mutex::new(master_sigel)),
             active_sigels: Arc::new(Mutex::new(HashMap::new())),
-            learning_engine: LearningEngine::new(),
+            learning_engine: Arc::new(learning_engine),
             cosmic_processor: CosmicProcessor::new(),
-            config,
+            config: Arc::new(watch::Sender::new(config)),
             is_running: Arc::new(Mutex::new(false)),
         })
     }
@@ -109,19 +142,16 @@ impl SigmosServer {
             server_clone.auto_save_task().await;
         });
 
-        if self.config.background_learning {
-            let server_clone = self.clone();
-            tokio::spawn(async move {
-                server_clone.background_learning_task().await;
-            });
-        }
+        // Both run idle while switched off, so a reload can switch them on
+        let server_clone = self.clone();
+        tokio::spawn(async move {
+            server_clone.background_learning_task().await;
+        });
 
-        if self.config.system_monitoring {
-            let server_clone = self.clone();
-            tokio::spawn(async move {
-                server_clone.system_monitoring_task().await;
-            });
-        }
+        let server_clone = self.clone();
+        tokio::spawn(async move {
+            server_clone.system_monitoring_task().await;
+        });
 
         info!("SigmosServer started successfully");
         Ok(())
@@ -145,7 +175,7 @@ impl SigmosServer {
     pub fn register_sigel(&self, sigel: Sigel) -> Result<Uuid, Box<dyn std::error::Error>> {
         let mut active_sigels = self.active_sigels.lock().unwrap();
         
-        if active_sigels.len() >= self.config.max_active_sigels {
+        if active_sigels.len() >= self.config.borrow().max_active_sigels {
             return Err("Maximum number of active Sigels reached".into());
         }
 
@@ -163,7 +193,7 @@ impl SigmosServer {
             // Save the Sigel before removing
             let sigel = sigel_arc.lock().unwrap();
             let filename = format!("{}.sig", sigel.name);
-            let path = self.config.sigel_directory.join(filename);
+            let path = self.config.borrow().sigel_directory.join(filename);
             drop(sigel); // Release lock before saving
             
             if let Ok(sigel) = sigel_arc.lock() {
@@ -198,13 +228,92 @@ impl SigmosServer {
             .collect()
     }
 
+    pub fn config(&self) -> ServerConfig {
+        self.config.borrow().clone()
+    }
+
+    /// Apply new settings while running. Background tasks pick up new intervals and
+    /// switches straight away, and loaded Sigels stay loaded. The master Sigel and
+    /// Sigel directory only change on restart.
+    pub fn reload_config(&self, new_config: ServerConfig) -> ConfigReload {
+        let mut reload = ConfigReload::default();
+        let active = self.active_sigels.lock().unwrap().len();
+
+        self.config.send_modify(|config| {
+            if new_config.master_sigel_path != config.master_sigel_path {
+                reload.needs_restart.push(format!("master Sigel {:?} -> {:?}", config.master_sigel_path, new_config.master_sigel_path));
+            }
+            if new_config.sigel_directory != config.sigel_directory {
+                reload.needs_restart.push(format!("Sigel directory {:?} -> {:?}", config.sigel_directory, new_config.sigel_directory));
+            }
+
+            let mut apply = |name: &str, old: String, new: String| {
+                if old != new {
+                    reload.applied.push(format!("{} {} -> {}", name, old, new));
+                }
+            };
+            apply("background learning", config.background_learning.to_string(), new_config.background_learning.to_string());
+            apply("cosmic alignment interval", format!("{:?}", config.cosmic_alignment_interval), format!("{:?}", new_config.cosmic_alignment_interval));
+            apply("evolution interval", format!("{:?}", config.evolution_interval), format!("{:?}", new_config.evolution_interval));
+            apply("auto-save interval", format!("{:?}", config.auto_save_interval), format!("{:?}", new_config.auto_save_interval));
+            apply("observation mode", config.observation_mode.to_string(), new_config.observation_mode.to_string());
+            apply("system monitoring", config.system_monitoring.to_string(), new_config.system_monitoring.to_string());
+            apply("max active Sigels", config.max_active_sigels.to_string(), new_config.max_active_sigels.to_string());
+
+            *config = ServerConfig {
+                master_sigel_path: config.master_sigel_path.clone(),
+                sigel_directory: config.sigel_directory.clone(),
+                ..new_config
+            };
+        });
+
+        for change in &reload.applied {
+            info!("Config reloaded: {}", change);
+        }
+        for change in &reload.needs_restart {
+            warn!("Config change needs a restart: {}", change);
+        }
+        if active > self.config.borrow().max_active_sigels {
+            warn!("{} Sigels are active, more than the new maximum; they stay loaded but no more can register", active);
+        }
+        reload
+    }
+
+    /// Reload with `load` whenever the file at `path` changes, until the server stops.
+    /// Settings that fail to load are logged and the current ones kept.
+    pub async fn watch_config_file<F>(&self, path: PathBuf, load: F)
+    where
+        F: Fn() -> Result<ServerConfig, Box<dyn std::error::Error>>,
+    {
+        let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        let mut last_modified = modified(&path);
+        let mut interval = interval(CONFIG_POLL_INTERVAL);
+
+        while *self.is_running.lock().unwrap() {
+            interval.tick().await;
+
+            let current = modified(&path);
+            if current == last_modified {
+                continue;
+            }
+            last_modified = current;
+
+            info!("{:?} changed, reloading configuration", path);
+            match load() {
+                Ok(config) => { self.reload_config(config); },
+                Err(e) => error!("Keeping the current configuration: {}", e),
+            }
+        }
+    }
+
     async fn load_existing_sigels(&self) -> Result<(), Box<dyn std::error::Error>> {
-        if !self.config.sigel_directory.exists() {
+        let sigel_directory = self.config.borrow().sigel_directory.clone();
+        if !sigel_directory.exists() {
             return Ok(());
         }
 
         let mut count = 0;
-        for entry in std::fs::read_dir(&self.config.sigel_directory)? {
+        for entry in std::fs::read_dir(&sigel_directory)? {
             let entry = entry?;
             let path = entry.path();
             
@@ -227,11 +336,10 @@ impl SigmosServer {
     }
 
     async fn cosmic_alignment_task(&self) {
-        let mut interval = interval(self.config.cosmic_alignment_interval);
+        let mut updates = self.config.subscribe();
+        let mut interval = interval(self.config.borrow().cosmic_alignment_interval);
         
-        while *self.is_running.lock().unwrap() {
-            interval.tick().await;
-            
+        while self.next_tick(&mut interval, &mut updates, |config| config.cosmic_alignment_interval).await {
             // Align master Sigel
             if let Ok(mut master) = self.master_sigel.lock() {
                 self.cosmic_processor.align_with_cosmos(&mut master);
@@ -250,11 +358,10 @@ impl SigmosServer {
     }
 
     async fn evolution_task(&self) {
-        let mut interval = interval(self.config.evolution_interval);
+        let mut updates = self.config.subscribe();
+        let mut interval = interval(self.config.borrow().evolution_interval);
         
-        while *self.is_running.lock().unwrap() {
-            interval.tick().await;
-            
+        while self.next_tick(&mut interval, &mut updates, |config| config.evolution_interval).await {
             // Evolve master Sigel
             if let Ok(mut master) = self.master_sigel.lock() {
                 master.evolve();
@@ -273,11 +380,10 @@ impl SigmosServer {
     }
 
     async fn auto_save_task(&self) {
-        let mut interval = interval(self.config.auto_save_interval);
+        let mut updates = self.config.subscribe();
+        let mut interval = interval(self.config.borrow().auto_save_interval);
         
-        while *self.is_running.lock().unwrap() {
-            interval.tick().await;
-            
+        while self.next_tick(&mut interval, &mut updates, |config| config.auto_save_interval).await {
             if let Err(e) = self.save_all_sigels().await {
                 error!("Auto-save failed: {}", e);
             } else {
@@ -287,11 +393,14 @@ impl SigmosServer {
     }
 
     async fn background_learning_task(&self) {
+        let mut updates = self.config.subscribe();
         let mut interval = interval(Duration::from_secs(120)); // Every 2 minutes
         
-        while *self.is_running.lock().unwrap() {
-            interval.tick().await;
-            
+        while self.next_tick(&mut interval, &mut updates, |_| Duration::from_secs(120)).await {
+            if !self.config.borrow().background_learning {
+                continue;
+            }
+
             // Perform light background learning for all Sigels
             if let Ok(active_sigels) = self.active_sigels.lock() {
                 for (_, sigel_arc) in active_sigels.iter() {
@@ -313,12 +422,12 @@ impl SigmosServer {
     }
 
     async fn system_monitoring_task(&self) {
+        let mut updates = self.config.subscribe();
         let mut interval = interval(Duration::from_secs(60)); // Every minute
         
-        while *self.is_running.lock().unwrap() {
-            interval.tick().await;
-            
-            if self.config.observation_mode {
+        while self.next_tick(&mut interval, &mut updates, |_| Duration::from_secs(60)).await {
+            let config = self.config.borrow().clone();
+            if config.system_monitoring && config.observation_mode {
                 // Simulate system observation - Sigels learning from environment
                 let observations = self.collect_system_observations().await;
                 
@@ -351,10 +460,36 @@ impl SigmosServer {
         observations
     }
 
+    /// Waits for the next tick of `interval`, restarting it whenever a reload changes
+    /// the period `period` reads from the config. False once the server has stopped.
+    async fn next_tick(
+        &self,
+        interval: &mut Interval,
+        updates: &mut watch::Receiver<ServerConfig>,
+        period: fn(&ServerConfig) -> Duration,
+    ) -> bool {
+        loop {
+            tokio::select! {
+                _ = interval.tick() => return *self.is_running.lock().unwrap(),
+                changed = updates.changed() => {
+                    if changed.is_err() {
+                        return false;
+                    }
+                    let new_period = period(&updates.borrow_and_update());
+                    if new_period != interval.period() {
+                        *interval = interval_at(Instant::now() + new_period, new_period);
+                    }
+                },
+            }
+        }
+    }
+
     async fn save_all_sigels(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.config();
+
         // Save master Sigel
         if let Ok(master) = self.master_sigel.lock() {
-            save_sigel_to_file(&*master, &self.config.master_sigel_path)?;
+            save_sigel_to_file(&*master, &config.master_sigel_path)?;
         }
         
         // Save all active Sigels
@@ -362,7 +497,7 @@ impl SigmosServer {
             for (_, sigel_arc) in active_sigels.iter() {
                 if let Ok(sigel) = sigel_arc.lock() {
                     let filename = format!("{}.sig", sigel.name);
-                    let path = self.config.sigel_directory.join(filename);
+                    let path = config.sigel_directory.join(filename);
                     save_sigel_to_file(&*sigel, &path)?;
                 }
             }
@@ -402,14 +537,22 @@ impl Clone for SigmosServer {
         Self {
             master_sigel: Arc::clone(&self.master_sigel),
             active_sigels: Arc::clone(&self.active_sigels),
-            learning_engine: LearningEngine::new(),
+            learning_engine: Arc::clone(&self.learning_engine),
             cosmic_processor: CosmicProcessor::new(),
-            config: self.config.clone(),
+            config: Arc::clone(&self.config),
             is_running: Arc::clone(&self.is_running),
         }
     }
 }
 
+/// What a config reload changed.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigReload {
+    pub applied: Vec<String>,
+    /// Changes ignored until the server is restarted.
+    pub needs_restart: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ServerStatus {
     pub is_running: bool,
@@ -0,0 +1,128 @@
+use crate::interaction::ConversationTurn;
+use crate::dialogue_state::DialogueState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+const SESSIONS_EXTENSION: &str = "sessions";
+
+/// A conversation with a Sigel that can be saved and resumed later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSession {
+    pub id: Uuid,
+    pub sigel_id: Uuid,
+    pub started_at: SystemTime,
+    pub updated_at: SystemTime,
+    pub turns: Vec<ConversationTurn>,
+    pub context_memory: HashMap<String, f64>,
+    #[serde(default)]
+    pub dialogue_state: DialogueState,
+}
+
+impl ConversationSession {
+    pub fn new(sigel_id: Uuid) -> Self {
+        let now = SystemTime::now();
+        Self {
+            id: Uuid::new_v4(),
+            sigel_id,
+            started_at: now,
+            updated_at: now,
+            turns: Vec::new(),
+            context_memory: HashMap::new(),
+            dialogue_state: DialogueState::default(),
+        }
+    }
+}
+
+/// Sessions saved for one Sigel, one JSON file each, in a directory next to the
+/// Sigel file, e.g. `my_ai.sig.sessions/`.
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self { dir: dir.as_ref().to_path_buf() }
+    }
+
+    pub fn for_sigel<P: AsRef<Path>>(sigel_path: P) -> Self {
+        let mut dir = sigel_path.as_ref().as_os_str().to_owned();
+        dir.push(".");
+        dir.push(SESSIONS_EXTENSION);
+        Self::new(PathBuf::from(dir))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn save(&self, session: &ConversationSession) -> Result<(), Box<dyn std::error::Error>> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(session.id), serde_json::to_string_pretty(session)?)?;
+        Ok(())
+    }
+
+    /// Load a session by id, unique id prefix, or `latest` for the most recently updated one.
+    pub fn load(&self, id: &str) -> Result<ConversationSession, Box<dyn std::error::Error>> {
+        if id == "latest" {
+            return self.list()?
+                .into_iter()
+                .max_by_key(|session| session.updated_at)
+                .ok_or_else(|| format!("No saved sessions in {}", self.dir.display()).into());
+        }
+
+        let matches: Vec<Uuid> = self.ids()?
+            .into_iter()
+            .filter(|candidate| candidate.to_string().starts_with(&id.to_lowercase()))
+            .collect();
+        match matches.as_slice() {
+            [single] => {
+                let content = fs::read_to_string(self.path(*single))?;
+                Ok(serde_json::from_str(&content)?)
+            },
+            [] => Err(format!("No session '{}' in {}", id, self.dir.display()).into()),
+            _ => Err(format!("Session id '{}' is ambiguous", id).into()),
+        }
+    }
+
+    /// All saved sessions, oldest first. Sessions that cannot be read are skipped.
+    pub fn list(&self) -> Result<Vec<ConversationSession>, Box<dyn std::error::Error>> {
+        let mut sessions = Vec::new();
+        for id in self.ids()? {
+            let path = self.path(id);
+            let session = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str::<ConversationSession>(&content).map_err(|e| e.to_string()));
+            match session {
+                Ok(session) => sessions.push(session),
+                Err(e) => log::warn!("Skipping unreadable session {}: {}", path.display(), e),
+            }
+        }
+        sessions.sort_by_key(|session| session.started_at);
+        Ok(sessions)
+    }
+
+    fn ids(&self) -> Result<Vec<Uuid>, Box<dyn std::error::Error>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| Uuid::parse_str(stem).ok()) {
+                    ids.push(id);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    fn path(&self, id: Uuid) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
+use crate::word_vectors::WordVectors;
+use crate::language::Language;
+use crate::memory_index::MemoryIndex;
 use std::time::SystemTime;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,8 +18,26 @@ pub struct Sigel {
     pub created_at: SystemTime,
     pub last_evolved: SystemTime,
     pub version: String,
+    #[serde(default)]
+    pub provenance: ProvenanceTable,
+    /// Saved next to the Sigel file rather than inside it.
+    #[serde(skip)]
+    pub memory_index: MemoryIndex,
 }
 
+/// Training sources, indexed by the compact ids used in `SourceCounts`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProvenanceTable {
+    pub sources: Vec<String>,
+    #[serde(skip)]
+    index: HashMap<String, u32>,
+}
+
+/// How often each source contributed to an item, as `(source id, count)` pairs sorted by id.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SourceCounts(pub Vec<(u32, u32)>);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Essence {
     pub character_traits: HashMap<String, f64>,
@@ -25,6 +46,63 @@ pub struct Essence {
     pub creative_potential: f64,
     pub logical_capacity: f64,
     pub empathy_level: f64,
+    /// Name of a persona to phrase responses with instead of the communication style's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+    /// Keep `communication_style` as configured, whatever the conversation suggests.
+    #[serde(default)]
+    pub style_locked: bool,
+    /// How strongly conversations have pointed at each style; empty until they first do.
+    #[serde(default, skip_serializing_if = "StyleMixture::is_empty")]
+    pub style_mixture: StyleMixture,
+}
+
+/// Communication style as a weighted mixture, shifted a little by each piece of evidence.
+/// The weights add up to 1.0.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StyleMixture {
+    pub weights: HashMap<CommunicationStyle, f64>,
+}
+
+impl StyleMixture {
+    /// Entirely one style.
+    pub fn pure(style: CommunicationStyle) -> Self {
+        Self { weights: HashMap::from([(style, 1.0)]) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    pub fn weight(&self, style: &CommunicationStyle) -> f64 {
+        self.weights.get(style).copied().unwrap_or(0.0)
+    }
+
+    pub fn dominant(&self) -> Option<CommunicationStyle> {
+        CommunicationStyle::ALL.into_iter()
+            .filter(|style| self.weight(style) > 0.0)
+            .max_by(|a, b| self.weight(a).total_cmp(&self.weight(b)))
+    }
+
+    /// Move `rate` of the whole mixture's weight towards `style`.
+    pub fn shift_towards(&mut self, style: CommunicationStyle, rate: f64) {
+        let rate = rate.clamp(0.0, 1.0);
+        for weight in self.weights.values_mut() {
+            *weight *= 1.0 - rate;
+        }
+        *self.weights.entry(style).or_insert(0.0) += rate;
+        self.weights.retain(|_, weight| *weight > 1e-4);
+    }
+
+    /// Styles by weight, strongest first.
+    pub fn ranked(&self) -> Vec<(CommunicationStyle, f64)> {
+        let mut ranked: Vec<_> = CommunicationStyle::ALL.into_iter()
+            .map(|style| (style.clone(), self.weight(&style)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +120,11 @@ pub struct PatternMatrix {
     pub semantic_networks: HashMap<String, Vec<String>>,
     pub association_strength: HashMap<(String, String), f64>,
     pub temporal_patterns: Vec<TemporalPattern>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pattern_sources: HashMap<String, SourceCounts>,
+    /// "word neighbor" -> training sources of that entry in `semantic_networks`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub association_sources: HashMap<String, SourceCounts>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +132,8 @@ pub struct TemporalPattern {
     pub sequence: Vec<String>,
     pub frequency: f64,
     pub context_relevance: f64,
+    #[serde(default, skip_serializing_if = "SourceCounts::is_empty")]
+    pub sources: SourceCounts,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +152,8 @@ pub struct EpisodicMemory {
     pub context: String,
     pub emotional_weight: f64,
     pub relevance_score: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +161,20 @@ pub struct SemanticKnowledge {
     pub vocabulary: HashMap<String, WordKnowledge>,
     pub concepts: HashMap<String, ConceptNode>,
     pub relationships: Vec<ConceptRelation>,
+    #[serde(default, skip_serializing_if = "WordVectors::is_empty")]
+    pub word_vectors: WordVectors,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub languages: HashMap<Language, LanguagePartition>,
+}
+
+/// Vocabulary and n-gram patterns learned from text in one language.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LanguagePartition {
+    pub sentences: u64,
+    /// Word -> occurrences in this language.
+    pub vocabulary: HashMap<String, u32>,
+    /// N-gram -> strength, accumulated like `linguistic_patterns`.
+    pub patterns: HashMap<String, f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +183,8 @@ pub struct WordKnowledge {
     pub contexts: Vec<String>,
     pub emotional_valence: f64,
     pub semantic_weight: f64,
+    #[serde(default, skip_serializing_if = "SourceCounts::is_empty")]
+    pub sources: SourceCounts,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +194,8 @@ pub struct ConceptNode {
     pub connections: Vec<String>,
     pub abstraction_level: f64,
     pub certainty: f64,
+    #[serde(default, skip_serializing_if = "SourceCounts::is_empty")]
+    pub sources: SourceCounts,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,9 +204,11 @@ pub struct ConceptRelation {
     pub to: String,
     pub relation_type: RelationType,
     pub strength: f64,
+    #[serde(default, skip_serializing_if = "SourceCounts::is_empty")]
+    pub sources: SourceCounts,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RelationType {
     IsA,
     PartOf,
@@ -128,7 +235,7 @@ pub struct EmotionalValue {
     pub dominance: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum CommunicationStyle {
     Formal,
     Casual,
@@ -141,6 +248,39 @@ pub enum CommunicationStyle {
     Philosophical,
 }
 
+impl CommunicationStyle {
+    pub const ALL: [CommunicationStyle; 9] = [
+        CommunicationStyle::Formal,
+        CommunicationStyle::Casual,
+        CommunicationStyle::Academic,
+        CommunicationStyle::Creative,
+        CommunicationStyle::Analytical,
+        CommunicationStyle::Empathetic,
+        CommunicationStyle::Cosmic,
+        CommunicationStyle::Transcendent,
+        CommunicationStyle::Philosophical,
+    ];
+
+    /// Lowercase name, which is also the name of the style's bundled persona.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommunicationStyle::Formal => "formal",
+            CommunicationStyle::Casual => "casual",
+            CommunicationStyle::Academic => "academic",
+            CommunicationStyle::Creative => "creative",
+            CommunicationStyle::Analytical => "analytical",
+            CommunicationStyle::Empathetic => "empathetic",
+            CommunicationStyle::Cosmic => "cosmic",
+            CommunicationStyle::Transcendent => "transcendent",
+            CommunicationStyle::Philosophical => "philosophical",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.name() == name.to_lowercase())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LearningState {
     pub training_iterations: u64,
@@ -173,6 +313,8 @@ impl Sigel {
             created_at: SystemTime::now(),
             last_evolved: SystemTime::now(),
             version: "0.1.0".to_string(),
+            provenance: ProvenanceTable::default(),
+            memory_index: MemoryIndex::default(),
         }
     }
 
@@ -193,10 +335,19 @@ impl Sigel {
             context,
             emotional_weight,
             relevance_score: 1.0,
+            source_id: None,
         };
+        self.memory_index.add(&memory);
         self.memory.episodic_memories.push(memory);
     }
 
+    pub fn add_memory_from_source(&mut self, content: String, context: String, emotional_weight: f64, source_id: u32) {
+        self.add_memory(content, context, emotional_weight);
+        if let Some(memory) = self.memory.episodic_memories.last_mut() {
+            memory.source_id = Some(source_id);
+        }
+    }
+
     pub fn learn_word(&mut self, word: String, context: String) {
         let word_knowledge = self.memory.semantic_knowledge.vocabulary
             .entry(word.clone())
@@ -207,6 +358,142 @@ impl Sigel {
             word_knowledge.contexts.push(context);
         }
     }
+
+    /// Set the communication style outright, replacing whatever conversations had built up.
+    pub fn set_communication_style(&mut self, style: CommunicationStyle) {
+        self.essence.style_mixture = StyleMixture::pure(style.clone());
+        self.essence.communication_style = style;
+    }
+
+    /// The language most of the training text was written in.
+    pub fn dominant_language(&self) -> Option<Language> {
+        self.memory.semantic_knowledge.languages.iter()
+            .filter(|(_, partition)| partition.sentences > 0)
+            .max_by(|a, b| a.1.sentences.cmp(&b.1.sentences).then(b.0.cmp(a.0)))
+            .map(|(&language, _)| language)
+    }
+
+    /// Drop partition entries for words and patterns no longer in the shared vocabulary and patterns.
+    pub fn prune_language_partitions(&mut self) {
+        let vocabulary = &self.memory.semantic_knowledge.vocabulary;
+        let patterns = &self.consciousness.pattern_recognition.linguistic_patterns;
+        for partition in self.memory.semantic_knowledge.languages.values_mut() {
+            partition.vocabulary.retain(|word, _| vocabulary.contains_key(word));
+            partition.patterns.retain(|pattern, _| patterns.contains_key(pattern));
+        }
+    }
+}
+
+impl ProvenanceTable {
+    /// The id for a source, registering it on first use.
+    pub fn source_id(&mut self, source: &str) -> u32 {
+        // The lookup index isn't serialized, so rebuild it after loading
+        if self.index.len() != self.sources.len() {
+            self.index = self.sources.iter()
+                .enumerate()
+                .map(|(id, name)| (name.clone(), id as u32))
+                .collect();
+        }
+
+        if let Some(&id) = self.index.get(source) {
+            return id;
+        }
+
+        let id = self.sources.len() as u32;
+        self.sources.push(source.to_string());
+        self.index.insert(source.to_string(), id);
+        id
+    }
+
+    pub fn source_name(&self, id: u32) -> Option<&str> {
+        self.sources.get(id as usize).map(|s| s.as_str())
+    }
+
+    /// Look up a source by its full name, or by file name if that is unambiguous.
+    pub fn find(&self, source: &str) -> Option<u32> {
+        if let Some(id) = self.sources.iter().position(|s| s == source) {
+            return Some(id as u32);
+        }
+
+        let mut matches = self.sources.iter()
+            .enumerate()
+            .filter(|(_, s)| std::path::Path::new(s).file_name().is_some_and(|name| name == source));
+        match (matches.next(), matches.next()) {
+            (Some((id, _)), None) => Some(id as u32),
+            _ => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+/// The `association_sources` key of `neighbor` in `word`'s `semantic_networks` entry.
+pub(crate) fn association_key(word: &str, neighbor: &str) -> String {
+    format!("{} {}", word, neighbor)
+}
+
+impl SourceCounts {
+    pub fn record(&mut self, source_id: u32, count: u32) {
+        match self.0.binary_search_by_key(&source_id, |&(id, _)| id) {
+            Ok(idx) => self.0[idx].1 = self.0[idx].1.saturating_add(count),
+            Err(idx) => self.0.insert(idx, (source_id, count)),
+        }
+    }
+
+    pub fn merge(&mut self, other: &SourceCounts) {
+        for &(source_id, count) in &other.0 {
+            self.record(source_id, count);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn count(&self, source_id: u32) -> u32 {
+        self.0.binary_search_by_key(&source_id, |&(id, _)| id)
+            .map(|idx| self.0[idx].1)
+            .unwrap_or(0)
+    }
+
+    /// Keep `keep` (0.0-1.0) of one source's count, returning the count before scaling.
+    pub fn scale(&mut self, source_id: u32, keep: f64) -> u32 {
+        let Ok(idx) = self.0.binary_search_by_key(&source_id, |&(id, _)| id) else { return 0 };
+        let before = self.0[idx].1;
+        let after = (before as f64 * keep.clamp(0.0, 1.0)).round() as u32;
+        if after == 0 {
+            self.0.remove(idx);
+        } else {
+            self.0[idx].1 = after;
+        }
+        before
+    }
+
+    pub fn scale_all(&mut self, keep: f64) {
+        let keep = keep.clamp(0.0, 1.0);
+        for (_, count) in &mut self.0 {
+            *count = (*count as f64 * keep).round() as u32;
+        }
+        self.0.retain(|&(_, count)| count > 0);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.iter().map(|&(_, count)| count as u64).sum()
+    }
+
+    /// The `n` largest contributors, most frequent first.
+    pub fn top(&self, n: usize) -> Vec<(u32, u32)> {
+        let mut counts = self.0.clone();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        counts.truncate(n);
+        counts
+    }
 }
 
 impl Default for Essence {
@@ -224,6 +511,9 @@ impl Default for Essence {
             creative_potential: 0.7,
             logical_capacity: 0.9,
             empathy_level: 0.6,
+            persona: None,
+            style_locked: false,
+            style_mixture: StyleMixture::default(),
         }
     }
 }
@@ -247,6 +537,8 @@ impl Default for PatternMatrix {
             semantic_networks: HashMap::new(),
             association_strength: HashMap::new(),
             temporal_patterns: Vec::new(),
+            pattern_sources: HashMap::new(),
+            association_sources: HashMap::new(),
         }
     }
 }
@@ -268,6 +560,8 @@ impl Default for SemanticKnowledge {
             vocabulary: HashMap::new(),
             concepts: HashMap::new(),
             relationships: Vec::new(),
+            word_vectors: WordVectors::default(),
+            languages: HashMap::new(),
         }
     }
 }
@@ -279,6 +573,7 @@ impl Default for WordKnowledge {
             contexts: Vec::new(),
             emotional_valence: 0.0,
             semantic_weight: 1.0,
+            sources: SourceCounts::default(),
         }
     }
 }
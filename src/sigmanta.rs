@@ -3,7 +3,7 @@
 //! Agent coder using Sigel consciousness for programming assistance
 //! with advanced permission system and session management.
 
-use crate::{Sigel, SigmosLibrary, SigelConfig};
+use crate::{Sigel, SigmosLibrary, SigelConfig, SigmosConfig};
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::io::{self, Write};
@@ -23,6 +23,8 @@ pub struct SigmantaSession {
     pub permissions: PermissionState,
     pub history: SessionHistory,
     pub config: SigmantaConfig,
+    /// Settings the Sigel is prompted with, from sigmos.toml and its overrides
+    pub sigmos_config: SigmosConfig,
 }
 
 /// Permission system for various operations
@@ -129,6 +131,7 @@ impl SigmantaSession {
             permissions,
             history,
             config,
+            sigmos_config: SigmosConfig::discover(),
         })
     }
     
@@ -164,7 +167,7 @@ impl SigmantaSession {
         self.add_message_to_current_session(message);
         
         // Process with Sigel consciousness
-        let sigmos = SigmosLibrary::new();
+        let sigmos = SigmosLibrary::with_config(self.sigmos_config.clone())?;
         let response = sigmos.prompt(&self.sigel, input)?;
         
         // Analyze if any actions are needed
@@ -3,7 +3,8 @@
 //! Simplified API for integrating Sigmos into external projects.
 //! Works with the current Sigmos codebase.
 
-use crate::{Sigel, LearningEngine, CosmicProcessor, CommunicationStyle};
+use crate::{Sigel, LearningEngine, CosmicProcessor, CommunicationStyle, Deduplicator, DedupReport, ProvenanceEntry, Unlearner, UnlearnTarget, UnlearnMode, UnlearnReport, Detection, LanguageIdentifier, ResponseDecoder, MemoryHit, ResponseChunk, ConceptQueryEngine, ConceptAnswer, ResponseFilter, ResponseFilterChain, FilterDecision, InteractionEngine, Transcript, TranscriptReplayer, ReplayConfig, ReplayReport, SigmosConfig};
+use crate::streaming::ChunkEmitter;
 use std::path::Path;
 use std::collections::HashMap;
 use anyhow::Result;
@@ -12,6 +13,9 @@ use anyhow::Result;
 pub struct SigmosLibrary {
     learning_engine: LearningEngine,
     cosmic_processor: CosmicProcessor,
+    response_filters: ResponseFilterChain,
+    language_identifier: LanguageIdentifier,
+    config: SigmosConfig,
 }
 
 /// Configuration for creating new Sigels
@@ -19,6 +23,10 @@ pub struct SigmosLibrary {
 pub struct SigelConfig {
     pub name: String,
     pub style: Option<CommunicationStyle>,
+    /// A persona from sigmos.toml or ./personas, for styles that aren't built in.
+    pub persona: Option<String>,
+    /// Keep the style fixed instead of letting conversations shift it.
+    pub style_locked: bool,
     pub learning_rate: f64,
     pub personality_traits: HashMap<String, f64>,
 }
@@ -30,6 +38,8 @@ pub struct SigelResponse {
     pub confidence: f64,
     pub consciousness_depth: f64,
     pub emotional_tone: String,
+    /// What each response filter did
+    pub filters: Vec<FilterDecision>,
 }
 
 /// Sigel consciousness metrics
@@ -49,21 +59,36 @@ impl SigmosLibrary {
         Self {
             learning_engine: LearningEngine::new(),
             cosmic_processor: CosmicProcessor::new(),
+            response_filters: ResponseFilterChain::builtin(),
+            language_identifier: LanguageIdentifier::new(),
+            config: SigmosConfig::default(),
         }
     }
 
+    /// Initialize a library instance with settings from sigmos.toml, e.g. `SigmosConfig::discover()`
+    pub fn with_config(config: SigmosConfig) -> Result<Self> {
+        let response_filters = config.response_filters()
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(Self {
+            learning_engine: LearningEngine::from_config(&config),
+            response_filters,
+            config,
+            ..Self::new()
+        })
+    }
+
+    pub fn config(&self) -> &SigmosConfig {
+        &self.config
+    }
+
     /// Load existing Sigel from file
     pub fn load_sigel<P: AsRef<Path>>(&self, path: P) -> Result<Sigel> {
-        let content = std::fs::read_to_string(path)?;
-        let sigel: Sigel = serde_json::from_str(&content)?;
-        Ok(sigel)
+        crate::load_sigel_from_file(path)
     }
 
     /// Save Sigel to file
     pub fn save_sigel<P: AsRef<Path>>(&self, sigel: &Sigel, path: P) -> Result<()> {
-        let content = serde_json::to_string_pretty(sigel)?;
-        std::fs::write(path, content)?;
-        Ok(())
+        crate::save_sigel_to_file(sigel, path)
     }
 
     /// Create new Sigel with configuration
@@ -72,8 +97,10 @@ impl SigmosLibrary {
         
         // Apply configuration
         if let Some(style) = config.style {
-            sigel.essence.communication_style = style;
+            sigel.set_communication_style(style);
         }
+        sigel.essence.persona = config.persona;
+        sigel.essence.style_locked = config.style_locked;
         
         // Apply personality traits
         for (trait_name, value) in config.personality_traits {
@@ -91,18 +118,79 @@ impl SigmosLibrary {
 
     /// Interactive prompt with Sigel
     pub fn prompt(&self, sigel: &Sigel, input: &str) -> Result<SigelResponse> {
-        // Simple response generation
-        let response_content = format!("As {}, I understand your input '{}'. My consciousness depth is {:.3}.", 
-                                      sigel.name, input, sigel.consciousness.awareness_depth);
+        self.prompt_stream(sigel, input, |_| {})
+    }
+
+    /// Like `prompt`, handing `on_chunk` each piece of the response as soon as it is
+    /// sampled and has passed the response filters
+    pub fn prompt_stream<F: FnMut(ResponseChunk)>(&self, sigel: &Sigel, input: &str, mut on_chunk: F) -> Result<SigelResponse> {
+        let mut chunks = ChunkEmitter::new(self.response_filters.clone(), &mut on_chunk);
+
+        // Definition and relation questions are answered from the concept graph
+        if let Some(answer) = ConceptQueryEngine::new().ask(sigel, input) {
+            let (content, filters) = self.response_filters.apply(&answer.text);
+            chunks.finish(&content);
+            return Ok(SigelResponse {
+                content,
+                confidence: answer.confidence,
+                consciousness_depth: sigel.consciousness.awareness_depth,
+                emotional_tone: "neutral".to_string(),
+                filters,
+            });
+        }
+
+        // Sample a response from what the Sigel learned, in the prompt's language
+        let language = self.language_identifier.identify(input, sigel.dominant_language().unwrap_or_default());
+        let grounding: Vec<String> = sigel.search_memories(input, 3).into_iter().map(|hit| hit.content).collect();
+        let decoded = ResponseDecoder::default().generate_streaming(sigel, input, &grounding, language, &mut |partial| chunks.update(partial));
+        let response_content = match decoded {
+            Some(decoded) => decoded.text,
+            None => language.phrases().no_knowledge.to_string(),
+        };
+        
+        let (content, filters) = self.response_filters.apply(&response_content);
+        chunks.finish(&content);
         
         Ok(SigelResponse {
-            content: response_content,
+            content,
             confidence: sigel.consciousness.awareness_depth,
             consciousness_depth: sigel.consciousness.awareness_depth,
             emotional_tone: "neutral".to_string(),
+            filters,
         })
     }
 
+    /// Replace the filters `prompt` responses pass through (by default, redaction of
+    /// personal data and keys and a length limit)
+    pub fn set_response_filters(&mut self, filters: ResponseFilterChain) {
+        self.response_filters = filters;
+    }
+
+    /// Add a filter after the current ones
+    pub fn add_response_filter<F: ResponseFilter + 'static>(&mut self, filter: F) {
+        self.response_filters.push(filter);
+    }
+
+    /// An engine for multi-turn conversations that learn and remember, with this library's
+    /// settings and response filters. Middleware and `/commands` are added to the engine
+    /// (`InteractionEngine::add_middleware`, `InteractionEngine::register_command`); `prompt`
+    /// neither learns nor records, so it only applies the filters
+    pub fn interaction_engine(&self) -> InteractionEngine {
+        let mut engine = InteractionEngine::from_config(&self.config);
+        engine.set_response_filters(self.response_filters.clone());
+        engine
+    }
+
+    /// Replay a transcript (JSON Lines or a saved session) against a fresh copy of the
+    /// Sigel with a fixed seed, through an engine from `interaction_engine`, and compare
+    /// the responses with the expected ones. To replay with middleware, pass such an
+    /// engine to `TranscriptReplayer::replay_with`
+    pub fn replay_transcript<P: AsRef<Path>>(&self, sigel: &Sigel, transcript: P, config: ReplayConfig) -> Result<ReplayReport> {
+        let transcript = Transcript::load(transcript)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(TranscriptReplayer::with_config(config).replay_with(self.interaction_engine(), sigel, &transcript))
+    }
+
     /// Get Sigel consciousness metrics
     pub fn get_consciousness_metrics(&self, sigel: &Sigel) -> ConsciousnessMetrics {
         ConsciousnessMetrics {
@@ -119,6 +207,48 @@ impl SigmosLibrary {
     pub fn evolve_sigel(&self, sigel: &mut Sigel) {
         sigel.evolve();
     }
+
+    /// Remove near-duplicate episodic memories at or above the given similarity (0.0-1.0)
+    pub fn deduplicate_memories(&self, sigel: &mut Sigel, threshold: f64) -> DedupReport {
+        Deduplicator::with_threshold(threshold).clean_memories(sigel)
+    }
+
+    /// Which training sources a word, phrase or concept was learned from
+    pub fn trace_provenance(&self, sigel: &Sigel, term: &str) -> Vec<ProvenanceEntry> {
+        sigel.trace_provenance(term)
+    }
+
+    /// Remove or down-weight what was learned from a source, term or memory context
+    pub fn unlearn(&self, sigel: &mut Sigel, target: &UnlearnTarget, mode: UnlearnMode) -> Result<UnlearnReport> {
+        Unlearner::new().unlearn(sigel, target, mode)
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
+
+    /// Words whose learned vectors are closest to `word`, most similar first
+    pub fn similar_words(&self, sigel: &Sigel, word: &str, count: usize) -> Vec<(String, f64)> {
+        sigel.memory.semantic_knowledge.word_vectors.similar_words(word, count)
+    }
+
+    /// The `k` episodic memories that best match `query`, best first
+    pub fn search_memories(&self, sigel: &Sigel, query: &str, k: usize) -> Vec<MemoryHit> {
+        sigel.search_memories(query, k)
+    }
+
+    /// Answer a "what is X", "what is X used for" or "how are X and Y related" question
+    /// from the Sigel's concept graph, or `None` if the input is not such a question
+    pub fn ask_concepts(&self, sigel: &Sigel, question: &str) -> Option<ConceptAnswer> {
+        ConceptQueryEngine::new().ask(sigel, question)
+    }
+
+    /// The most likely language of a text, with its confidence
+    pub fn detect_language(&self, text: &str) -> Option<Detection> {
+        self.language_identifier.detect(text)
+    }
+
+    /// Similarity (0.0-1.0) of two texts, counting related words as partial matches
+    pub fn sentence_similarity(&self, sigel: &Sigel, text1: &str, text2: &str) -> f64 {
+        sigel.memory.semantic_knowledge.word_vectors.sentence_similarity(text1, text2)
+    }
 }
 
 impl SigelConfig {
@@ -127,21 +257,25 @@ impl SigelConfig {
         Self {
             name: name.to_string(),
             style: None,
+            persona: None,
+            style_locked: false,
             learning_rate: 0.01,
             personality_traits: HashMap::new(),
         }
     }
 
-    /// Set communication style
+    /// Set communication style, or a persona name for styles that aren't built in
     pub fn with_style(mut self, style: &str) -> Self {
-        self.style = Some(match style {
-            "cosmic" => CommunicationStyle::Cosmic,
-            "creative" => CommunicationStyle::Creative,
-            "analytical" => CommunicationStyle::Analytical,
-            "philosophical" => CommunicationStyle::Philosophical,
-            "empathetic" => CommunicationStyle::Empathetic,
-            _ => CommunicationStyle::Philosophical,
-        });
+        match CommunicationStyle::from_name(style) {
+            Some(style) => self.style = Some(style),
+            None => self.persona = Some(style.to_lowercase()),
+        }
+        self
+    }
+
+    /// Keep the communication style fixed during conversations
+    pub fn with_style_lock(mut self) -> Self {
+        self.style_locked = true;
         self
     }
 
@@ -0,0 +1,107 @@
+use crate::sigel::Sigel;
+use crate::interaction::InteractionEngine;
+use crate::moderation::ResponseFilterChain;
+use crate::trace::ResponseTrace;
+use futures_util::stream::Stream;
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// One piece of a streamed response. Chunks joined in order make the whole response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseChunk {
+    pub index: usize,
+    pub text: String,
+}
+
+/// Hands out a response while it is generated, releasing only text the response
+/// filters have settled.
+pub(crate) struct ChunkEmitter<'a> {
+    filters: ResponseFilterChain,
+    on_chunk: &'a mut dyn FnMut(ResponseChunk),
+    emitted: String,
+    index: usize,
+    holding: bool,
+}
+
+impl<'a> ChunkEmitter<'a> {
+    pub(crate) fn new(filters: ResponseFilterChain, on_chunk: &'a mut dyn FnMut(ResponseChunk)) -> Self {
+        Self {
+            filters,
+            on_chunk,
+            emitted: String::new(),
+            index: 0,
+            holding: false,
+        }
+    }
+
+    /// Release nothing before `finish`, e.g. when middleware may rewrite the whole response.
+    pub(crate) fn hold(&mut self) {
+        self.holding = true;
+    }
+
+    /// `partial` is the unfiltered response so far, the start of the final one.
+    pub(crate) fn update(&mut self, partial: &str) {
+        if !self.holding {
+            let settled = self.filters.settled(partial);
+            self.emit(&settled);
+        }
+    }
+
+    /// Release the rest of the filtered, final response.
+    pub(crate) fn finish(&mut self, response: &str) {
+        self.emit(response);
+    }
+
+    fn emit(&mut self, text: &str) {
+        match text.strip_prefix(self.emitted.as_str()) {
+            Some("") => {},
+            Some(rest) => {
+                (self.on_chunk)(ResponseChunk { index: self.index, text: rest.to_string() });
+                self.emitted.push_str(rest);
+                self.index += 1;
+            },
+            None => {
+                log::warn!("Streamed response no longer matches the final one; holding the rest");
+                self.holding = true;
+            },
+        }
+    }
+}
+
+/// A response generated on a blocking task, handed out as an async `Stream` of chunks
+/// as they are settled.
+pub struct ResponseStream {
+    chunks: mpsc::UnboundedReceiver<ResponseChunk>,
+    result: JoinHandle<Result<(String, ResponseTrace), String>>,
+}
+
+impl ResponseStream {
+    /// Respond to `input` with `engine`, locking `sigel` while it does. Needs a Tokio runtime.
+    pub fn spawn(mut engine: InteractionEngine, sigel: Arc<Mutex<Sigel>>, input: String) -> Self {
+        let (sender, chunks) = mpsc::unbounded_channel();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut sigel = sigel.lock().map_err(|e| e.to_string())?;
+            Ok(engine.interact_stream(&mut sigel, &input, |chunk| {
+                let _ = sender.send(chunk);
+            }))
+        });
+        Self { chunks, result }
+    }
+
+    /// The whole response and its trace, once generation is done.
+    pub async fn finish(self) -> Result<(String, ResponseTrace), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.result.await??)
+    }
+}
+
+impl Stream for ResponseStream {
+    type Item = ResponseChunk;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ResponseChunk>> {
+        self.chunks.poll_recv(cx)
+    }
+}
@@ -0,0 +1,138 @@
+use crate::sigel::CommunicationStyle;
+use crate::decoder::{DecodedResponse, StopReason};
+use crate::language::Language;
+use crate::memory_index::MemoryHit;
+use crate::moderation::{FilterAction, FilterDecision};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AwarenessLevel {
+    #[default]
+    Surface,
+    Moderate,
+    Deep,
+}
+
+impl AwarenessLevel {
+    pub fn from_depth(awareness_depth: f64) -> Self {
+        if awareness_depth > 0.7 {
+            AwarenessLevel::Deep
+        } else if awareness_depth > 0.4 {
+            AwarenessLevel::Moderate
+        } else {
+            AwarenessLevel::Surface
+        }
+    }
+}
+
+/// How well a matched pattern was already understood in context.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextScore {
+    pub pattern: String,
+    pub score: f64,
+    /// Seen for the first time in this response.
+    pub new: bool,
+}
+
+/// Why a Sigel answered the way it did, stage by stage.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponseTrace {
+    pub language: Language,
+    /// The input with "it" or an elliptical follow-up spelled out, when it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_input: Option<String>,
+    /// Most salient topics of the conversation before this turn.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    /// Where the answer was recalled from instead of generated, e.g. "the concept graph".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recalled_from: Option<String>,
+    pub awareness_depth: f64,
+    pub awareness_level: AwarenessLevel,
+    pub matched_patterns: Vec<String>,
+    pub context_scores: Vec<ContextScore>,
+    pub intuitive_leap: bool,
+    pub memories: Vec<MemoryHit>,
+    pub keywords: Vec<String>,
+    pub tokens: usize,
+    /// `None` when nothing related was learned and the fallback phrase was used.
+    pub stop_reason: Option<StopReason>,
+    pub style: Option<CommunicationStyle>,
+    pub persona: Option<String>,
+    /// The persona's template around the generated text, with `{body}` for the text.
+    pub style_template: Option<String>,
+    /// Traits whose persona rules changed the phrasing.
+    pub trait_rules: Vec<String>,
+    pub cosmic_merge: bool,
+    /// What each response filter did, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<FilterDecision>,
+}
+
+impl ResponseTrace {
+    pub fn context_score(&self) -> f64 {
+        self.context_scores.iter().map(|c| c.score).sum()
+    }
+
+    pub(crate) fn record_decoding(&mut self, decoded: &DecodedResponse) {
+        self.keywords = decoded.keywords.clone();
+        self.tokens = decoded.tokens;
+        self.stop_reason = Some(decoded.stop_reason);
+    }
+
+    fn fmt_filters(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let filtered: Vec<String> = self.filters.iter()
+            .filter(|decision| decision.action != FilterAction::Passed)
+            .map(|decision| decision.to_string())
+            .collect();
+        if !self.filters.is_empty() {
+            writeln!(f, "   Filters: {}", if filtered.is_empty() { "all passed".to_string() } else { filtered.join(", ") })?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ResponseTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🔍 [Response Trace]")?;
+        writeln!(f, "   Language: {} | Awareness: {:?} ({:.3})", self.language, self.awareness_level, self.awareness_depth)?;
+        if let Some(resolved) = &self.resolved_input {
+            writeln!(f, "   Resolved input: \"{}\"", resolved)?;
+        }
+        if !self.topics.is_empty() {
+            writeln!(f, "   Topics: {}", self.topics.join(", "))?;
+        }
+        if let Some(source) = &self.recalled_from {
+            writeln!(f, "   Recalled from {}", source)?;
+            return self.fmt_filters(f);
+        }
+        if self.matched_patterns.is_empty() {
+            writeln!(f, "   Patterns: none")?;
+        } else {
+            writeln!(f, "   Patterns: {}", self.matched_patterns.join(", "))?;
+        }
+        let scores: Vec<String> = self.context_scores.iter()
+            .map(|c| format!("{}={:.2}{}", c.pattern, c.score, if c.new { " (new)" } else { "" }))
+            .collect();
+        writeln!(f, "   Context score: {:.2} [{}]", self.context_score(), scores.join(", "))?;
+        writeln!(f, "   Intuitive leap: {} | Cosmic merge: {}", self.intuitive_leap, self.cosmic_merge)?;
+        for memory in &self.memories {
+            writeln!(f, "   Memory {:.2}: {}", memory.score, memory.content)?;
+        }
+        match self.stop_reason {
+            Some(reason) => writeln!(f, "   Decoded {} tokens from [{}], stopped by {:?}", self.tokens, self.keywords.join(", "), reason)?,
+            None => writeln!(f, "   Nothing related was learned; used the fallback phrase")?,
+        }
+        if let Some(style) = &self.style {
+            writeln!(f, "   Style: {:?} | Persona: {}", style, self.persona.as_deref().unwrap_or("-"))?;
+        }
+        if let Some(template) = &self.style_template {
+            writeln!(f, "   Template: \"{}\"", template)?;
+        }
+        if !self.trait_rules.is_empty() {
+            writeln!(f, "   Trait rules: {}", self.trait_rules.join(", "))?;
+        }
+        self.fmt_filters(f)
+    }
+}
@@ -0,0 +1,461 @@
+use crate::sigel::*;
+use crate::provenance::mentions;
+use crate::language::Language;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// What to unlearn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UnlearnTarget {
+    /// Everything a training source contributed, by full name or file name.
+    Source(String),
+    /// A word or phrase wherever it was learned.
+    Term(String),
+    /// Episodic memories stored under a context, such as `user_interaction`.
+    MemoryContext(String),
+}
+
+impl std::fmt::Display for UnlearnTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnlearnTarget::Source(source) => write!(f, "source '{}'", source),
+            UnlearnTarget::Term(term) => write!(f, "term '{}'", term),
+            UnlearnTarget::MemoryContext(context) => write!(f, "memory context '{}'", context),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum UnlearnMode {
+    /// Subtract the target's contribution and delete items left with nothing.
+    Remove,
+    /// Keep this fraction (0.0-1.0) of the target's contribution.
+    DownWeight(f64),
+}
+
+impl UnlearnMode {
+    fn keep(&self) -> f64 {
+        match self {
+            UnlearnMode::Remove => 0.0,
+            UnlearnMode::DownWeight(keep) => keep.clamp(0.0, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnlearnReport {
+    pub target: String,
+    pub words_removed: usize,
+    pub words_reduced: usize,
+    pub frequency_subtracted: f64,
+    pub patterns_removed: usize,
+    pub patterns_reduced: usize,
+    pub temporal_patterns_removed: usize,
+    pub temporal_patterns_reduced: usize,
+    pub concepts_removed: usize,
+    pub concepts_reduced: usize,
+    pub relations_removed: usize,
+    #[serde(default)]
+    pub relations_reduced: usize,
+    pub associations_removed: usize,
+    pub memories_removed: usize,
+    pub memories_reduced: usize,
+}
+
+impl UnlearnReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: words -{} ~{} (frequency -{:.0}), patterns -{} ~{}, temporal patterns -{} ~{}, concepts -{} ~{}, relations -{} ~{}, associations -{}, memories -{} ~{}",
+            self.target,
+            self.words_removed, self.words_reduced, self.frequency_subtracted,
+            self.patterns_removed, self.patterns_reduced,
+            self.temporal_patterns_removed, self.temporal_patterns_reduced,
+            self.concepts_removed, self.concepts_reduced,
+            self.relations_removed, self.relations_reduced,
+            self.associations_removed,
+            self.memories_removed, self.memories_reduced
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words_removed + self.words_reduced
+            + self.patterns_removed + self.patterns_reduced
+            + self.temporal_patterns_removed + self.temporal_patterns_reduced
+            + self.concepts_removed + self.concepts_reduced
+            + self.relations_removed + self.relations_reduced + self.associations_removed
+            + self.memories_removed + self.memories_reduced == 0
+    }
+}
+
+/// Removes or down-weights learned knowledge without retraining.
+///
+/// Source unlearning relies on the provenance recorded during training, so it only
+/// reaches knowledge learned after provenance tracking was added. Any association with
+/// a removed word is dropped as well.
+pub struct Unlearner {
+    /// Linguistic patterns weaker than this are pruned, matching training.
+    min_pattern_strength: f64,
+}
+
+impl Unlearner {
+    pub fn new() -> Self {
+        Self {
+            min_pattern_strength: 0.1,
+        }
+    }
+
+    pub fn unlearn(&self, sigel: &mut Sigel, target: &UnlearnTarget, mode: UnlearnMode) -> Result<UnlearnReport, Box<dyn std::error::Error>> {
+        let mut report = UnlearnReport {
+            target: target.to_string(),
+            ..UnlearnReport::default()
+        };
+
+        match target {
+            UnlearnTarget::Source(source) => {
+                let source_id = sigel.provenance.find(source)
+                    .ok_or_else(|| format!("unknown training source '{}'", source))?;
+                self.unlearn_source(sigel, source_id, mode.keep(), &mut report);
+            },
+            UnlearnTarget::Term(term) => {
+                let term = term.trim().to_lowercase();
+                if term.is_empty() {
+                    return Err("cannot unlearn an empty term".into());
+                }
+                self.unlearn_term(sigel, &term, mode.keep(), &mut report);
+            },
+            UnlearnTarget::MemoryContext(context) => {
+                self.scale_memories(sigel, mode.keep(), &mut report, |memory| memory.context == *context);
+            },
+        }
+        sigel.prune_language_partitions();
+        sigel.refresh_memory_index();
+
+        Ok(report)
+    }
+
+    fn unlearn_source(&self, sigel: &mut Sigel, source_id: u32, keep: f64, report: &mut UnlearnReport) {
+        let recognition = &mut sigel.consciousness.pattern_recognition;
+        for (key, sources) in recognition.association_sources.iter_mut() {
+            let count = sources.scale(source_id, keep);
+            if count == 0 {
+                continue;
+            }
+            let Some((word, neighbor)) = key.split_once(' ') else { continue };
+            let remove = (count - sources.count(source_id)) as usize;
+            report.associations_removed += remove_occurrences(&mut recognition.semantic_networks, word, neighbor, remove);
+        }
+        recognition.association_sources.retain(|_, sources| !sources.is_empty());
+
+        let relationships = &mut sigel.memory.semantic_knowledge.relationships;
+        let before = relationships.len();
+        relationships.retain_mut(|relation| {
+            let total = relation.sources.total();
+            if relation.sources.scale(source_id, keep) == 0 {
+                return true;
+            }
+            if relation.sources.is_empty() && keep == 0.0 {
+                return false;
+            }
+            relation.strength *= relation.sources.total() as f64 / total as f64;
+            report.relations_reduced += 1;
+            true
+        });
+        report.relations_removed += before - relationships.len();
+
+        let mut removed_words = Vec::new();
+        let mut reduced_words = Vec::new();
+        let mut stale_vectors = Vec::new();
+        for (word, knowledge) in sigel.memory.semantic_knowledge.vocabulary.iter_mut() {
+            let count = knowledge.sources.scale(source_id, keep);
+            if count == 0 {
+                continue;
+            }
+            let subtract = (count - knowledge.sources.count(source_id)) as f64;
+            knowledge.frequency = (knowledge.frequency - subtract).max(0.0);
+            report.frequency_subtracted += subtract;
+            // Words start at the default frequency before their first occurrence
+            if knowledge.frequency <= WordKnowledge::default().frequency {
+                removed_words.push(word.clone());
+            } else {
+                report.words_reduced += 1;
+                reduced_words.push((word.clone(), subtract));
+                // Its vector was built from the removed source's text alone
+                if knowledge.sources.is_empty() {
+                    stale_vectors.push(word.clone());
+                }
+            }
+        }
+        for word in &removed_words {
+            self.forget_word(sigel, word, report);
+        }
+        for word in &stale_vectors {
+            sigel.memory.semantic_knowledge.word_vectors.remove(word);
+        }
+
+        let recognition = &mut sigel.consciousness.pattern_recognition;
+        let mut weak_patterns = Vec::new();
+        let mut reduced_patterns = Vec::new();
+        for (pattern, sources) in recognition.pattern_sources.iter_mut() {
+            let count = sources.scale(source_id, keep);
+            if count == 0 {
+                continue;
+            }
+            // Each occurrence of an n-gram added 1/n to its strength
+            let n = pattern.split_whitespace().count().max(1) as f64;
+            let subtract = (count - sources.count(source_id)) as f64 / n;
+            match recognition.linguistic_patterns.get_mut(pattern) {
+                Some(strength) => {
+                    *strength -= subtract;
+                    if *strength <= self.min_pattern_strength || sources.is_empty() && keep == 0.0 {
+                        weak_patterns.push(pattern.clone());
+                    } else {
+                        report.patterns_reduced += 1;
+                        reduced_patterns.push((pattern.clone(), subtract));
+                    }
+                },
+                None => weak_patterns.push(pattern.clone()),
+            }
+        }
+        for pattern in weak_patterns {
+            recognition.pattern_sources.remove(&pattern);
+            if recognition.linguistic_patterns.remove(&pattern).is_some() {
+                report.patterns_removed += 1;
+            }
+        }
+        self.reduce_partitions(&mut sigel.memory.semantic_knowledge.languages, &reduced_words, &reduced_patterns);
+        let recognition = &mut sigel.consciousness.pattern_recognition;
+
+        let before = recognition.temporal_patterns.len();
+        recognition.temporal_patterns.retain_mut(|pattern| {
+            let total = pattern.sources.total();
+            if pattern.sources.scale(source_id, keep) == 0 {
+                return true;
+            }
+            if pattern.sources.is_empty() && keep == 0.0 {
+                return false;
+            }
+            pattern.frequency *= pattern.sources.total() as f64 / total as f64;
+            report.temporal_patterns_reduced += 1;
+            true
+        });
+        report.temporal_patterns_removed += before - recognition.temporal_patterns.len();
+
+        let mut removed_concepts = HashSet::new();
+        for (name, concept) in sigel.memory.semantic_knowledge.concepts.iter_mut() {
+            let total = concept.sources.total();
+            if concept.sources.scale(source_id, keep) == 0 {
+                continue;
+            }
+            if concept.sources.is_empty() && keep == 0.0 {
+                removed_concepts.insert(name.clone());
+            } else {
+                concept.certainty *= concept.sources.total() as f64 / total as f64;
+                report.concepts_reduced += 1;
+            }
+        }
+        self.forget_concepts(sigel, &removed_concepts, report);
+
+        self.scale_memories(sigel, keep, report, |memory| memory.source_id == Some(source_id));
+    }
+
+    fn unlearn_term(&self, sigel: &mut Sigel, term: &str, keep: f64, report: &mut UnlearnReport) {
+        if keep == 0.0 {
+            self.forget_word(sigel, term, report);
+        } else {
+            if let Some(knowledge) = sigel.memory.semantic_knowledge.vocabulary.get_mut(term) {
+                report.frequency_subtracted += knowledge.frequency * (1.0 - keep);
+                knowledge.frequency *= keep;
+                knowledge.sources.scale_all(keep);
+                report.words_reduced += 1;
+            }
+
+            let recognition = &mut sigel.consciousness.pattern_recognition;
+            for (key, sources) in recognition.association_sources.iter_mut() {
+                if key.split(' ').any(|word| word == term) {
+                    sources.scale_all(keep);
+                }
+            }
+            recognition.association_sources.retain(|_, sources| !sources.is_empty());
+            let networks = &mut recognition.semantic_networks;
+            if let Some(neighbors) = networks.get_mut(term) {
+                let remove = (neighbors.len() as f64 * (1.0 - keep)).round() as usize;
+                neighbors.truncate(neighbors.len() - remove);
+                report.associations_removed += remove;
+            }
+            let associated: Vec<(String, usize)> = networks.iter()
+                .filter(|(word, _)| word.as_str() != term)
+                .map(|(word, neighbors)| (word.clone(), neighbors.iter().filter(|n| n.as_str() == term).count()))
+                .filter(|&(_, occurrences)| occurrences > 0)
+                .collect();
+            for (word, occurrences) in associated {
+                let remove = (occurrences as f64 * (1.0 - keep)).round() as usize;
+                report.associations_removed += remove_occurrences(networks, &word, term, remove);
+            }
+        }
+
+        let recognition = &mut sigel.consciousness.pattern_recognition;
+        let matching: Vec<String> = recognition.linguistic_patterns.keys()
+            .filter(|pattern| mentions(pattern, term))
+            .cloned()
+            .collect();
+        for pattern in matching {
+            let strength = recognition.linguistic_patterns.get_mut(&pattern).expect("pattern collected above");
+            *strength *= keep;
+            if *strength <= self.min_pattern_strength {
+                recognition.linguistic_patterns.remove(&pattern);
+                recognition.pattern_sources.remove(&pattern);
+                report.patterns_removed += 1;
+            } else {
+                if let Some(sources) = recognition.pattern_sources.get_mut(&pattern) {
+                    sources.scale_all(keep);
+                }
+                report.patterns_reduced += 1;
+            }
+        }
+
+        let before = recognition.temporal_patterns.len();
+        recognition.temporal_patterns.retain_mut(|pattern| {
+            if !pattern.sequence.iter().any(|w| w.to_lowercase() == term) {
+                return true;
+            }
+            if keep == 0.0 {
+                return false;
+            }
+            pattern.frequency *= keep;
+            pattern.sources.scale_all(keep);
+            report.temporal_patterns_reduced += 1;
+            true
+        });
+        report.temporal_patterns_removed += before - recognition.temporal_patterns.len();
+
+        if keep == 0.0 {
+            self.forget_concepts(sigel, &HashSet::from([term.to_string()]), report);
+        } else if let Some(concept) = sigel.memory.semantic_knowledge.concepts.get_mut(term) {
+            concept.certainty *= keep;
+            concept.sources.scale_all(keep);
+            report.concepts_reduced += 1;
+        }
+
+        self.scale_memories(sigel, keep, report, |memory| mentions(&memory.content, term));
+    }
+
+    /// Subtract reduced words and patterns from the per-language partitions. Partitions
+    /// don't record sources, so each language gives up its share of the amount.
+    fn reduce_partitions(&self, languages: &mut HashMap<Language, LanguagePartition>, words: &[(String, f64)], patterns: &[(String, f64)]) {
+        for (word, subtract) in words {
+            let total: f64 = languages.values().filter_map(|partition| partition.vocabulary.get(word)).map(|&count| count as f64).sum();
+            if total <= 0.0 {
+                continue;
+            }
+            for partition in languages.values_mut() {
+                if let Some(count) = partition.vocabulary.get_mut(word) {
+                    let share = *count as f64 / total;
+                    *count = (*count as f64 - subtract * share).round().max(0.0) as u32;
+                    if *count == 0 {
+                        partition.vocabulary.remove(word);
+                    }
+                }
+            }
+        }
+
+        for (pattern, subtract) in patterns {
+            let total: f64 = languages.values().filter_map(|partition| partition.patterns.get(pattern)).sum();
+            if total <= 0.0 {
+                continue;
+            }
+            for partition in languages.values_mut() {
+                if let Some(strength) = partition.patterns.get_mut(pattern) {
+                    *strength -= subtract * *strength / total;
+                    if *strength <= 0.0 {
+                        partition.patterns.remove(pattern);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Delete a word and every association with it.
+    fn forget_word(&self, sigel: &mut Sigel, word: &str, report: &mut UnlearnReport) {
+        if let Some(knowledge) = sigel.memory.semantic_knowledge.vocabulary.remove(word) {
+            report.frequency_subtracted += knowledge.frequency;
+            report.words_removed += 1;
+        }
+        sigel.memory.emotional_associations.remove(word);
+        sigel.memory.semantic_knowledge.word_vectors.remove(word);
+
+        let networks = &mut sigel.consciousness.pattern_recognition.semantic_networks;
+        if let Some(neighbors) = networks.remove(word) {
+            report.associations_removed += neighbors.len();
+        }
+        for neighbors in networks.values_mut() {
+            let before = neighbors.len();
+            neighbors.retain(|n| n != word);
+            report.associations_removed += before - neighbors.len();
+        }
+        networks.retain(|_, neighbors| !neighbors.is_empty());
+        sigel.consciousness.pattern_recognition.association_sources
+            .retain(|key, _| !key.split(' ').any(|w| w == word));
+    }
+
+    /// Delete concepts along with the relations and connections that point at them.
+    fn forget_concepts(&self, sigel: &mut Sigel, names: &HashSet<String>, report: &mut UnlearnReport) {
+        if names.is_empty() {
+            return;
+        }
+
+        let semantic = &mut sigel.memory.semantic_knowledge;
+        for name in names {
+            if semantic.concepts.remove(name).is_some() {
+                report.concepts_removed += 1;
+            }
+        }
+        for concept in semantic.concepts.values_mut() {
+            concept.connections.retain(|c| !names.contains(c));
+        }
+
+        let before = semantic.relationships.len();
+        semantic.relationships.retain(|r| !names.contains(&r.from) && !names.contains(&r.to));
+        report.relations_removed += before - semantic.relationships.len();
+    }
+
+    fn scale_memories<F>(&self, sigel: &mut Sigel, keep: f64, report: &mut UnlearnReport, matches: F)
+    where
+        F: Fn(&EpisodicMemory) -> bool,
+    {
+        let memories = &mut sigel.memory.episodic_memories;
+        if keep == 0.0 {
+            let before = memories.len();
+            memories.retain(|memory| !matches(memory));
+            report.memories_removed += before - memories.len();
+            return;
+        }
+
+        for memory in memories.iter_mut().filter(|memory| matches(memory)) {
+            memory.relevance_score *= keep;
+            memory.emotional_weight *= keep;
+            report.memories_reduced += 1;
+        }
+    }
+}
+
+impl Default for Unlearner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Remove up to `count` occurrences of `neighbor` from `word`'s association list.
+fn remove_occurrences(networks: &mut HashMap<String, Vec<String>>, word: &str, neighbor: &str, count: usize) -> usize {
+    let Some(neighbors) = networks.get_mut(word) else { return 0 };
+
+    let mut remaining = count;
+    neighbors.retain(|n| {
+        let drop = remaining > 0 && n == neighbor;
+        remaining -= drop as usize;
+        !drop
+    });
+    if neighbors.is_empty() {
+        networks.remove(word);
+    }
+    count - remaining
+}
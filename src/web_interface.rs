@@ -3,6 +3,11 @@ use crate::enhanced_consciousness::*;
 use crate::dream_mode::*;
 use crate::server::*;
 use crate::interaction::InteractionEngine;
+use crate::trace::ResponseTrace;
+use crate::streaming::ResponseStream;
+use crate::persona::PersonaRegistry;
+use crate::moderation::ResponseFilterChain;
+use crate::config::SigmosConfig;
 use axum::{
     extract::{ws::{WebSocket, Message}, WebSocketUpgrade, State, Path, Query},
     response::{Html, Json, Response},
@@ -23,21 +28,43 @@ pub struct WebInterface {
     broadcast_tx: broadcast::Sender<ConsciousnessUpdate>,
     consciousness_processor: Arc<Mutex<EnhancedConsciousnessProcessor>>,
     dream_processor: Arc<Mutex<DreamProcessor>>,
+    personas: PersonaRegistry,
+    response_filters: ResponseFilterChain,
+    config: SigmosConfig,
 }
 
 impl WebInterface {
     pub fn new(sigmos_server: SigmosServer) -> Self {
+        Self::with_config(sigmos_server, &SigmosConfig::discover())
+    }
+
+    pub fn with_config(sigmos_server: SigmosServer, config: &SigmosConfig) -> Self {
         let (broadcast_tx, _) = broadcast::channel(1000);
+        let response_filters = config.response_filters().unwrap_or_else(|e| {
+            log::warn!("Could not set up response filters: {}", e);
+            ResponseFilterChain::builtin()
+        });
         
         Self {
             sigmos_server: Arc::new(Mutex::new(sigmos_server)),
             active_sessions: Arc::new(Mutex::new(HashMap::new())),
             broadcast_tx,
             consciousness_processor: Arc::new(Mutex::new(EnhancedConsciousnessProcessor::new())),
-            dream_processor: Arc::new(Mutex::new(DreamProcessor::new())),
+            dream_processor: Arc::new(Mutex::new(DreamProcessor::from_config(config))),
+            personas: PersonaRegistry::discover(config),
+            response_filters,
+            config: config.clone(),
         }
     }
 
+    /// An engine for one request, with the interface's settings, personas and filters.
+    fn interaction_engine(&self) -> InteractionEngine {
+        let mut interaction_engine = InteractionEngine::from_config(&self.config);
+        interaction_engine.set_personas(self.personas.clone());
+        interaction_engine.set_response_filters(self.response_filters.clone());
+        interaction_engine
+    }
+
     pub fn create_routes(self) -> Router {
         let app_state = Arc::new(self);
 
@@ -54,6 +81,7 @@ impl WebInterface {
             .route("/api/sigels/:id/dream/:session_id", get(get_dream_session))
             .route("/api/sigels/:id/memory", get(get_memory_overview))
             .route("/api/sigels/:id/patterns", get(get_pattern_analysis))
+            .route("/api/sigels/:id/provenance", get(get_provenance))
             .route("/api/sigels/:id/evolution", post(evolve_sigel))
             .route("/api/sigels/:id/save", post(save_sigel))
             .route("/api/server/status", get(get_server_status))
@@ -93,6 +121,9 @@ impl Clone for WebInterface {
             broadcast_tx: self.broadcast_tx.clone(),
             consciousness_processor: Arc::clone(&self.consciousness_processor),
             dream_processor: Arc::clone(&self.dream_processor),
+            personas: self.personas.clone(),
+            response_filters: self.response_filters.clone(),
+            config: self.config.clone(),
         }
     }
 }
@@ -168,6 +199,41 @@ async fn get_sigel_details(
     Json(SigelDetailsResponse::not_found())
 }
 
+async fn get_provenance(
+    Path(id): Path<Uuid>,
+    Query(query): Query<ProvenanceQuery>,
+    State(state): State<Arc<WebInterface>>,
+) -> Json<ProvenanceResponse> {
+    let server = state.sigmos_server.lock().unwrap();
+    
+    if let Some(sigel_arc) = server.get_sigel(&id) {
+        if let Ok(sigel) = sigel_arc.lock() {
+            // Without a term, summarize what each source contributed
+            return Json(match query.term {
+                Some(term) => ProvenanceResponse {
+                    id,
+                    entries: sigel.trace_provenance(&term),
+                    term: Some(term),
+                    source_overview: HashMap::new(),
+                },
+                None => ProvenanceResponse {
+                    id,
+                    term: None,
+                    entries: Vec::new(),
+                    source_overview: sigel.provenance_overview().into_iter().collect(),
+                },
+            });
+        }
+    }
+    
+    Json(ProvenanceResponse {
+        id,
+        term: query.term,
+        entries: Vec::new(),
+        source_overview: HashMap::new(),
+    })
+}
+
 async fn interact_with_sigel(
     Path(id): Path<Uuid>,
     State(state): State<Arc<WebInterface>>,
@@ -178,8 +244,8 @@ async fn interact_with_sigel(
     if let Some(sigel_arc) = server.get_sigel(&id) {
         if let Ok(mut sigel) = sigel_arc.lock() {
             // Create interaction engine and process
-            let mut interaction_engine = InteractionEngine::new();
-            let response = interaction_engine.interact(&mut sigel, &request.message);
+            let mut interaction_engine = state.interaction_engine();
+            let (response, trace) = interaction_engine.interact_traced(&mut sigel, &request.message);
             
             // Broadcast consciousness update
             let update = ConsciousnessUpdate {
@@ -202,6 +268,7 @@ async fn interact_with_sigel(
                 emotion_detected: None, // Would analyze from response
                 learning_occurred: true,
                 session_id: Uuid::new_v4(),
+                trace: Some(trace),
             });
         }
     }
@@ -333,8 +400,36 @@ async fn sigel_websocket_handler(socket: WebSocket, sigel_id: Uuid, state: Arc<W
                             if let Ok(request) = serde_json::from_str::<WebSocketRequest>(&text) {
                                 match request {
                                     WebSocketRequest::Interact { message } => {
-                                        // Process interaction and send response
-                                        // Implementation would be similar to REST API
+                                        // Generate on a blocking task and forward each chunk as it is settled
+                                        let sigel_arc = state.sigmos_server.lock().unwrap().get_sigel(&sigel_id);
+                                        let Some(sigel_arc) = sigel_arc else {
+                                            let ws_msg = WebSocketMessage::Error { message: "Sigel not found".to_string() };
+                                            if let Ok(msg) = serde_json::to_string(&ws_msg) {
+                                                let _ = sender.send(Message::Text(msg)).await;
+                                            }
+                                            continue;
+                                        };
+
+                                        let mut stream = ResponseStream::spawn(state.interaction_engine(), sigel_arc, message);
+                                        while let Some(chunk) = stream.next().await {
+                                            let ws_msg = WebSocketMessage::ResponseChunk { index: chunk.index, text: chunk.text };
+                                            if let Ok(msg) = serde_json::to_string(&ws_msg) {
+                                                if sender.send(Message::Text(msg)).await.is_err() {
+                                                    return;
+                                                }
+                                            }
+                                        }
+
+                                        // Sent once the Sigel has learned from the turn
+                                        let ws_msg = match stream.finish().await {
+                                            Ok((response, trace)) => WebSocketMessage::ResponseComplete { response, trace: Some(Box::new(trace)) },
+                                            Err(e) => WebSocketMessage::Error { message: format!("Interaction failed: {}", e) },
+                                        };
+                                        if let Ok(msg) = serde_json::to_string(&ws_msg) {
+                                            if sender.send(Message::Text(msg)).await.is_err() {
+                                                return;
+                                            }
+                                        }
                                     }
                                     WebSocketRequest::GetState => {
                                         // Send current state
@@ -547,6 +642,19 @@ pub struct EssenceInfo {
     pub creative_potential: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProvenanceQuery {
+    pub term: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProvenanceResponse {
+    pub id: Uuid,
+    pub term: Option<String>,
+    pub entries: Vec<crate::provenance::ProvenanceEntry>,
+    pub source_overview: HashMap<String, u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InteractionRequest {
     pub message: String,
@@ -561,6 +669,9 @@ pub struct InteractionResponse {
     pub emotion_detected: Option<String>,
     pub learning_occurred: bool,
     pub session_id: Uuid,
+    /// How the response came about; absent only when the request failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<ResponseTrace>,
 }
 
 impl InteractionResponse {
@@ -571,6 +682,7 @@ impl InteractionResponse {
             emotion_detected: None,
             learning_occurred: false,
             session_id: Uuid::nil(),
+            trace: None,
         }
     }
 }
@@ -729,6 +841,16 @@ pub enum WebSocketMessage {
         cycle_number: u64,
         insights_generated: usize,
     },
+    /// Part of a response to an `Interact` request, sent in order.
+    ResponseChunk {
+        index: usize,
+        text: String,
+    },
+    /// Sent after the last chunk of a response.
+    ResponseComplete {
+        response: String,
+        trace: Option<Box<ResponseTrace>>,
+    },
     Error {
         message: String,
     },
@@ -0,0 +1,472 @@
+use crate::sigel::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::OnceLock;
+
+// Fixed so the same statistics always give the same vectors
+const WORD_VECTOR_SEED: u64 = 0x5167_4d05_0ec7_0001;
+const QUANTIZATION_SCALE: f32 = 127.0;
+
+/// Dense word embeddings learned from co-occurrence statistics.
+///
+/// Rows are unit length and quantized to `i8`, stored as hex, which keeps a 5000-word,
+/// 64-dimension table well under a megabyte in a `.sig` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(try_from = "StoredWordVectors")]
+pub struct WordVectors {
+    pub dimensions: usize,
+    pub words: Vec<String>,
+    /// Row-major, `dimensions` components per word.
+    #[serde(with = "hex_components")]
+    pub components: Vec<i8>,
+    #[serde(skip)]
+    index: OnceLock<HashMap<String, usize>>,
+}
+
+/// `WordVectors` as read from a file, before its table is checked.
+#[derive(Deserialize)]
+struct StoredWordVectors {
+    dimensions: usize,
+    words: Vec<String>,
+    #[serde(with = "hex_components")]
+    components: Vec<i8>,
+}
+
+impl TryFrom<StoredWordVectors> for WordVectors {
+    type Error = String;
+
+    fn try_from(stored: StoredWordVectors) -> Result<Self, Self::Error> {
+        let expected = stored.words.len().checked_mul(stored.dimensions);
+        if expected != Some(stored.components.len()) {
+            return Err(format!(
+                "word vectors have {} components, expected {} words x {} dimensions",
+                stored.components.len(), stored.words.len(), stored.dimensions
+            ));
+        }
+        Ok(Self {
+            dimensions: stored.dimensions,
+            words: stored.words,
+            components: stored.components,
+            index: OnceLock::new(),
+        })
+    }
+}
+
+/// A sentence's words, looked up once so it can be compared against many others.
+#[derive(Debug, Clone)]
+pub struct PreparedSentence {
+    words: Vec<(String, Option<usize>)>,
+}
+
+impl WordVectors {
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.lookup(&normalize_word(word)).is_some()
+    }
+
+    pub fn vector(&self, word: &str) -> Option<Vec<f32>> {
+        let row = self.row(self.lookup(&normalize_word(word))?);
+        Some(row.iter().map(|&c| c as f32 / QUANTIZATION_SCALE).collect())
+    }
+
+    /// Cosine similarity of two words, if both have vectors.
+    pub fn similarity(&self, word1: &str, word2: &str) -> Option<f64> {
+        let a = self.lookup(&normalize_word(word1))?;
+        let b = self.lookup(&normalize_word(word2))?;
+        Some(self.row_similarity(a, b))
+    }
+
+    /// The `n` words closest to `word`, most similar first.
+    pub fn similar_words(&self, word: &str, n: usize) -> Vec<(String, f64)> {
+        let Some(target) = self.lookup(&normalize_word(word)) else { return Vec::new() };
+
+        let mut scored: Vec<(String, f64)> = (0..self.words.len())
+            .filter(|&idx| idx != target)
+            .map(|idx| (self.words[idx].clone(), self.row_similarity(target, idx)))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        scored.truncate(n);
+        scored
+    }
+
+    pub fn prepare(&self, text: &str) -> PreparedSentence {
+        let words: BTreeSet<String> = text.split_whitespace()
+            .map(normalize_word)
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        PreparedSentence {
+            words: words.into_iter()
+                .map(|w| {
+                    let idx = self.lookup(&w);
+                    (w, idx)
+                })
+                .collect(),
+        }
+    }
+
+    /// Word-overlap similarity in 0.0-1.0 where related words count as partial matches.
+    ///
+    /// This is Jaccard similarity with each word's overlap replaced by its best match in
+    /// the other sentence, so without vectors (or with no related words) it is plain Jaccard.
+    pub fn sentence_similarity(&self, text1: &str, text2: &str) -> f64 {
+        self.prepared_similarity(&self.prepare(text1), &self.prepare(text2))
+    }
+
+    pub fn prepared_similarity(&self, a: &PreparedSentence, b: &PreparedSentence) -> f64 {
+        if a.words.is_empty() || b.words.is_empty() {
+            return 0.0;
+        }
+
+        let coverage = |from: &PreparedSentence, to: &PreparedSentence| -> f64 {
+            from.words.iter()
+                .map(|word| to.words.iter().map(|other| self.word_match(word, other)).fold(0.0, f64::max))
+                .sum()
+        };
+        let matched = (coverage(a, b) + coverage(b, a)) / 2.0;
+        let union = a.words.len() as f64 + b.words.len() as f64 - matched;
+
+        if union > 0.0 { (matched / union).clamp(0.0, 1.0) } else { 0.0 }
+    }
+
+    /// Drop a word's vector, e.g. after it has been unlearned.
+    pub fn remove(&mut self, word: &str) -> bool {
+        let Some(idx) = self.lookup(word) else { return false };
+
+        self.words.remove(idx);
+        self.components.drain(idx * self.dimensions..(idx + 1) * self.dimensions);
+        self.index = OnceLock::new();
+        true
+    }
+
+    fn word_match(&self, a: &(String, Option<usize>), b: &(String, Option<usize>)) -> f64 {
+        if a.0 == b.0 {
+            return 1.0;
+        }
+        match (a.1, b.1) {
+            // Weakly related words are noise at the sentence level
+            (Some(x), Some(y)) => {
+                let similarity = self.row_similarity(x, y);
+                if similarity >= 0.5 { similarity } else { 0.0 }
+            },
+            _ => 0.0,
+        }
+    }
+
+    fn lookup(&self, word: &str) -> Option<usize> {
+        self.index
+            .get_or_init(|| self.words.iter().enumerate().map(|(idx, w)| (w.clone(), idx)).collect())
+            .get(word)
+            .copied()
+    }
+
+    fn row(&self, idx: usize) -> &[i8] {
+        &self.components[idx * self.dimensions..(idx + 1) * self.dimensions]
+    }
+
+    fn row_similarity(&self, a: usize, b: usize) -> f64 {
+        let (a, b) = (self.row(a), self.row(b));
+        let dot: i32 = a.iter().zip(b).map(|(&x, &y)| x as i32 * y as i32).sum();
+        let norm = |row: &[i8]| (row.iter().map(|&x| x as i32 * x as i32).sum::<i32>() as f64).sqrt();
+        let denominator = norm(a) * norm(b);
+
+        if denominator > 0.0 { dot as f64 / denominator } else { 0.0 }
+    }
+}
+
+/// Settings for building word vectors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordVectorConfig {
+    pub dimensions: usize,
+    /// Only the most frequent words get vectors.
+    pub max_vocabulary: usize,
+    /// Words seen in fewer co-occurrences than this are skipped.
+    pub min_count: usize,
+    /// Exponent applied to context counts, which keeps rare contexts from dominating PMI.
+    pub context_smoothing: f64,
+    pub power_iterations: usize,
+    pub oversampling: usize,
+}
+
+impl Default for WordVectorConfig {
+    fn default() -> Self {
+        Self {
+            dimensions: 64,
+            max_vocabulary: 5000,
+            min_count: 2,
+            context_smoothing: 0.75,
+            power_iterations: 3,
+            oversampling: 8,
+        }
+    }
+}
+
+/// Builds word vectors from a Sigel's semantic networks: a positive PMI matrix over the
+/// co-occurrence counts, reduced with a seeded randomized truncated SVD.
+pub struct WordVectorTrainer {
+    config: WordVectorConfig,
+}
+
+impl WordVectorTrainer {
+    pub fn new(config: WordVectorConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &WordVectorConfig {
+        &self.config
+    }
+
+    /// Rebuild the Sigel's word vectors, returning how many words got one.
+    pub fn train(&self, sigel: &mut Sigel) -> usize {
+        let vectors = self.build(sigel);
+        let count = vectors.len();
+        sigel.memory.semantic_knowledge.word_vectors = vectors;
+        count
+    }
+
+    pub fn build(&self, sigel: &Sigel) -> WordVectors {
+        let (words, matrix) = self.ppmi_matrix(&sigel.consciousness.pattern_recognition.semantic_networks);
+        if words.len() < 2 {
+            return WordVectors::default();
+        }
+
+        let rank = self.config.dimensions.clamp(1, words.len());
+        let embeddings = truncated_svd(&matrix, words.len(), rank, self.config.oversampling, self.config.power_iterations);
+
+        let mut vectors = WordVectors {
+            dimensions: rank,
+            ..WordVectors::default()
+        };
+        for (word, row) in words.into_iter().zip(embeddings) {
+            let norm = row.iter().map(|x| x * x).sum::<f64>().sqrt();
+            // Words whose contexts were all filtered out carry no information
+            if norm <= f64::EPSILON {
+                continue;
+            }
+            vectors.words.push(word);
+            vectors.components.extend(row.iter().map(|x| (x / norm * QUANTIZATION_SCALE as f64).round() as i8));
+        }
+        vectors
+    }
+
+    /// Sparse PPMI rows for the selected vocabulary, with columns sorted by index.
+    fn ppmi_matrix(&self, networks: &HashMap<String, Vec<String>>) -> (Vec<String>, Vec<Vec<(usize, f64)>>) {
+        let mut totals: Vec<(&str, usize)> = networks.iter()
+            .filter(|(word, neighbors)| !word.is_empty() && neighbors.len() >= self.config.min_count)
+            .map(|(word, neighbors)| (word.as_str(), neighbors.len()))
+            .collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        totals.truncate(self.config.max_vocabulary);
+
+        let words: Vec<String> = totals.iter().map(|(word, _)| word.to_string()).collect();
+        let index: HashMap<&str, usize> = totals.iter().enumerate().map(|(idx, (word, _))| (*word, idx)).collect();
+
+        let counts: Vec<BTreeMap<usize, f64>> = words.iter()
+            .map(|word| {
+                let mut row = BTreeMap::new();
+                for neighbor in &networks[word] {
+                    if let Some(&col) = index.get(neighbor.as_str()) {
+                        *row.entry(col).or_insert(0.0) += 1.0;
+                    }
+                }
+                row
+            })
+            .collect();
+
+        let row_sums: Vec<f64> = counts.iter().map(|row| row.values().sum()).collect();
+        let mut col_sums = vec![0.0; words.len()];
+        for row in &counts {
+            for (&col, &count) in row {
+                col_sums[col] += count;
+            }
+        }
+        let total: f64 = row_sums.iter().sum();
+        let smoothed: Vec<f64> = col_sums.iter().map(|c| c.powf(self.config.context_smoothing)).collect();
+        let smoothed_total: f64 = smoothed.iter().sum();
+
+        let matrix = counts.into_iter()
+            .enumerate()
+            .map(|(row, cols)| {
+                cols.into_iter()
+                    .filter_map(|(col, count)| {
+                        let pmi = ((count / total) / ((row_sums[row] / total) * (smoothed[col] / smoothed_total))).ln();
+                        (pmi > 0.0).then_some((col, pmi))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        (words, matrix)
+    }
+}
+
+impl Default for WordVectorTrainer {
+    fn default() -> Self {
+        Self::new(WordVectorConfig::default())
+    }
+}
+
+/// Rows of `U * sqrt(S)` for the top `rank` singular vectors of a square sparse matrix,
+/// using a randomized range finder followed by an exact eigen-decomposition of the small
+/// projected problem.
+fn truncated_svd(matrix: &[Vec<(usize, f64)>], n: usize, rank: usize, oversampling: usize, power_iterations: usize) -> Vec<Vec<f64>> {
+    let width = (rank + oversampling).min(n);
+    let mut rng = StdRng::seed_from_u64(WORD_VECTOR_SEED);
+
+    // Columns of a random test matrix, pushed through A to sample its range
+    let omega: Vec<Vec<f64>> = (0..width)
+        .map(|_| (0..n).map(|_| rng.gen_range(-1.0..1.0)).collect())
+        .collect();
+    let mut q = orthonormalize(omega.iter().map(|col| multiply(matrix, col, n)).collect());
+    for _ in 0..power_iterations {
+        let z = orthonormalize(q.iter().map(|col| multiply_transpose(matrix, col, n)).collect());
+        q = orthonormalize(z.iter().map(|col| multiply(matrix, col, n)).collect());
+    }
+
+    // B = Q^T A, whose rows are A^T q for each column q
+    let b: Vec<Vec<f64>> = q.iter().map(|col| multiply_transpose(matrix, col, n)).collect();
+    let gram: Vec<Vec<f64>> = b.iter()
+        .map(|row_a| b.iter().map(|row_b| dot(row_a, row_b)).collect())
+        .collect();
+    let (eigenvalues, eigenvectors) = symmetric_eigen(gram);
+
+    let mut order: Vec<usize> = (0..eigenvalues.len()).collect();
+    order.sort_by(|&x, &y| eigenvalues[y].total_cmp(&eigenvalues[x]));
+    order.truncate(rank);
+
+    (0..n)
+        .map(|i| {
+            order.iter()
+                .map(|&t| {
+                    let u: f64 = q.iter().enumerate().map(|(c, col)| col[i] * eigenvectors[c][t]).sum();
+                    u * eigenvalues[t].max(0.0).powf(0.25)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn multiply(matrix: &[Vec<(usize, f64)>], vector: &[f64], n: usize) -> Vec<f64> {
+    let mut result = vec![0.0; n];
+    for (row, cols) in matrix.iter().enumerate() {
+        result[row] = cols.iter().map(|&(col, value)| value * vector[col]).sum();
+    }
+    result
+}
+
+fn multiply_transpose(matrix: &[Vec<(usize, f64)>], vector: &[f64], n: usize) -> Vec<f64> {
+    let mut result = vec![0.0; n];
+    for (row, cols) in matrix.iter().enumerate() {
+        for &(col, value) in cols {
+            result[col] += value * vector[row];
+        }
+    }
+    result
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Modified Gram-Schmidt; columns that collapse to zero are kept as zero vectors.
+fn orthonormalize(mut columns: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    for i in 0..columns.len() {
+        let (done, rest) = columns.split_at_mut(i);
+        let column = &mut rest[0];
+        for previous in done.iter() {
+            let projection = dot(column, previous);
+            for (x, p) in column.iter_mut().zip(previous) {
+                *x -= projection * p;
+            }
+        }
+        let norm = dot(column, column).sqrt();
+        let scale = if norm > 1e-10 { 1.0 / norm } else { 0.0 };
+        column.iter_mut().for_each(|x| *x *= scale);
+    }
+    columns
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a small symmetric matrix, by cyclic Jacobi rotations.
+fn symmetric_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut v: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n).flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        let scale: f64 = (0..n).map(|i| a[i][i] * a[i][i]).sum::<f64>().max(f64::MIN_POSITIVE);
+        if off_diagonal <= scale * 1e-24 {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() <= f64::MIN_POSITIVE {
+                    continue;
+                }
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (upper, lower) = a.split_at_mut(q);
+                for (apk, aqk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    let (x, y) = (*apk, *aqk);
+                    *apk = c * x - s * y;
+                    *aqk = s * x + c * y;
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+fn normalize_word(word: &str) -> String {
+    word.to_lowercase().trim_matches(|c: char| !c.is_alphabetic()).to_string()
+}
+
+mod hex_components {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(components: &[i8], serializer: S) -> Result<S::Ok, S::Error> {
+        let hex: String = components.iter().map(|&c| format!("{:02x}", c as u8)).collect();
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<i8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        if hex.len() % 2 != 0 {
+            return Err(serde::de::Error::custom("word vector components must be an even number of hex digits"));
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| {
+                let digits = hex.get(i..i + 2).ok_or_else(|| serde::de::Error::custom("invalid hex in word vector components"))?;
+                u8::from_str_radix(digits, 16)
+                    .map(|byte| byte as i8)
+                    .map_err(serde::de::Error::custom)
+            })
+            .collect()
+    }
+}